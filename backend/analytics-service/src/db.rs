@@ -1,16 +1,19 @@
 use mongodb::{Collection, Database};
 
-use crate::models::ExerciseTimeseries;
+use crate::models::{CatalogExercise, ExerciseTimeseries};
 
 #[derive(Clone)]
 pub struct Collections {
     pub exercise_timeseries: Collection<ExerciseTimeseries>,
+    /// Read model of program-service's exercise catalog
+    pub exercise_catalog: Collection<CatalogExercise>,
 }
 
 impl Collections {
     pub fn new(db: &Database) -> Self {
         Self {
             exercise_timeseries: db.collection("exercise_timeseries"),
+            exercise_catalog: db.collection("exercise_catalog"),
         }
     }
 }
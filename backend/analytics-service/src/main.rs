@@ -43,6 +43,14 @@ async fn main() {
         .await
         .expect("Failed to start RabbitMQ consumer");
 
+    // Start RabbitMQ consumer for exercise catalog events
+    services::rabbitmq::start_exercise_events_consumer(
+        &config.rabbitmq_url,
+        collections.clone(),
+    )
+    .await
+    .expect("Failed to start RabbitMQ consumer");
+
     let state = AppState { collections };
 
     // CORS configuration
@@ -39,6 +39,26 @@ pub struct AnalyticsMessage {
     pub started_program_id: String,
     pub exercise_id: String,
     pub set_number: i32,
+    /// Empty if the publisher hasn't synced the exercise yet
+    pub muscles: Vec<MuscleIntensity>,
+}
+
+/// Local read model of a program-service catalog exercise, kept current by exercise events
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CatalogExercise {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    pub muscles: Vec<MuscleIntensity>,
+    /// When the last event for this exercise was applied
+    pub synced_at: DateTime<Utc>,
+}
+
+/// Catalog entry as carried by program-service exercise events (only the fields used here)
+#[derive(Debug, Deserialize)]
+pub struct ExerciseSnapshot {
+    pub id: String,
+    pub name: String,
     pub muscles: Vec<MuscleIntensity>,
 }
 
@@ -1,4 +1,5 @@
 mod analytics;
+pub mod exercise_catalog;
 pub mod rabbitmq;
 
 pub use analytics::*;
@@ -1,16 +1,34 @@
+use std::time::Duration;
+
+use bson::doc;
 use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use lapin::{
-    options::{BasicAckOptions, BasicConsumeOptions, QueueDeclareOptions},
+    options::{
+        BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicPublishOptions,
+        ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
+    },
     types::FieldTable,
-    Connection, ConnectionProperties,
+    BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
 };
+use serde::Deserialize;
 
 use crate::db::Collections;
 use crate::error::AppError;
-use crate::models::{AnalyticsMessage, ExerciseTimeseries, TimeseriesMetadata};
+use crate::models::{AnalyticsMessage, ExerciseSnapshot, ExerciseTimeseries, TimeseriesMetadata};
+use crate::services::exercise_catalog;
 
 pub const ANALYTICS_QUEUE: &str = "analytics_queue";
+pub const EXERCISE_EVENTS_EXCHANGE: &str = "exercise_events";
+pub const EXERCISE_EVENTS_QUEUE: &str = "analytics_service_exercise_events";
+/// Routing key program-service answers with a CatalogSynced event
+const CATALOG_RESYNC_ROUTING_KEY: &str = "catalog.resync_requested";
+const CATALOG_RESYNC_QUEUE: &str = "program_service_catalog_resync_requests";
+const SERVICE_NAME: &str = "analytics-service";
+/// Wait before requeueing an event whose handler failed
+const REQUEUE_DELAY_SECS: u64 = 1;
+/// How long a measurement waits for its exercise to reach the catalog read model
+const CATALOG_SYNC_WAIT_SECS: i64 = 300;
 
 /// Start the RabbitMQ consumer
 pub async fn start_consumer(rabbitmq_url: &str, collections: Collections) -> Result<(), AppError> {
@@ -55,42 +73,308 @@ pub async fn start_consumer(rabbitmq_url: &str, collections: Collections) -> Res
         while let Some(delivery_result) = consumer.next().await {
             match delivery_result {
                 Ok(delivery) => {
-                    // Parse the message
-                    match serde_json::from_slice::<AnalyticsMessage>(&delivery.data) {
-                        Ok(message) => {
-                            // Convert to timeseries document
-                            let timestamp = DateTime::parse_from_rfc3339(&message.timestamp)
-                                .map(|dt| dt.with_timezone(&Utc))
-                                .unwrap_or_else(|_| Utc::now());
-
-                            let doc = ExerciseTimeseries {
-                                timestamp,
-                                meta_field: TimeseriesMetadata {
-                                    user_id: message.user_id,
-                                    started_program_id: message.started_program_id,
-                                    exercise_id: message.exercise_id,
-                                    set_number: message.set_number,
-                                },
-                                muscles: message.muscles,
-                            };
-
-                            // Insert into timeseries collection
-                            if let Err(e) = collections.exercise_timeseries.insert_one(&doc).await {
-                                eprintln!("Failed to insert timeseries document: {}", e);
-                            } else {
-                                println!(
-                                    "Inserted timeseries document for exercise: {}",
-                                    doc.meta_field.exercise_id
-                                );
-                            }
-                        }
+                    let handled = match serde_json::from_slice::<AnalyticsMessage>(&delivery.data) {
+                        Ok(message) => handle_analytics_message(&collections, message).await,
                         Err(e) => {
+                            // Unreadable messages would fail again; drop them
                             eprintln!("Failed to parse analytics message: {}", e);
+                            Ok(())
+                        }
+                    };
+
+                    if let Err(e) = handled {
+                        eprintln!("Failed to handle analytics message, requeueing: {:?}", e);
+                        tokio::time::sleep(Duration::from_secs(REQUEUE_DELAY_SECS)).await;
+                        if let Err(e) = delivery
+                            .nack(BasicNackOptions {
+                                requeue: true,
+                                ..BasicNackOptions::default()
+                            })
+                            .await
+                        {
+                            eprintln!("Failed to nack message: {}", e);
                         }
+                    } else if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                        eprintln!("Failed to ack message: {}", e);
                     }
+                }
+                Err(e) => {
+                    eprintln!("Error receiving message: {}", e);
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Store a completed set as a timeseries measurement. A message for an exercise the
+/// catalog read model hasn't synced yet is held (requeued) rather than stored without
+/// muscles; once it is older than CATALOG_SYNC_WAIT_SECS the exercise is assumed gone
+/// and the message is dropped.
+async fn handle_analytics_message(
+    collections: &Collections,
+    message: AnalyticsMessage,
+) -> Result<(), AppError> {
+    let sent_at = DateTime::parse_from_rfc3339(&message.timestamp)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok();
+
+    // Older publishers or an exercise the publisher hasn't synced: use the local catalog
+    let muscles = if message.muscles.is_empty() {
+        match exercise_catalog::get_muscles(collections, &message.exercise_id).await? {
+            Some(muscles) => muscles,
+            None => {
+                let waiting = sent_at.is_some_and(|t| {
+                    Utc::now() - t < chrono::Duration::seconds(CATALOG_SYNC_WAIT_SECS)
+                });
+                if !waiting {
+                    eprintln!(
+                        "Dropping analytics message: exercise {} never reached the catalog",
+                        message.exercise_id
+                    );
+                    return Ok(());
+                }
+                return Err(AppError::InternalServerError(format!(
+                    "Exercise {} is not in the catalog yet",
+                    message.exercise_id
+                )));
+            }
+        }
+    } else {
+        message.muscles
+    };
+
+    let doc = ExerciseTimeseries {
+        timestamp: sent_at.unwrap_or_else(Utc::now),
+        meta_field: TimeseriesMetadata {
+            user_id: message.user_id,
+            started_program_id: message.started_program_id,
+            exercise_id: message.exercise_id,
+            set_number: message.set_number,
+        },
+        muscles,
+    };
+
+    // Insert into timeseries collection
+    collections.exercise_timeseries.insert_one(&doc).await?;
+    println!(
+        "Inserted timeseries document for exercise: {}",
+        doc.meta_field.exercise_id
+    );
+
+    Ok(())
+}
+
+/// Exercise catalog events published by program-service that analytics reacts to
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum ExerciseEvent {
+    ExerciseCreated { exercise: ExerciseSnapshot },
+    ExerciseUpdated { exercise: ExerciseSnapshot },
+    ExerciseDeleted { exercise_id: String },
+    ExerciseMerged { source_id: String, target_id: String },
+    CatalogSynced { exercises: Vec<ExerciseSnapshot> },
+}
+
+/// Keep the catalog read model current, and re-tag timeseries measurements of a merged
+/// exercise with the exercise it was merged into. Only metaField is updated, which
+/// time-series collections allow. Every event is safe to apply twice.
+async fn handle_exercise_event(
+    collections: &Collections,
+    event: ExerciseEvent,
+) -> Result<(), AppError> {
+    match event {
+        ExerciseEvent::ExerciseCreated { exercise }
+        | ExerciseEvent::ExerciseUpdated { exercise } => {
+            exercise_catalog::upsert_exercise(collections, exercise).await?;
+        }
+        ExerciseEvent::ExerciseDeleted { exercise_id } => {
+            exercise_catalog::delete_exercise(collections, &exercise_id).await?;
+        }
+        ExerciseEvent::CatalogSynced { exercises } => {
+            let count = exercises.len();
+            exercise_catalog::replace_catalog(collections, exercises).await?;
+            println!("Synced exercise catalog read model ({} exercises)", count);
+        }
+        ExerciseEvent::ExerciseMerged {
+            source_id,
+            target_id,
+        } => {
+            let result = collections
+                .exercise_timeseries
+                .update_many(
+                    doc! { "metaField.exerciseId": &source_id },
+                    doc! { "$set": { "metaField.exerciseId": &target_id } },
+                )
+                .await?;
+
+            exercise_catalog::delete_exercise(collections, &source_id).await?;
+
+            println!(
+                "Moved {} timeseries measurement(s) from {} to {}",
+                result.modified_count, source_id, target_id
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Ask program-service to publish the whole catalog when the read model is empty,
+/// e.g. on first start; the answer arrives as a CatalogSynced event
+async fn request_catalog_if_empty(
+    channel: &Channel,
+    collections: &Collections,
+) -> Result<(), AppError> {
+    if collections.exercise_catalog.estimated_document_count().await? > 0 {
+        return Ok(());
+    }
+
+    // Declared here too so a request sent before program-service's first start isn't lost
+    channel
+        .queue_declare(
+            CATALOG_RESYNC_QUEUE,
+            QueueDeclareOptions {
+                durable: true,
+                ..QueueDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to declare queue: {}", e)))?;
+    channel
+        .queue_bind(
+            CATALOG_RESYNC_QUEUE,
+            EXERCISE_EVENTS_EXCHANGE,
+            CATALOG_RESYNC_ROUTING_KEY,
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to bind queue: {}", e)))?;
 
-                    // Acknowledge the message
-                    if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+    let payload = serde_json::to_vec(&serde_json::json!({ "requested_by": SERVICE_NAME }))
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to serialize resync request: {}", e))
+        })?;
+
+    channel
+        .basic_publish(
+            EXERCISE_EVENTS_EXCHANGE,
+            CATALOG_RESYNC_ROUTING_KEY,
+            BasicPublishOptions::default(),
+            &payload,
+            BasicProperties::default()
+                .with_content_type("application/json".into())
+                .with_delivery_mode(2), // Persistent
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to publish message: {}", e)))?;
+
+    println!("Exercise catalog read model is empty, requested a resync");
+
+    Ok(())
+}
+
+/// Start the RabbitMQ consumer for exercise catalog events
+pub async fn start_exercise_events_consumer(
+    rabbitmq_url: &str,
+    collections: Collections,
+) -> Result<(), AppError> {
+    let connection = Connection::connect(rabbitmq_url, ConnectionProperties::default())
+        .await
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to connect to RabbitMQ: {}", e))
+        })?;
+
+    let channel = connection.create_channel().await.map_err(|e| {
+        AppError::InternalServerError(format!("Failed to create RabbitMQ channel: {}", e))
+    })?;
+
+    channel
+        .exchange_declare(
+            EXERCISE_EVENTS_EXCHANGE,
+            ExchangeKind::Topic,
+            ExchangeDeclareOptions {
+                durable: true,
+                ..ExchangeDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to declare exchange: {}", e)))?;
+
+    channel
+        .queue_declare(
+            EXERCISE_EVENTS_QUEUE,
+            QueueDeclareOptions {
+                durable: true,
+                ..QueueDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to declare queue: {}", e)))?;
+
+    channel
+        .queue_bind(
+            EXERCISE_EVENTS_QUEUE,
+            EXERCISE_EVENTS_EXCHANGE,
+            "exercise.*",
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to bind queue: {}", e)))?;
+
+    println!(
+        "RabbitMQ consumer connected, listening on queue '{}'",
+        EXERCISE_EVENTS_QUEUE
+    );
+
+    let mut consumer = channel
+        .basic_consume(
+            EXERCISE_EVENTS_QUEUE,
+            "analytics_service_exercise_events_consumer",
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create consumer: {}", e)))?;
+
+    request_catalog_if_empty(&channel, &collections).await?;
+
+    tokio::spawn(async move {
+        // Keep the connection alive for as long as the consumer runs
+        let _connection = connection;
+
+        while let Some(delivery_result) = consumer.next().await {
+            match delivery_result {
+                Ok(delivery) => {
+                    let handled = match serde_json::from_slice::<ExerciseEvent>(&delivery.data) {
+                        Ok(event) => handle_exercise_event(&collections, event).await,
+                        Err(e) => {
+                            // Unreadable messages would fail again; drop them
+                            eprintln!("Failed to parse exercise event: {}", e);
+                            Ok(())
+                        }
+                    };
+
+                    if let Err(e) = handled {
+                        eprintln!("Failed to handle exercise event, requeueing: {:?}", e);
+                        // Back off so a database outage doesn't turn into a redelivery loop
+                        tokio::time::sleep(Duration::from_secs(REQUEUE_DELAY_SECS)).await;
+                        if let Err(e) = delivery
+                            .nack(BasicNackOptions {
+                                requeue: true,
+                                ..BasicNackOptions::default()
+                            })
+                            .await
+                        {
+                            eprintln!("Failed to nack message: {}", e);
+                        }
+                    } else if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
                         eprintln!("Failed to ack message: {}", e);
                     }
                 }
@@ -6,6 +6,8 @@ pub struct Config {
     pub workout_service_url: String,
     pub started_program_service_url: String,
     pub analytics_service_url: String,
+    /// Expose program-service's simulated checkout completion (development only)
+    pub payment_local_checkout: bool,
 }
 
 impl Config {
@@ -16,6 +18,7 @@ impl Config {
             workout_service_url: env::var("WORKOUT_SERVICE_URL").unwrap_or_else(|_| "http://192.168.1.9:3001".to_string()),
             started_program_service_url: env::var("STARTED_PROGRAM_SERVICE_URL").unwrap_or_else(|_| "http://192.168.1.9:3003".to_string()),
             analytics_service_url: env::var("ANALYTICS_SERVICE_URL").unwrap_or_else(|_| "http://192.168.1.9:3004".to_string()),
+            payment_local_checkout: env::var("PAYMENT_LOCAL_CHECKOUT").is_ok_and(|v| v == "true"),
         }
     }
 }
@@ -1,4 +1,5 @@
 use axum::{
+    http::header,
     routing::{delete, get, patch, post, put},
     Router,
 };
@@ -14,9 +15,10 @@ mod state;
 
 use config::Config;
 use proxy::{
-    proxy_to_analytics_protected, proxy_to_backend_protected, proxy_to_backend_public,
+    proxy_to_analytics_protected, proxy_to_backend_admin_only, proxy_to_backend_protected,
+    proxy_to_backend_public,
     proxy_to_started_program_protected, proxy_to_workout_admin_only, proxy_to_workout_coach_only,
-    proxy_to_workout_protected,
+    proxy_to_workout_protected, proxy_to_workout_public,
 };
 use state::AppState;
 
@@ -43,7 +45,9 @@ async fn main() {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        // Program revisions for optimistic concurrency (If-Match)
+        .expose_headers([header::ETAG]);
 
     // Public auth routes (no JWT validation) -> Backend
     let public_routes = Router::new()
@@ -56,8 +60,18 @@ async fn main() {
         .route("/resend-verification", post(proxy_to_backend_public));
 
     // Protected backend routes -> Backend
-    let protected_backend_routes =
-        Router::new().route("/protected", get(proxy_to_backend_protected));
+    // Personal access token management requires a JWT session
+    let protected_backend_routes = Router::new()
+        .route("/protected", get(proxy_to_backend_protected))
+        .route(
+            "/tokens",
+            get(proxy_to_backend_protected).post(proxy_to_backend_protected),
+        )
+        .route("/tokens/{token_id}", delete(proxy_to_backend_protected))
+        // User account management - Admin only
+        .route("/users/{user_id}", delete(proxy_to_backend_admin_only))
+        .route("/users/{user_id}/role", patch(proxy_to_backend_admin_only))
+        .route("/users/{user_id}/disable", post(proxy_to_backend_admin_only));
 
     // Protected workout service routes -> Workout Service
     // Role-based access:
@@ -68,6 +82,14 @@ async fn main() {
         // Program search routes (any authenticated user)
         .route("/programs/search/public", get(proxy_to_workout_protected))
         .route("/programs/search/mine", get(proxy_to_workout_coach_only)) // Coach sees their own programs
+        .route(
+            "/programs/search/public/facets",
+            get(proxy_to_workout_protected),
+        )
+        .route(
+            "/programs/search/mine/facets",
+            get(proxy_to_workout_coach_only),
+        )
         // Program CRUD
         .route(
             "/programs",
@@ -82,11 +104,55 @@ async fn main() {
             "/programs/{program_id}/image",
             post(proxy_to_workout_coach_only), // Coach only
         )
+        // Export - any user (program-service checks the program is public or owned)
+        .route(
+            "/programs/{program_id}/export",
+            get(proxy_to_workout_protected),
+        )
+        // Import creates a draft - Coach only
+        .route("/programs/import", post(proxy_to_workout_coach_only))
+        // Fork - any user (program-service checks the source is public or owned)
+        .route(
+            "/programs/{program_id}/fork",
+            post(proxy_to_workout_protected),
+        )
         // Next workout (for started-program-service) - any user
         .route(
             "/programs/{program_id}/next-workout",
             get(proxy_to_workout_protected),
         )
+        // Purchases - any user
+        .route(
+            "/programs/{program_id}/purchase",
+            post(proxy_to_workout_protected),
+        )
+        .route(
+            "/programs/{program_id}/entitlement",
+            get(proxy_to_workout_protected),
+        )
+        .route("/entitlements/mine", get(proxy_to_workout_protected))
+        .route("/payments/mine", get(proxy_to_workout_protected))
+        // Stored program and exercise images - public so image components can load them without a token
+        .route("/files/{*key}", get(proxy_to_workout_public))
+        // Payment provider webhooks - no JWT, verified by program-service
+        .route("/payments/webhook", post(proxy_to_workout_public))
+        // Reviews - any user reviews, the program's coach replies, admins moderate
+        .route(
+            "/programs/{program_id}/reviews",
+            get(proxy_to_workout_protected),
+        )
+        .route(
+            "/programs/{program_id}/reviews/mine",
+            put(proxy_to_workout_protected).delete(proxy_to_workout_protected),
+        )
+        .route(
+            "/programs/{program_id}/reviews/{review_id}/reply",
+            put(proxy_to_workout_coach_only),
+        )
+        .route(
+            "/reviews/{review_id}",
+            patch(proxy_to_workout_admin_only).delete(proxy_to_workout_admin_only),
+        )
         // Workout exercises - Coach only for modifications
         .route(
             "/programs/{program_id}/workouts",
@@ -96,6 +162,36 @@ async fn main() {
             "/programs/{program_id}/workout-exercises",
             put(proxy_to_workout_coach_only).delete(proxy_to_workout_coach_only),
         )
+        // Week and workout layout - Coach only
+        .route("/programs/{program_id}/weeks", post(proxy_to_workout_coach_only))
+        .route(
+            "/programs/{program_id}/weeks/{week}",
+            delete(proxy_to_workout_coach_only),
+        )
+        .route(
+            "/programs/{program_id}/weeks/{week}/copy",
+            post(proxy_to_workout_coach_only),
+        )
+        .route(
+            "/programs/{program_id}/weeks/{week}/repeat",
+            post(proxy_to_workout_coach_only),
+        )
+        .route(
+            "/programs/{program_id}/weeks/{week}/order",
+            put(proxy_to_workout_coach_only),
+        )
+        .route(
+            "/programs/{program_id}/weeks/{week}/progression",
+            post(proxy_to_workout_coach_only),
+        )
+        .route(
+            "/programs/{program_id}/workouts/{workout_number}/copy",
+            post(proxy_to_workout_coach_only),
+        )
+        .route(
+            "/programs/{program_id}/workouts/{workout_number}/move",
+            post(proxy_to_workout_coach_only),
+        )
         // Exercise catalog
         // GET: Any authenticated user
         // POST/PATCH/DELETE: Admin only
@@ -103,10 +199,45 @@ async fn main() {
             "/exercises",
             get(proxy_to_workout_protected).post(proxy_to_workout_admin_only),
         )
+        // Catalog import/export and read model resync - Admin only
+        .route("/exercises/export", get(proxy_to_workout_admin_only))
+        .route("/exercises/import", post(proxy_to_workout_admin_only))
+        .route("/exercises/resync", post(proxy_to_workout_admin_only))
         .route("/exercises/{exercise_id}", get(proxy_to_workout_protected))
         .route(
             "/exercises/{exercise_id}",
             patch(proxy_to_workout_admin_only).delete(proxy_to_workout_admin_only),
+        )
+        .route(
+            "/exercises/{exercise_id}/image",
+            post(proxy_to_workout_admin_only), // Admin only
+        )
+        // Substitutions - GET: any authenticated user, POST/DELETE: Admin only
+        .route(
+            "/exercises/{exercise_id}/alternatives",
+            get(proxy_to_workout_protected),
+        )
+        .route(
+            "/exercises/{exercise_id}/substitutions",
+            get(proxy_to_workout_protected).post(proxy_to_workout_admin_only),
+        )
+        .route(
+            "/exercises/{exercise_id}/substitutions/{substitute_id}",
+            delete(proxy_to_workout_admin_only),
+        )
+        // Muscle registry - any authenticated user
+        .route("/muscles", get(proxy_to_workout_protected))
+        // Tag vocabulary
+        // GET: Any authenticated user
+        // POST/PATCH/DELETE: Admin only
+        .route(
+            "/tags",
+            get(proxy_to_workout_protected).post(proxy_to_workout_admin_only),
+        )
+        .route("/tags/{tag_id}", get(proxy_to_workout_protected))
+        .route(
+            "/tags/{tag_id}",
+            patch(proxy_to_workout_admin_only).delete(proxy_to_workout_admin_only),
         );
 
     // Protected started program service routes -> Started Program Service
@@ -163,10 +294,22 @@ async fn main() {
             get(proxy_to_analytics_protected),
         );
 
+    // Simulated checkout completion lets any buyer mark a payment paid, so it is only
+    // routed when explicitly enabled for development
+    let local_payment_routes = if config.payment_local_checkout {
+        Router::new().route(
+            "/payments/local/{reference}/complete",
+            post(proxy_to_workout_protected),
+        )
+    } else {
+        Router::new()
+    };
+
     let app = Router::new()
         .merge(public_routes)
         .merge(protected_backend_routes)
         .merge(workout_routes)
+        .merge(local_payment_routes)
         .merge(started_program_routes)
         .merge(analytics_routes)
         .layer(cors)
@@ -3,6 +3,10 @@ use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
+use crate::state::AppState;
+
+/// Personal access tokens issued by the auth service start with this prefix
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "rpz_";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
@@ -12,18 +16,39 @@ pub struct Claims {
     pub role: String,
 }
 
-pub fn extract_and_validate_token(
-    headers: &HeaderMap,
-    jwt_secret: &str,
-) -> Result<Claims, AppError> {
+/// Authenticated caller, resolved from either a JWT or a personal access token
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub sub: String,
+    pub role: String,
+    /// None for JWT sessions (full access), Some for personal access tokens
+    pub scopes: Option<Vec<String>>,
+}
+
+/// Response from the auth service token introspection endpoint
+#[derive(Debug, Deserialize)]
+struct IntrospectResponse {
+    sub: String,
+    role: String,
+    scopes: Vec<String>,
+}
+
+fn extract_bearer_token(headers: &HeaderMap) -> Result<&str, AppError> {
     let auth_header = headers
         .get("authorization")
         .and_then(|v| v.to_str().ok())
         .ok_or_else(|| AppError::Unauthorized("Missing authorization header".to_string()))?;
 
-    let token = auth_header
+    auth_header
         .strip_prefix("Bearer ")
-        .ok_or_else(|| AppError::Unauthorized("Invalid authorization format".to_string()))?;
+        .ok_or_else(|| AppError::Unauthorized("Invalid authorization format".to_string()))
+}
+
+pub fn extract_and_validate_token(
+    headers: &HeaderMap,
+    jwt_secret: &str,
+) -> Result<Claims, AppError> {
+    let token = extract_bearer_token(headers)?;
 
     let mut validation = Validation::new(Algorithm::HS256);
     validation.set_audience(&["my-app"]);
@@ -37,3 +62,49 @@ pub fn extract_and_validate_token(
 
     Ok(token_data.claims)
 }
+
+/// Authenticate a request using either a JWT or a personal access token.
+/// Personal access tokens are opaque, so they are validated by the auth service.
+pub async fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<AuthUser, AppError> {
+    let token = extract_bearer_token(headers)?;
+
+    if !token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX) {
+        let claims = extract_and_validate_token(headers, &state.jwt_secret)?;
+        return Ok(AuthUser {
+            sub: claims.sub,
+            role: claims.role,
+            scopes: None,
+        });
+    }
+
+    let response = state
+        .http_client
+        .post(format!("{}/tokens/introspect", state.backend_url))
+        .json(&serde_json::json!({ "token": token }))
+        .send()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Token introspection failed: {}", e)))?;
+
+    if response.status().as_u16() == 401 {
+        return Err(AppError::Unauthorized(
+            "Invalid or expired access token".to_string(),
+        ));
+    }
+
+    if !response.status().is_success() {
+        return Err(AppError::InternalServerError(format!(
+            "Token introspection failed: {}",
+            response.status()
+        )));
+    }
+
+    let identity: IntrospectResponse = response.json().await.map_err(|e| {
+        AppError::InternalServerError(format!("Failed to parse introspection response: {}", e))
+    })?;
+
+    Ok(AuthUser {
+        sub: identity.sub,
+        role: identity.role,
+        scopes: Some(identity.scopes),
+    })
+}
@@ -1,3 +1 @@
 pub mod auth;
-
-pub use auth::Claims;
@@ -8,7 +8,7 @@ use bytes::Bytes;
 use reqwest::Client;
 
 use crate::error::AppError;
-use crate::middleware::auth::{extract_and_validate_token, Claims};
+use crate::middleware::auth::{authenticate, AuthUser};
 use crate::state::AppState;
 
 /// Roles allowed for different operations
@@ -36,14 +36,49 @@ impl RequiredRole {
     }
 }
 
+/// Scopes a personal access token needs for each service
+pub const SCOPE_PROGRAMS_READ: &str = "programs:read";
+pub const SCOPE_WORKOUTS_READ: &str = "workouts:read";
+pub const SCOPE_ANALYTICS_READ: &str = "analytics:read";
+
 /// Validate that the user has the required role
-fn validate_role(claims: &Claims, required: RequiredRole) -> Result<(), AppError> {
-    if required.is_satisfied_by(&claims.role) {
+fn validate_role(user: &AuthUser, required: RequiredRole) -> Result<(), AppError> {
+    if required.is_satisfied_by(&user.role) {
         Ok(())
     } else {
         Err(AppError::Forbidden(format!(
             "Insufficient permissions. Required role: {:?}, your role: {}",
-            required, claims.role
+            required, user.role
+        )))
+    }
+}
+
+/// Validate that a personal access token grants the required scope.
+/// JWT sessions are not scoped. Routes without a scope only accept JWT sessions,
+/// and personal access tokens are read-only.
+fn validate_scope(user: &AuthUser, method: &Method, scope: Option<&str>) -> Result<(), AppError> {
+    let Some(scopes) = &user.scopes else {
+        return Ok(());
+    };
+
+    let Some(scope) = scope else {
+        return Err(AppError::Forbidden(
+            "Personal access tokens cannot be used for this route".to_string(),
+        ));
+    };
+
+    if !matches!(*method, Method::GET | Method::HEAD) {
+        return Err(AppError::Forbidden(
+            "Personal access tokens are read-only".to_string(),
+        ));
+    }
+
+    if scopes.iter().any(|s| s == scope) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!(
+            "Insufficient scope. Required scope: {}",
+            scope
         )))
     }
 }
@@ -138,8 +173,45 @@ pub async fn proxy_to_backend_protected(
     let uri = request.uri().clone();
     let headers = request.headers().clone();
 
-    // Validate JWT
-    let claims = extract_and_validate_token(&headers, &state.jwt_secret)?;
+    // Validate JWT or personal access token
+    let user = authenticate(&state, &headers).await?;
+
+    // Token management and account routes require a JWT session
+    validate_scope(&user, &method, None)?;
+
+    let body = axum::body::to_bytes(request.into_body(), usize::MAX)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to read body: {}", e)))?;
+
+    let target_url = build_target_url(&state.backend_url, &uri);
+    proxy_request(
+        &state.http_client,
+        method,
+        &target_url,
+        &headers,
+        body,
+        Some((&user.sub, &user.role)),
+    )
+    .await
+}
+
+/// Proxy to backend for admin-only operations (manage user accounts)
+pub async fn proxy_to_backend_admin_only(
+    State(state): State<AppState>,
+    request: Request,
+) -> Result<impl IntoResponse, AppError> {
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    let headers = request.headers().clone();
+
+    // Validate JWT or personal access token
+    let user = authenticate(&state, &headers).await?;
+
+    // Account management requires a JWT session
+    validate_scope(&user, &method, None)?;
+
+    // Validate role - only admin can change roles, disable or delete users
+    validate_role(&user, RequiredRole::Admin)?;
 
     let body = axum::body::to_bytes(request.into_body(), usize::MAX)
         .await
@@ -152,7 +224,7 @@ pub async fn proxy_to_backend_protected(
         &target_url,
         &headers,
         body,
-        Some((&claims.sub, &claims.role)),
+        Some((&user.sub, &user.role)),
     )
     .await
 }
@@ -166,8 +238,11 @@ pub async fn proxy_to_workout_protected(
     let uri = request.uri().clone();
     let headers = request.headers().clone();
 
-    // Validate JWT
-    let claims = extract_and_validate_token(&headers, &state.jwt_secret)?;
+    // Validate JWT or personal access token
+    let user = authenticate(&state, &headers).await?;
+
+    // Personal access tokens need the programs:read scope
+    validate_scope(&user, &method, Some(SCOPE_PROGRAMS_READ))?;
 
     let body = axum::body::to_bytes(request.into_body(), usize::MAX)
         .await
@@ -180,11 +255,28 @@ pub async fn proxy_to_workout_protected(
         &target_url,
         &headers,
         body,
-        Some((&claims.sub, &claims.role)),
+        Some((&user.sub, &user.role)),
     )
     .await
 }
 
+/// Proxy to workout service without authentication (payment provider webhooks)
+pub async fn proxy_to_workout_public(
+    State(state): State<AppState>,
+    request: Request,
+) -> Result<impl IntoResponse, AppError> {
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    let headers = request.headers().clone();
+
+    let body = axum::body::to_bytes(request.into_body(), usize::MAX)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to read body: {}", e)))?;
+
+    let target_url = build_target_url(&state.workout_service_url, &uri);
+    proxy_request(&state.http_client, method, &target_url, &headers, body, None).await
+}
+
 /// Proxy to workout service for coach-only operations (create/edit/delete programs)
 pub async fn proxy_to_workout_coach_only(
     State(state): State<AppState>,
@@ -194,11 +286,14 @@ pub async fn proxy_to_workout_coach_only(
     let uri = request.uri().clone();
     let headers = request.headers().clone();
 
-    // Validate JWT
-    let claims = extract_and_validate_token(&headers, &state.jwt_secret)?;
+    // Validate JWT or personal access token
+    let user = authenticate(&state, &headers).await?;
+
+    // Personal access tokens need the programs:read scope
+    validate_scope(&user, &method, Some(SCOPE_PROGRAMS_READ))?;
 
     // Validate role - only coach or admin can create/edit/delete programs
-    validate_role(&claims, RequiredRole::Coach)?;
+    validate_role(&user, RequiredRole::Coach)?;
 
     let body = axum::body::to_bytes(request.into_body(), usize::MAX)
         .await
@@ -211,7 +306,7 @@ pub async fn proxy_to_workout_coach_only(
         &target_url,
         &headers,
         body,
-        Some((&claims.sub, &claims.role)),
+        Some((&user.sub, &user.role)),
     )
     .await
 }
@@ -225,11 +320,14 @@ pub async fn proxy_to_workout_admin_only(
     let uri = request.uri().clone();
     let headers = request.headers().clone();
 
-    // Validate JWT
-    let claims = extract_and_validate_token(&headers, &state.jwt_secret)?;
+    // Validate JWT or personal access token
+    let user = authenticate(&state, &headers).await?;
+
+    // Personal access tokens need the programs:read scope
+    validate_scope(&user, &method, Some(SCOPE_PROGRAMS_READ))?;
 
     // Validate role - only admin can create/edit/delete exercises
-    validate_role(&claims, RequiredRole::Admin)?;
+    validate_role(&user, RequiredRole::Admin)?;
 
     let body = axum::body::to_bytes(request.into_body(), usize::MAX)
         .await
@@ -242,7 +340,7 @@ pub async fn proxy_to_workout_admin_only(
         &target_url,
         &headers,
         body,
-        Some((&claims.sub, &claims.role)),
+        Some((&user.sub, &user.role)),
     )
     .await
 }
@@ -256,11 +354,14 @@ pub async fn proxy_to_started_program_protected(
     let uri = request.uri().clone();
     let headers = request.headers().clone();
 
-    // Validate JWT
-    let claims = extract_and_validate_token(&headers, &state.jwt_secret)?;
+    // Validate JWT or personal access token
+    let user = authenticate(&state, &headers).await?;
+
+    // Personal access tokens need the workouts:read scope
+    validate_scope(&user, &method, Some(SCOPE_WORKOUTS_READ))?;
 
     // Any authenticated user can use started programs
-    validate_role(&claims, RequiredRole::User)?;
+    validate_role(&user, RequiredRole::User)?;
 
     let body = axum::body::to_bytes(request.into_body(), usize::MAX)
         .await
@@ -273,7 +374,7 @@ pub async fn proxy_to_started_program_protected(
         &target_url,
         &headers,
         body,
-        Some((&claims.sub, &claims.role)),
+        Some((&user.sub, &user.role)),
     )
     .await
 }
@@ -287,11 +388,14 @@ pub async fn proxy_to_analytics_protected(
     let uri = request.uri().clone();
     let headers = request.headers().clone();
 
-    // Validate JWT
-    let claims = extract_and_validate_token(&headers, &state.jwt_secret)?;
+    // Validate JWT or personal access token
+    let user = authenticate(&state, &headers).await?;
+
+    // Personal access tokens need the analytics:read scope
+    validate_scope(&user, &method, Some(SCOPE_ANALYTICS_READ))?;
 
     // Any authenticated user can view analytics
-    validate_role(&claims, RequiredRole::User)?;
+    validate_role(&user, RequiredRole::User)?;
 
     let body = axum::body::to_bytes(request.into_body(), usize::MAX)
         .await
@@ -304,7 +408,7 @@ pub async fn proxy_to_analytics_protected(
         &target_url,
         &headers,
         body,
-        Some((&claims.sub, &claims.role)),
+        Some((&user.sub, &user.role)),
     )
     .await
 }
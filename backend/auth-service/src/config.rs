@@ -1,6 +1,9 @@
 pub const ACCESS_TOKEN_DURATION_HOURS: i64 = 1;
 pub const REFRESH_TOKEN_DURATION_DAYS: i64 = 7;
 pub const VERIFICATION_TOKEN_DURATION_HOURS: i64 = 24;
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "rpz_";
+pub const OUTBOX_POLL_INTERVAL_SECS: u64 = 2;
+pub const OUTBOX_BATCH_SIZE: i64 = 100;
 
 #[derive(Clone)]
 pub struct SmtpConfig {
@@ -21,4 +24,52 @@ impl SmtpConfig {
             app_url: std::env::var("APP_URL").unwrap_or_else(|_| "http://localhost:3000".to_string()),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Password rules applied at registration and Argon2 cost parameters used for hashing
+#[derive(Clone)]
+pub struct PasswordConfig {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+}
+
+/// Read a setting from the environment, panicking on values that don't parse
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    match std::env::var(key) {
+        Ok(v) => v
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid value for {}: {}", key, v)),
+        Err(_) => default,
+    }
+}
+
+impl PasswordConfig {
+    pub fn from_env() -> Self {
+        let config = Self {
+            min_length: env_or("PASSWORD_MIN_LENGTH", 8),
+            max_length: env_or("PASSWORD_MAX_LENGTH", 128),
+            require_uppercase: env_or("PASSWORD_REQUIRE_UPPERCASE", false),
+            require_lowercase: env_or("PASSWORD_REQUIRE_LOWERCASE", false),
+            require_digit: env_or("PASSWORD_REQUIRE_DIGIT", false),
+            require_symbol: env_or("PASSWORD_REQUIRE_SYMBOL", false),
+            argon2_memory_kib: env_or("ARGON2_MEMORY_KIB", argon2::Params::DEFAULT_M_COST),
+            argon2_iterations: env_or("ARGON2_ITERATIONS", argon2::Params::DEFAULT_T_COST),
+            argon2_parallelism: env_or("ARGON2_PARALLELISM", argon2::Params::DEFAULT_P_COST),
+        };
+        if config.min_length > config.max_length {
+            panic!(
+                "PASSWORD_MIN_LENGTH ({}) must not exceed PASSWORD_MAX_LENGTH ({})",
+                config.min_length, config.max_length
+            );
+        }
+        config
+    }
+}
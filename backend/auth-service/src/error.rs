@@ -10,11 +10,13 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
+#[derive(Debug)]
 pub enum AppError {
     Unauthorized(String),
     InternalServerError(String),
     BadRequest(String),
     Conflict(String),
+    NotFound(String),
 }
 
 impl IntoResponse for AppError {
@@ -24,6 +26,7 @@ impl IntoResponse for AppError {
             AppError::InternalServerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
         };
 
         let body = Json(ErrorResponse {
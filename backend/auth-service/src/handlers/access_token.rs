@@ -0,0 +1,65 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{
+    AccessTokenResponse, CreateAccessTokenRequest, CreateAccessTokenResponse, IntrospectRequest,
+    IntrospectResponse,
+};
+use crate::services;
+use crate::state::AppState;
+
+fn extract_username(headers: &HeaderMap) -> Result<String, AppError> {
+    headers
+        .get("X-User-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Unauthorized("Missing user info".to_string()))
+}
+
+/// POST /tokens - Create a personal access token (the plain token is only returned here)
+pub async fn create_access_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateAccessTokenRequest>,
+) -> Result<(StatusCode, Json<CreateAccessTokenResponse>), AppError> {
+    let username = extract_username(&headers)?;
+    let user_id = services::find_user_id_by_username(&state.db, &username).await?;
+    let response = services::create_personal_access_token(&state.db, user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// GET /tokens - List the caller's personal access tokens
+pub async fn list_access_tokens(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<AccessTokenResponse>>, AppError> {
+    let username = extract_username(&headers)?;
+    let user_id = services::find_user_id_by_username(&state.db, &username).await?;
+    let tokens = services::list_personal_access_tokens(&state.db, user_id).await?;
+    Ok(Json(tokens))
+}
+
+/// DELETE /tokens/:id - Revoke a personal access token
+pub async fn revoke_access_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(token_id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let username = extract_username(&headers)?;
+    let user_id = services::find_user_id_by_username(&state.db, &username).await?;
+    services::revoke_personal_access_token(&state.db, user_id, token_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /tokens/introspect - Internal endpoint used by the API gateway to validate a token
+pub async fn introspect_access_token(
+    State(state): State<AppState>,
+    Json(payload): Json<IntrospectRequest>,
+) -> Result<Json<IntrospectResponse>, AppError> {
+    let identity = services::introspect_personal_access_token(&state.db, &payload.token).await?;
+    Ok(Json(identity))
+}
@@ -1,21 +1,20 @@
-use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
-};
 use axum::{extract::State, http::{HeaderMap, StatusCode}, Json};
 use serde::{Deserialize, Serialize};
 
 use crate::config::ACCESS_TOKEN_DURATION_HOURS;
 use crate::error::AppError;
-use crate::models::{RefreshToken, Role, User};
+use crate::models::{RefreshToken, Role, User, UserEvent};
 use crate::services::{
-    create_refresh_token, create_verification_token, generate_access_token, send_verification_email,
+    create_refresh_token, create_verification_token, enqueue_event, generate_access_token,
+    hash_password, needs_rehash, send_verification_email, validate_password, verify_password,
 };
 use crate::state::AppState;
 
 #[derive(Deserialize)]
 pub struct LoginRequest {
-    pub username: String,
+    /// Username or email, matched case-insensitively
+    #[serde(alias = "username", alias = "email")]
+    pub identifier: String,
     pub password: String,
 }
 
@@ -50,21 +49,35 @@ fn is_valid_email(email: &str) -> bool {
     email.contains('@') && email.len() >= 3 && email.contains('.')
 }
 
+/// Map a failed user insert to a conflict when it hits one of the unique indexes
+fn map_user_insert_error(e: sqlx::Error) -> AppError {
+    if let Some(db_err) = e.as_database_error()
+        && db_err.is_unique_violation()
+    {
+        return match db_err.constraint() {
+            Some(c) if c.contains("email") => AppError::Conflict("Email already exists".to_string()),
+            _ => AppError::Conflict("Username already exists".to_string()),
+        };
+    }
+    AppError::InternalServerError(format!("Failed to create user: {}", e))
+}
+
 pub async fn register(
     State(state): State<AppState>,
     Json(payload): Json<RegisterRequest>,
 ) -> Result<Json<RegisterResponse>, AppError> {
+    // Usernames can't look like emails, since login accepts either
+    if payload.username.trim().is_empty() || payload.username.contains('@') {
+        return Err(AppError::BadRequest("Invalid username".to_string()));
+    }
+
     // Validate email format
     if !is_valid_email(&payload.email) {
         return Err(AppError::BadRequest("Invalid email format".to_string()));
     }
 
-    // Validate password length
-    if payload.password.len() < 8 {
-        return Err(AppError::BadRequest(
-            "Password must be at least 8 characters".to_string(),
-        ));
-    }
+    // Validate password against the configured policy
+    validate_password(&state.password_config, &payload.password, &payload.username)?;
 
     // Validate password confirmation
     if payload.password != payload.confirm_password {
@@ -77,38 +90,18 @@ pub async fn register(
         ));
     }
 
-    // Check if username already exists
-    let existing_username: Option<(i32,)> =
-        sqlx::query_as("SELECT id FROM users WHERE username = $1")
-            .bind(&payload.username)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
-
-    if existing_username.is_some() {
-        return Err(AppError::Conflict("Username already exists".to_string()));
-    }
+    // Hash password
+    let password_hash = hash_password(&state.password_config, &payload.password)?;
 
-    // Check if email already exists
-    let existing_email: Option<(i32,)> = sqlx::query_as("SELECT id FROM users WHERE email = $1")
-        .bind(&payload.email)
-        .fetch_optional(&state.db)
+    let mut tx = state
+        .db
+        .begin()
         .await
         .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
 
-    if existing_email.is_some() {
-        return Err(AppError::Conflict("Email already exists".to_string()));
-    }
+    // Create user (email_verified defaults to false); the case-insensitive unique indexes
+    // reject taken usernames and emails
 
-    // Hash password
-    let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-    let password_hash = argon2
-        .hash_password(payload.password.as_bytes(), &salt)
-        .map_err(|e| AppError::InternalServerError(format!("Password hashing failed: {}", e)))?
-        .to_string();
-
-    // Create user (email_verified defaults to false)
     let row: (i32,) = sqlx::query_as(
         "INSERT INTO users (username, email, password_hash, role, email_verified) VALUES ($1, $2, $3, $4, false) RETURNING id"
     )
@@ -116,9 +109,24 @@ pub async fn register(
     .bind(&payload.email)
     .bind(&password_hash)
     .bind(payload.role.to_string())
-    .fetch_one(&state.db)
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| AppError::InternalServerError(format!("Failed to create user: {}", e)))?;
+    .map_err(map_user_insert_error)?;
+
+    enqueue_event(
+        &mut tx,
+        &UserEvent::UserRegistered {
+            user_id: row.0,
+            username: payload.username.clone(),
+            email: payload.email.clone(),
+            role: payload.role.to_string(),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create user: {}", e)))?;
 
     // Create verification token and send email
     let verification_token = create_verification_token(&state.db, row.0).await?;
@@ -135,21 +143,46 @@ pub async fn login(
     State(state): State<AppState>,
     Json(payload): Json<LoginRequest>,
 ) -> Result<Json<AuthResponse>, AppError> {
+    // A legacy username containing '@' can equal another user's email: prefer the email
+    // match when the input looks like an email, otherwise the username match
+    let identifier = payload.identifier.trim();
     let user: User = sqlx::query_as(
-        "SELECT id, username, password_hash, role, email_verified FROM users WHERE username = $1",
+        r#"
+        SELECT id, username, password_hash, role, email_verified, disabled
+        FROM users
+        WHERE LOWER(username) = LOWER($1) OR LOWER(email) = LOWER($1)
+        ORDER BY CASE WHEN $2 THEN LOWER(email) = LOWER($1) ELSE LOWER(username) = LOWER($1) END DESC
+        LIMIT 1
+        "#,
     )
-    .bind(&payload.username)
+    .bind(identifier)
+    .bind(identifier.contains('@'))
     .fetch_optional(&state.db)
     .await
     .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
     .ok_or_else(|| AppError::Unauthorized("Invalid username or password".to_string()))?;
 
-    let parsed_hash = PasswordHash::new(&user.password_hash)
-        .map_err(|e| AppError::InternalServerError(format!("Hash parsing failed: {}", e)))?;
+    if !verify_password(&payload.password, &user.password_hash)? {
+        return Err(AppError::Unauthorized(
+            "Invalid username or password".to_string(),
+        ));
+    }
 
-    Argon2::default()
-        .verify_password(payload.password.as_bytes(), &parsed_hash)
-        .map_err(|_| AppError::Unauthorized("Invalid username or password".to_string()))?;
+    // Transparently upgrade hashes created with outdated Argon2 parameters (best effort)
+    if needs_rehash(&state.password_config, &user.password_hash)
+        && let Ok(new_hash) = hash_password(&state.password_config, &payload.password)
+    {
+        sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+            .bind(&new_hash)
+            .bind(user.id)
+            .execute(&state.db)
+            .await
+            .ok();
+    }
+
+    if user.disabled {
+        return Err(AppError::Unauthorized("Account is disabled".to_string()));
+    }
 
     // Check if email is verified
     if !user.email_verified {
@@ -195,7 +228,7 @@ pub async fn refresh(
 
     // Get user
     let user: User = sqlx::query_as(
-        "SELECT id, username, password_hash, role, email_verified FROM users WHERE id = $1",
+        "SELECT id, username, password_hash, role, email_verified, disabled FROM users WHERE id = $1",
     )
     .bind(stored_token.user_id)
     .fetch_optional(&state.db)
@@ -203,6 +236,10 @@ pub async fn refresh(
     .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
     .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
 
+    if user.disabled {
+        return Err(AppError::Unauthorized("Account is disabled".to_string()));
+    }
+
     // Delete old refresh token (rotation)
     sqlx::query("DELETE FROM refresh_tokens WHERE id = $1")
         .bind(stored_token.id)
@@ -2,8 +2,8 @@ use axum::{extract::Query, extract::State, Json};
 use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
-use crate::models::UserBasic;
-use crate::services::{create_verification_token, send_verification_email};
+use crate::models::{UserBasic, UserEvent};
+use crate::services::{create_verification_token, enqueue_event, send_verification_email};
 use crate::state::AppState;
 
 #[derive(Deserialize)]
@@ -51,19 +51,32 @@ pub async fn verify_email(
         ));
     }
 
-    // Mark user as verified
-    sqlx::query("UPDATE users SET email_verified = true WHERE id = $1")
-        .bind(user_id)
-        .execute(&state.db)
+    let mut tx = state
+        .db
+        .begin()
         .await
-        .map_err(|e| AppError::InternalServerError(format!("Failed to verify user: {}", e)))?;
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    // Mark user as verified
+    let (username,): (String,) =
+        sqlx::query_as("UPDATE users SET email_verified = true WHERE id = $1 RETURNING username")
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to verify user: {}", e)))?;
 
     // Delete the used token
     sqlx::query("DELETE FROM email_verification_tokens WHERE id = $1")
         .bind(token_id)
-        .execute(&state.db)
+        .execute(&mut *tx)
         .await
-        .ok();
+        .map_err(|e| AppError::InternalServerError(format!("Failed to verify user: {}", e)))?;
+
+    enqueue_event(&mut tx, &UserEvent::EmailVerified { user_id, username }).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to verify user: {}", e)))?;
 
     Ok(Json(VerifyEmailResponse {
         message: "Email verified successfully. You can now log in.".to_string(),
@@ -76,7 +89,7 @@ pub async fn resend_verification(
 ) -> Result<Json<VerifyEmailResponse>, AppError> {
     // Find user by email
     let user: Option<UserBasic> = sqlx::query_as(
-        "SELECT id, email FROM users WHERE email = $1 AND email_verified = false",
+        "SELECT id, email FROM users WHERE LOWER(email) = LOWER($1) AND email_verified = false",
     )
     .bind(&payload.email)
     .fetch_optional(&state.db)
@@ -1,5 +1,11 @@
+pub mod access_token;
 pub mod auth;
 pub mod email;
+pub mod user;
 
+pub use access_token::{
+    create_access_token, introspect_access_token, list_access_tokens, revoke_access_token,
+};
 pub use auth::{login, logout, protected, public, refresh, register};
-pub use email::{resend_verification, verify_email};
\ No newline at end of file
+pub use email::{resend_verification, verify_email};
+pub use user::{change_user_role, delete_user, disable_user};
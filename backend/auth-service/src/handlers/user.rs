@@ -0,0 +1,152 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::models::{ChangeRoleRequest, UserEvent};
+use crate::services::enqueue_event;
+use crate::state::AppState;
+
+#[derive(Serialize)]
+pub struct UserRoleResponse {
+    pub user_id: i32,
+    pub username: String,
+    pub role: String,
+}
+
+/// PATCH /users/:id/role - Change a user's role (admin only, enforced by the gateway)
+pub async fn change_user_role(
+    State(state): State<AppState>,
+    Path(user_id): Path<i32>,
+    Json(payload): Json<ChangeRoleRequest>,
+) -> Result<Json<UserRoleResponse>, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    // Lock the row so the old role in the event matches what was replaced
+    let (username, old_role): (String, String) =
+        sqlx::query_as("SELECT username, role FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let new_role = payload.role.to_string();
+
+    if old_role != new_role {
+        sqlx::query("UPDATE users SET role = $1 WHERE id = $2")
+            .bind(&new_role)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to change role: {}", e)))?;
+
+        enqueue_event(
+            &mut tx,
+            &UserEvent::RoleChanged {
+                user_id,
+                username: username.clone(),
+                old_role,
+                new_role: new_role.clone(),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to change role: {}", e)))?;
+
+    Ok(Json(UserRoleResponse {
+        user_id,
+        username,
+        role: new_role,
+    }))
+}
+
+/// POST /users/:id/disable - Disable an account and revoke its sessions (admin only)
+pub async fn disable_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    let (username, was_disabled): (String, bool) =
+        sqlx::query_as("SELECT username, disabled FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if was_disabled {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    sqlx::query("UPDATE users SET disabled = true WHERE id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to disable user: {}", e)))?;
+
+    // Disabled users can't refresh sessions or keep using access tokens
+    sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to disable user: {}", e)))?;
+
+    sqlx::query("DELETE FROM personal_access_tokens WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to disable user: {}", e)))?;
+
+    enqueue_event(&mut tx, &UserEvent::UserDisabled { user_id, username }).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to disable user: {}", e)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /users/:id - Delete an account (admin only)
+pub async fn delete_user(
+    State(state): State<AppState>,
+    Path(user_id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    // Tokens are removed by ON DELETE CASCADE
+    let (username,): (String,) =
+        sqlx::query_as("DELETE FROM users WHERE id = $1 RETURNING username")
+            .bind(user_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to delete user: {}", e)))?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    enqueue_event(&mut tx, &UserEvent::UserDeleted { user_id, username }).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to delete user: {}", e)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
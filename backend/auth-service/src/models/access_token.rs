@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Scopes a personal access token can be granted
+pub const ACCESS_TOKEN_SCOPES: &[&str] = &["programs:read", "workouts:read", "analytics:read"];
+
+#[derive(sqlx::FromRow)]
+pub struct PersonalAccessToken {
+    pub id: i32,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateAccessTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// None = token never expires
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct AccessTokenResponse {
+    pub id: i32,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Returned only once, on creation - the plain token is never stored
+#[derive(Serialize)]
+pub struct CreateAccessTokenResponse {
+    #[serde(flatten)]
+    pub token_info: AccessTokenResponse,
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+pub struct IntrospectRequest {
+    pub token: String,
+}
+
+/// Identity behind a valid personal access token (used by the API gateway)
+#[derive(Serialize, sqlx::FromRow)]
+pub struct IntrospectResponse {
+    pub sub: String,
+    pub role: String,
+    pub scopes: Vec<String>,
+}
+
+impl From<PersonalAccessToken> for AccessTokenResponse {
+    fn from(t: PersonalAccessToken) -> Self {
+        Self {
+            id: t.id,
+            name: t.name,
+            token_prefix: t.token_prefix,
+            scopes: t.scopes,
+            last_used_at: t.last_used_at,
+            expires_at: t.expires_at,
+            created_at: t.created_at,
+        }
+    }
+}
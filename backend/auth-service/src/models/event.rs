@@ -0,0 +1,64 @@
+use serde::Serialize;
+
+/// User lifecycle events published to other services through the outbox
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum UserEvent {
+    UserRegistered {
+        user_id: i32,
+        username: String,
+        email: String,
+        role: String,
+    },
+    EmailVerified {
+        user_id: i32,
+        username: String,
+    },
+    RoleChanged {
+        user_id: i32,
+        username: String,
+        old_role: String,
+        new_role: String,
+    },
+    UserDisabled {
+        user_id: i32,
+        username: String,
+    },
+    UserDeleted {
+        user_id: i32,
+        username: String,
+    },
+}
+
+impl UserEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            UserEvent::UserRegistered { .. } => "UserRegistered",
+            UserEvent::EmailVerified { .. } => "EmailVerified",
+            UserEvent::RoleChanged { .. } => "RoleChanged",
+            UserEvent::UserDisabled { .. } => "UserDisabled",
+            UserEvent::UserDeleted { .. } => "UserDeleted",
+        }
+    }
+
+    /// Routing key on the user events topic exchange
+    pub fn routing_key(&self) -> &'static str {
+        match self {
+            UserEvent::UserRegistered { .. } => "user.registered",
+            UserEvent::EmailVerified { .. } => "user.email_verified",
+            UserEvent::RoleChanged { .. } => "user.role_changed",
+            UserEvent::UserDisabled { .. } => "user.disabled",
+            UserEvent::UserDeleted { .. } => "user.deleted",
+        }
+    }
+}
+
+/// Event waiting in the outbox to be published
+#[derive(sqlx::FromRow)]
+pub struct OutboxEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub routing_key: String,
+    pub payload: sqlx::types::Json<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
@@ -1,5 +1,12 @@
+pub mod access_token;
+pub mod event;
 pub mod token;
 pub mod user;
 
+pub use access_token::{
+    AccessTokenResponse, CreateAccessTokenRequest, CreateAccessTokenResponse, IntrospectRequest,
+    IntrospectResponse, PersonalAccessToken, ACCESS_TOKEN_SCOPES,
+};
+pub use event::{OutboxEvent, UserEvent};
 pub use token::{MyClaims, RefreshToken};
-pub use user::{Role, User, UserBasic};
\ No newline at end of file
+pub use user::{ChangeRoleRequest, Role, User, UserBasic};
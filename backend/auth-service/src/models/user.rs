@@ -7,6 +7,7 @@ pub struct User {
     pub password_hash: String,
     pub role: String,
     pub email_verified: bool,
+    pub disabled: bool,
 }
 
 #[derive(sqlx::FromRow)]
@@ -31,4 +32,9 @@ impl std::fmt::Display for Role {
             Role::Admin => write!(f, "admin"),
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize)]
+pub struct ChangeRoleRequest {
+    pub role: Role,
+}
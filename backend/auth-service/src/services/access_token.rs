@@ -0,0 +1,169 @@
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::config::PERSONAL_ACCESS_TOKEN_PREFIX;
+use crate::error::AppError;
+use crate::models::{
+    AccessTokenResponse, CreateAccessTokenRequest, CreateAccessTokenResponse, IntrospectResponse,
+    PersonalAccessToken, ACCESS_TOKEN_SCOPES,
+};
+
+/// Number of characters of the token kept in plain text so users can tell tokens apart
+const VISIBLE_PREFIX_LEN: usize = 8;
+
+/// Tokens are long random strings, so a fast hash is enough to store them safely
+pub fn hash_access_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn generate_personal_access_token() -> String {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    format!("{}{}", PERSONAL_ACCESS_TOKEN_PREFIX, secret)
+}
+
+pub async fn find_user_id_by_username(db: &PgPool, username: &str) -> Result<i32, AppError> {
+    let row: (i32,) = sqlx::query_as("SELECT id FROM users WHERE username = $1")
+        .bind(username)
+        .fetch_optional(db)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+        .ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
+
+    Ok(row.0)
+}
+
+pub async fn create_personal_access_token(
+    db: &PgPool,
+    user_id: i32,
+    req: CreateAccessTokenRequest,
+) -> Result<CreateAccessTokenResponse, AppError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("Token name is required".to_string()));
+    }
+
+    if req.scopes.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one scope is required".to_string(),
+        ));
+    }
+
+    if let Some(scope) = req
+        .scopes
+        .iter()
+        .find(|s| !ACCESS_TOKEN_SCOPES.contains(&s.as_str()))
+    {
+        return Err(AppError::BadRequest(format!(
+            "Unknown scope '{}'. Allowed: {}",
+            scope,
+            ACCESS_TOKEN_SCOPES.join(", ")
+        )));
+    }
+
+    let expires_at = match req.expires_in_days {
+        Some(days) if days <= 0 => {
+            return Err(AppError::BadRequest(
+                "expires_in_days must be positive".to_string(),
+            ))
+        }
+        Some(days) => Some(chrono::Utc::now() + chrono::Duration::days(days)),
+        None => None,
+    };
+
+    let mut scopes = req.scopes;
+    scopes.sort();
+    scopes.dedup();
+
+    let token = generate_personal_access_token();
+    let token_prefix = token[..PERSONAL_ACCESS_TOKEN_PREFIX.len() + VISIBLE_PREFIX_LEN].to_string();
+
+    let stored: PersonalAccessToken = sqlx::query_as(
+        r#"
+        INSERT INTO personal_access_tokens (user_id, name, token_hash, token_prefix, scopes, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, name, token_prefix, scopes, last_used_at, expires_at, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(hash_access_token(&token))
+    .bind(&token_prefix)
+    .bind(&scopes)
+    .bind(expires_at)
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to store access token: {}", e)))?;
+
+    Ok(CreateAccessTokenResponse {
+        token_info: stored.into(),
+        token,
+    })
+}
+
+pub async fn list_personal_access_tokens(
+    db: &PgPool,
+    user_id: i32,
+) -> Result<Vec<AccessTokenResponse>, AppError> {
+    let tokens: Vec<PersonalAccessToken> = sqlx::query_as(
+        r#"
+        SELECT id, name, token_prefix, scopes, last_used_at, expires_at, created_at
+        FROM personal_access_tokens
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    Ok(tokens.into_iter().map(|t| t.into()).collect())
+}
+
+pub async fn revoke_personal_access_token(
+    db: &PgPool,
+    user_id: i32,
+    token_id: i32,
+) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2")
+        .bind(token_id)
+        .bind(user_id)
+        .execute(db)
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Access token not found".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Resolve a plain token to its owner and scopes, recording the time it was used
+pub async fn introspect_personal_access_token(
+    db: &PgPool,
+    token: &str,
+) -> Result<IntrospectResponse, AppError> {
+    sqlx::query_as(
+        r#"
+        UPDATE personal_access_tokens t
+        SET last_used_at = NOW()
+        FROM users u
+        WHERE u.id = t.user_id
+          AND NOT u.disabled
+          AND t.token_hash = $1
+          AND (t.expires_at IS NULL OR t.expires_at > NOW())
+        RETURNING u.username AS sub, u.role, t.scopes
+        "#,
+    )
+    .bind(hash_access_token(token))
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?
+    .ok_or_else(|| AppError::Unauthorized("Invalid or expired access token".to_string()))
+}
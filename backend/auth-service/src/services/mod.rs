@@ -1,5 +1,15 @@
+pub mod access_token;
 pub mod email;
+pub mod outbox;
+pub mod password;
+pub mod rabbitmq;
 pub mod token;
 
+pub use access_token::{
+    create_personal_access_token, find_user_id_by_username, introspect_personal_access_token,
+    list_personal_access_tokens, revoke_personal_access_token,
+};
 pub use email::{create_verification_token, send_verification_email};
-pub use token::{create_refresh_token, generate_access_token};
\ No newline at end of file
+pub use outbox::{enqueue_event, start_outbox_relay};
+pub use password::{hash_password, needs_rehash, validate_password, verify_password};
+pub use token::{create_refresh_token, generate_access_token};
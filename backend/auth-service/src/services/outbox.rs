@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use sqlx::{PgConnection, PgPool};
+
+use crate::config::{OUTBOX_BATCH_SIZE, OUTBOX_POLL_INTERVAL_SECS};
+use crate::error::AppError;
+use crate::models::{OutboxEvent, UserEvent};
+use crate::services::rabbitmq::RabbitMQPublisher;
+
+/// Store an event in the outbox. Call this inside the transaction that changes the
+/// user, so the event is only published if that change is committed.
+pub async fn enqueue_event(conn: &mut PgConnection, event: &UserEvent) -> Result<(), AppError> {
+    let payload = serde_json::to_value(event).map_err(|e| {
+        AppError::InternalServerError(format!("Failed to serialize user event: {}", e))
+    })?;
+
+    sqlx::query(
+        "INSERT INTO outbox_events (event_type, routing_key, payload) VALUES ($1, $2, $3)",
+    )
+    .bind(event.event_type())
+    .bind(event.routing_key())
+    .bind(sqlx::types::Json(payload))
+    .execute(conn)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Failed to store user event: {}", e)))?;
+
+    Ok(())
+}
+
+/// Publish one batch of pending events in order.
+/// Rows stay locked until the batch is marked, so several relays can run side by side.
+async fn relay_batch(db: &PgPool, publisher: &RabbitMQPublisher) -> Result<usize, AppError> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    let events: Vec<OutboxEvent> = sqlx::query_as(
+        r#"
+        SELECT id, event_type, routing_key, payload, created_at
+        FROM outbox_events
+        WHERE published_at IS NULL
+        ORDER BY id
+        LIMIT $1
+        FOR UPDATE SKIP LOCKED
+        "#,
+    )
+    .bind(OUTBOX_BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    let mut published: Vec<i64> = Vec::new();
+    let mut publish_error = None;
+
+    for event in &events {
+        match publisher.publish_event(event).await {
+            Ok(()) => published.push(event.id),
+            Err(e) => {
+                // Stop at the first failure to keep events in order
+                publish_error = Some(e);
+                break;
+            }
+        }
+    }
+
+    if !published.is_empty() {
+        sqlx::query("UPDATE outbox_events SET published_at = NOW() WHERE id = ANY($1)")
+            .bind(&published)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Database error: {}", e)))?;
+
+    match publish_error {
+        Some(e) => Err(e),
+        None => Ok(published.len()),
+    }
+}
+
+/// Spawn the background task that moves events from the outbox to RabbitMQ.
+/// The broker connection is (re)established lazily, so auth-service keeps working
+/// while RabbitMQ is down and catches up once it is back.
+pub fn start_outbox_relay(db: PgPool, rabbitmq_url: String) {
+    tokio::spawn(async move {
+        let mut publisher: Option<RabbitMQPublisher> = None;
+
+        loop {
+            if publisher.is_none() {
+                match RabbitMQPublisher::new(&rabbitmq_url).await {
+                    Ok(p) => publisher = Some(p),
+                    Err(e) => eprintln!("Outbox relay: RabbitMQ unavailable, retrying: {:?}", e),
+                }
+            }
+
+            let mut sent_full_batch = false;
+            if let Some(p) = &publisher {
+                match relay_batch(&db, p).await {
+                    Ok(count) => sent_full_batch = count as i64 == OUTBOX_BATCH_SIZE,
+                    Err(e) => {
+                        eprintln!("Outbox relay: failed to publish events, reconnecting: {:?}", e);
+                        publisher = None;
+                    }
+                }
+            }
+
+            // Keep draining without waiting while there is a backlog
+            if !sent_full_batch {
+                tokio::time::sleep(Duration::from_secs(OUTBOX_POLL_INTERVAL_SECS)).await;
+            }
+        }
+    });
+}
@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+
+use crate::config::PasswordConfig;
+use crate::error::AppError;
+
+/// Bundled offline list of common and breached passwords
+static COMMON_PASSWORDS: LazyLock<HashSet<String>> = LazyLock::new(|| {
+    include_str!("../../data/common_passwords.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect()
+});
+
+/// Check a password against the configured policy.
+/// All violated rules are reported at once so the user can fix them in one go.
+pub fn validate_password(
+    config: &PasswordConfig,
+    password: &str,
+    username: &str,
+) -> Result<(), AppError> {
+    let mut violations: Vec<String> = Vec::new();
+    let length = password.chars().count();
+
+    if length < config.min_length {
+        violations.push(format!(
+            "must be at least {} characters",
+            config.min_length
+        ));
+    }
+    if length > config.max_length {
+        violations.push(format!("must be at most {} characters", config.max_length));
+    }
+    if config.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+        violations.push("must contain an uppercase letter".to_string());
+    }
+    if config.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+        violations.push("must contain a lowercase letter".to_string());
+    }
+    if config.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+        violations.push("must contain a digit".to_string());
+    }
+    if config.require_symbol && !password.chars().any(|c| !c.is_alphanumeric()) {
+        violations.push("must contain a symbol".to_string());
+    }
+
+    let lowercase = password.to_lowercase();
+    if !username.is_empty() && lowercase.contains(&username.to_lowercase()) {
+        violations.push("must not contain your username".to_string());
+    }
+    if COMMON_PASSWORDS.contains(&lowercase) {
+        violations.push("is too common and has appeared in data breaches".to_string());
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::BadRequest(format!(
+            "Password {}",
+            violations.join(", ")
+        )))
+    }
+}
+
+/// Build an Argon2 hasher with the configured cost parameters
+pub fn password_hasher(config: &PasswordConfig) -> Result<Argon2<'static>, AppError> {
+    let params = Params::new(
+        config.argon2_memory_kib,
+        config.argon2_iterations,
+        config.argon2_parallelism,
+        None,
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Invalid Argon2 parameters: {}", e)))?;
+
+    Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+}
+
+pub fn hash_password(config: &PasswordConfig, password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = password_hasher(config)?
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| AppError::InternalServerError(format!("Password hashing failed: {}", e)))?;
+
+    Ok(hash.to_string())
+}
+
+/// Verify a password against a stored hash, using the parameters recorded in the hash.
+/// Returns whether the password matched.
+pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, AppError> {
+    let parsed_hash = PasswordHash::new(password_hash)
+        .map_err(|e| AppError::InternalServerError(format!("Hash parsing failed: {}", e)))?;
+
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+/// Whether a stored hash was produced with a different algorithm or cost parameters
+/// than the ones currently configured
+pub fn needs_rehash(config: &PasswordConfig, password_hash: &str) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+        return true;
+    };
+    let Ok(params) = Params::try_from(&parsed_hash) else {
+        return true;
+    };
+
+    parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+        || params.m_cost() != config.argon2_memory_kib
+        || params.t_cost() != config.argon2_iterations
+        || params.p_cost() != config.argon2_parallelism
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PasswordConfig {
+        PasswordConfig {
+            min_length: 8,
+            max_length: 16,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+            argon2_memory_kib: Params::MIN_M_COST,
+            argon2_iterations: Params::MIN_T_COST,
+            argon2_parallelism: Params::MIN_P_COST,
+        }
+    }
+
+    fn violations(password: &str, username: &str) -> String {
+        match validate_password(&config(), password, username) {
+            Err(AppError::BadRequest(message)) => message,
+            other => panic!("expected a policy violation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn accepts_password_meeting_every_rule() {
+        assert!(validate_password(&config(), "Tr4ining-Log!", "alice").is_ok());
+    }
+
+    #[test]
+    fn enforces_length_bounds() {
+        assert!(violations("Ab1!", "alice").contains("at least 8 characters"));
+        assert!(violations("Abcdefgh1!Abcdefgh", "alice").contains("at most 16 characters"));
+    }
+
+    #[test]
+    fn counts_characters_rather_than_bytes() {
+        // Eight characters, but more than eight bytes
+        assert!(validate_password(&config(), "Äbc1!äöü", "alice").is_ok());
+    }
+
+    #[test]
+    fn reports_all_missing_character_classes_at_once() {
+        let message = violations("        ", "alice");
+        assert!(message.contains("uppercase letter"));
+        assert!(message.contains("lowercase letter"));
+        assert!(message.contains("digit"));
+        assert!(!message.contains("symbol"));
+    }
+
+    #[test]
+    fn rejects_password_containing_username_case_insensitively() {
+        assert!(violations("xALICEx1!", "alice").contains("username"));
+    }
+
+    #[test]
+    fn rejects_common_passwords_case_insensitively() {
+        let config = PasswordConfig {
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            ..config()
+        };
+        let common = COMMON_PASSWORDS.iter().find(|p| p.len() >= 8 && p.len() <= 16);
+        let common = common.expect("bundled list has a password within the length bounds");
+        let result = validate_password(&config, &common.to_uppercase(), "");
+        assert!(matches!(result, Err(AppError::BadRequest(m)) if m.contains("too common")));
+    }
+
+    #[test]
+    fn hashes_verify_and_track_parameters() {
+        let config = config();
+        let hash = hash_password(&config, "Tr4ining-Log!").unwrap();
+        assert!(verify_password("Tr4ining-Log!", &hash).unwrap());
+        assert!(!verify_password("wrong", &hash).unwrap());
+        assert!(!needs_rehash(&config, &hash));
+
+        let stronger = PasswordConfig {
+            argon2_iterations: config.argon2_iterations + 1,
+            ..config
+        };
+        assert!(needs_rehash(&stronger, &hash));
+        assert!(needs_rehash(&stronger, "not a hash"));
+    }
+}
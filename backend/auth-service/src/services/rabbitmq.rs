@@ -0,0 +1,101 @@
+use lapin::{
+    options::{BasicPublishOptions, ConfirmSelectOptions, ExchangeDeclareOptions},
+    types::{FieldTable, ShortString},
+    BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+
+use crate::error::AppError;
+use crate::models::OutboxEvent;
+
+pub const USER_EVENTS_EXCHANGE: &str = "user_events";
+
+/// RabbitMQ publisher for user lifecycle events
+pub struct RabbitMQPublisher {
+    // Kept alive for as long as the channel is in use
+    _connection: Connection,
+    channel: Channel,
+}
+
+impl RabbitMQPublisher {
+    pub async fn new(rabbitmq_url: &str) -> Result<Self, AppError> {
+        let connection = Connection::connect(rabbitmq_url, ConnectionProperties::default())
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to connect to RabbitMQ: {}", e))
+            })?;
+
+        let channel = connection.create_channel().await.map_err(|e| {
+            AppError::InternalServerError(format!("Failed to create RabbitMQ channel: {}", e))
+        })?;
+
+        // Wait for broker confirms so an event is only marked published once RabbitMQ has it
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to enable publisher confirms: {}", e))
+            })?;
+
+        channel
+            .exchange_declare(
+                USER_EVENTS_EXCHANGE,
+                ExchangeKind::Topic,
+                ExchangeDeclareOptions {
+                    durable: true,
+                    ..ExchangeDeclareOptions::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to declare exchange: {}", e))
+            })?;
+
+        println!(
+            "RabbitMQ connected, exchange '{}' declared",
+            USER_EVENTS_EXCHANGE
+        );
+
+        Ok(Self {
+            _connection: connection,
+            channel,
+        })
+    }
+
+    pub async fn publish_event(&self, event: &OutboxEvent) -> Result<(), AppError> {
+        let payload = serde_json::to_vec(&event.payload.0).map_err(|e| {
+            AppError::InternalServerError(format!("Failed to serialize user event: {}", e))
+        })?;
+
+        let confirmation = self
+            .channel
+            .basic_publish(
+                USER_EVENTS_EXCHANGE,
+                &event.routing_key,
+                BasicPublishOptions::default(),
+                &payload,
+                BasicProperties::default()
+                    .with_content_type("application/json".into())
+                    .with_delivery_mode(2) // Persistent
+                    .with_message_id(ShortString::from(event.id.to_string()))
+                    .with_type(ShortString::from(event.event_type.clone()))
+                    .with_timestamp(event.created_at.timestamp() as u64),
+            )
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to publish message: {}", e))
+            })?
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to confirm message: {}", e))
+            })?;
+
+        if !confirmation.is_ack() {
+            return Err(AppError::InternalServerError(
+                "RabbitMQ did not acknowledge the message".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
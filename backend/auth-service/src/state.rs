@@ -1,10 +1,11 @@
 use sqlx::PgPool;
 
-use crate::config::SmtpConfig;
+use crate::config::{PasswordConfig, SmtpConfig};
 
 #[derive(Clone)]
 pub struct AppState {
     pub jwt_secret: String,
     pub db: PgPool,
     pub smtp_config: SmtpConfig,
+    pub password_config: PasswordConfig,
 }
\ No newline at end of file
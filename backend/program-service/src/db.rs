@@ -1,20 +1,42 @@
-use mongodb::{Collection, Database};
+use mongodb::{Client, Collection, Database};
 
-use crate::models::{Exercise, Program, WorkoutExercise};
+use crate::models::{
+    Entitlement, Exercise, OutboxEvent, Payment, Program, ProgramChange, ProgramVersion, Review,
+    Substitution, Tag, WorkoutExercise,
+};
 
 #[derive(Clone)]
 pub struct Collections {
+    /// Client handle for starting sessions on multi-document transactions
+    pub client: Client,
     pub programs: Collection<Program>,
     pub workout_exercises: Collection<WorkoutExercise>,
     pub exercises: Collection<Exercise>,
+    pub program_versions: Collection<ProgramVersion>,
+    pub program_changes: Collection<ProgramChange>,
+    pub tags: Collection<Tag>,
+    pub reviews: Collection<Review>,
+    pub payments: Collection<Payment>,
+    pub entitlements: Collection<Entitlement>,
+    pub substitutions: Collection<Substitution>,
+    pub outbox_events: Collection<OutboxEvent>,
 }
 
 impl Collections {
     pub fn new(db: &Database) -> Self {
         Self {
+            client: db.client().clone(),
             programs: db.collection("programs"),
             workout_exercises: db.collection("workout_exercises"),
             exercises: db.collection("exercises"),
+            program_versions: db.collection("program_versions"),
+            program_changes: db.collection("program_changes"),
+            tags: db.collection("tags"),
+            reviews: db.collection("reviews"),
+            payments: db.collection("payments"),
+            entitlements: db.collection("entitlements"),
+            substitutions: db.collection("exercise_substitutions"),
+            outbox_events: db.collection("outbox_events"),
         }
     }
 }
@@ -3,27 +3,78 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use mongodb::error::{ErrorKind, WriteFailure};
 use serde::Serialize;
 
+use crate::models::{ProgramChangeResponse, ValidationError};
+
 #[derive(Serialize)]
 pub struct ErrorResponse {
     pub error: String,
 }
 
+/// 422 body listing every problem found in program content
+#[derive(Serialize)]
+pub struct ValidationErrorResponse {
+    pub error: String,
+    pub errors: Vec<ValidationError>,
+}
+
+/// 409 body for a stale If-Match, listing what changed since the client's revision
+#[derive(Serialize)]
+pub struct ConflictResponse {
+    pub error: String,
+    pub current_revision: i64,
+    pub changes: Vec<ProgramChangeResponse>,
+}
+
+#[derive(Debug)]
 pub enum AppError {
     Unauthorized(String),
+    Forbidden(String),
+    PaymentRequired(String),
     InternalServerError(String),
     BadRequest(String),
     NotFound(String),
+    Conflict(String),
+    /// Transaction aborted by a concurrent write; safe to retry
+    TransactionConflict(String),
+    Validation(Vec<ValidationError>),
+    RevisionConflict {
+        current_revision: i64,
+        changes: Vec<ProgramChangeResponse>,
+    },
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::PaymentRequired(msg) => (StatusCode::PAYMENT_REQUIRED, msg),
             AppError::InternalServerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::TransactionConflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::Validation(errors) => {
+                let body = Json(ValidationErrorResponse {
+                    error: "Program content is invalid".to_string(),
+                    errors,
+                });
+                return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+            }
+            AppError::RevisionConflict {
+                current_revision,
+                changes,
+            } => {
+                let body = Json(ConflictResponse {
+                    error: format!("Program was modified (current revision {})", current_revision),
+                    current_revision,
+                    changes,
+                });
+                return (StatusCode::CONFLICT, body).into_response();
+            }
         };
 
         let body = Json(ErrorResponse {
@@ -34,8 +85,36 @@ impl IntoResponse for AppError {
     }
 }
 
+/// Server error code for a write that conflicted with a concurrent transaction
+const WRITE_CONFLICT: i32 = 112;
+
+/// Whether the server rejected a write because another transaction touched the same document
+fn is_write_conflict(err: &mongodb::error::Error) -> bool {
+    match err.kind.as_ref() {
+        ErrorKind::Command(e) => e.code == WRITE_CONFLICT,
+        ErrorKind::Write(WriteFailure::WriteError(e)) => e.code == WRITE_CONFLICT,
+        ErrorKind::InsertMany(e) => e
+            .write_errors
+            .iter()
+            .flatten()
+            .any(|w| w.code == WRITE_CONFLICT),
+        _ => false,
+    }
+}
+
 impl From<mongodb::error::Error> for AppError {
     fn from(err: mongodb::error::Error) -> Self {
+        if is_write_conflict(&err) {
+            return AppError::TransactionConflict(
+                "Program was modified concurrently, please retry".to_string(),
+            );
+        }
         AppError::InternalServerError(format!("Database error: {}", err))
     }
 }
+
+impl From<reqwest::Error> for AppError {
+    fn from(err: reqwest::Error) -> Self {
+        AppError::InternalServerError(format!("HTTP client error: {}", err))
+    }
+}
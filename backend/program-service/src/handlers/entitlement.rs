@@ -0,0 +1,40 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{EntitlementResponse, EntitlementStatusResponse};
+use crate::services::entitlement;
+use crate::state::AppState;
+
+fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
+    headers
+        .get("X-User-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Unauthorized("Missing X-User-Id header".to_string()))
+}
+
+/// GET /programs/:id/entitlement - Whether the caller may start the program
+pub async fn get_entitlement_status(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(program_id): Path<String>,
+) -> Result<Json<EntitlementStatusResponse>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let status =
+        entitlement::get_entitlement_status(&state.collections, &user_id, &program_id).await?;
+    Ok(Json(status))
+}
+
+/// GET /entitlements/mine - Programs the caller has purchased
+pub async fn get_user_entitlements(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<EntitlementResponse>>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let entitlements = entitlement::get_user_entitlements(&state.collections, &user_id).await?;
+    Ok(Json(entitlements))
+}
@@ -1,12 +1,14 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Multipart, Path, Query, State},
     http::StatusCode,
     Json,
 };
 
 use crate::error::AppError;
-use crate::models::{CreateExerciseRequest, ExerciseQueryParams, ExerciseResponse, PaginatedExerciseResponse, UpdateExerciseRequest};
+use crate::handlers::file::read_image_upload;
+use crate::models::{CreateExerciseRequest, DeleteExerciseQuery, ExerciseQueryParams, ExerciseResponse, PaginatedExerciseResponse, UpdateExerciseRequest};
 use crate::services;
+use crate::services::started_program_client::StartedProgramClient;
 use crate::state::AppState;
 
 /// POST /exercises - Create a new exercise
@@ -47,11 +49,38 @@ pub async fn update_exercise(
     Ok(Json(exercise))
 }
 
-/// DELETE /exercises/:id - Delete an exercise
+/// POST /exercises/:id/image - Upload exercise image (resized into thumbnail, card and full variants)
+pub async fn upload_exercise_image(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<String>,
+    multipart: Multipart,
+) -> Result<Json<ExerciseResponse>, AppError> {
+    let data = read_image_upload(multipart).await?;
+    let exercise = services::set_exercise_image(
+        &state.collections,
+        state.storage.as_ref(),
+        &exercise_id,
+        data,
+    )
+    .await?;
+    Ok(Json(exercise))
+}
+
+/// DELETE /exercises/:id - Delete an unused exercise, or ?deprecate=true / ?merge_into=<id>
 pub async fn delete_exercise(
     State(state): State<AppState>,
     Path(exercise_id): Path<String>,
+    Query(query): Query<DeleteExerciseQuery>,
 ) -> Result<StatusCode, AppError> {
-    services::delete_exercise(&state.collections, &exercise_id).await?;
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    services::delete_exercise(
+        &state.collections,
+        state.storage.as_ref(),
+        &started_programs,
+        &exercise_id,
+        query,
+    )
+    .await?;
     Ok(StatusCode::NO_CONTENT)
 }
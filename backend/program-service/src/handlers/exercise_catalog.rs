@@ -0,0 +1,74 @@
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{CatalogResyncResponse, ExerciseImportQuery, ExerciseImportReport, ExportQuery};
+use crate::services::exercise_catalog;
+use crate::state::AppState;
+
+/// GET /exercises/export?format=json|csv - Export the whole exercise catalog (admin)
+pub async fn export_exercises(
+    State(state): State<AppState>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let catalog = exercise_catalog::export_catalog(&state.collections).await?;
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(catalog).into_response()),
+        "csv" => {
+            let body = exercise_catalog::catalog_to_csv(&catalog)?;
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                    (header::CONTENT_DISPOSITION, "attachment; filename=\"exercises.csv\""),
+                ],
+                body,
+            )
+                .into_response())
+        }
+        other => Err(AppError::BadRequest(format!("Unsupported export format: {}", other))),
+    }
+}
+
+/// POST /exercises/import?dry_run=false - Validate and upsert exercises from JSON or text/csv (admin)
+/// Dry run by default; a real import that has failing rows is refused with 422 and writes nothing
+pub async fn import_exercises(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ExerciseImportQuery>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ExerciseImportReport>), AppError> {
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/csv"));
+
+    let rows = if is_csv {
+        exercise_catalog::parse_csv(&body)?
+    } else {
+        exercise_catalog::parse_json(&body)?
+    };
+
+    let dry_run = query.dry_run.unwrap_or(true);
+    let report = exercise_catalog::import_exercises(&state.collections, rows, dry_run).await?;
+
+    let status = if !dry_run && !report.applied {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(report)))
+}
+
+/// POST /exercises/resync - Publish the whole catalog for other services' read models (admin)
+pub async fn resync_exercises(
+    State(state): State<AppState>,
+) -> Result<(StatusCode, Json<CatalogResyncResponse>), AppError> {
+    let result = exercise_catalog::resync_catalog(&state.collections).await?;
+    Ok((StatusCode::ACCEPTED, Json(result)))
+}
@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::header,
+    response::IntoResponse,
+};
+
+use crate::error::AppError;
+use crate::services::program_image;
+use crate::state::AppState;
+
+const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024; // 5MB
+
+/// Read the "image" field of an upload; the format is checked from the bytes, not the declared type
+pub async fn read_image_upload(mut multipart: Multipart) -> Result<Vec<u8>, AppError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to read multipart: {}", e)))?
+    {
+        if field.name() == Some("image") {
+            let data = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::BadRequest(format!("Failed to read file data: {}", e)))?;
+
+            if data.len() > MAX_IMAGE_SIZE {
+                return Err(AppError::BadRequest("Image too large. Max size: 5MB".to_string()));
+            }
+
+            return Ok(data.to_vec());
+        }
+    }
+
+    Err(AppError::BadRequest("No image field found".to_string()))
+}
+
+/// GET /files/*key - Serve a stored object (program and exercise images)
+pub async fn get_file(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let file = program_image::get_file(state.storage.as_ref(), &key).await?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.content_type),
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable".to_string()),
+        ],
+        file.data,
+    ))
+}
@@ -1,7 +1,30 @@
+pub mod entitlement;
 pub mod exercise;
+pub mod exercise_catalog;
+pub mod file;
+pub mod muscle;
+pub mod payment;
 pub mod program;
+pub mod program_export;
+pub mod program_layout;
+pub mod progression;
+pub mod review;
+pub mod revision;
+pub mod substitution;
+pub mod tag;
 pub mod workout_exercise;
 
+pub use entitlement::*;
 pub use exercise::*;
+pub use exercise_catalog::*;
+pub use file::*;
+pub use muscle::*;
+pub use payment::*;
 pub use program::*;
+pub use program_export::*;
+pub use program_layout::*;
+pub use progression::*;
+pub use review::*;
+pub use substitution::*;
+pub use tag::*;
 pub use workout_exercise::*;
@@ -0,0 +1,9 @@
+use axum::Json;
+
+use crate::models::Muscle;
+use crate::services::muscle::MUSCLES;
+
+/// GET /muscles - Canonical muscle registry with body regions and body highlighter slugs
+pub async fn get_muscles() -> Json<&'static [Muscle]> {
+    Json(MUSCLES)
+}
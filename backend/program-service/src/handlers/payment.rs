@@ -0,0 +1,81 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{CompleteLocalPaymentRequest, PaymentResponse, PurchaseResponse};
+use crate::services::payment;
+use crate::state::AppState;
+
+fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
+    headers
+        .get("X-User-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Unauthorized("Missing X-User-Id header".to_string()))
+}
+
+/// POST /programs/:id/purchase - Start a purchase and get the checkout
+pub async fn purchase_program(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(program_id): Path<String>,
+) -> Result<(StatusCode, Json<PurchaseResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let purchase = payment::purchase_program(
+        &state.collections,
+        state.payment_provider.as_ref(),
+        &user_id,
+        &program_id,
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(purchase)))
+}
+
+/// POST /payments/webhook - Payment provider notifications
+pub async fn payment_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, AppError> {
+    payment::handle_webhook(
+        &state.collections,
+        state.payment_provider.as_ref(),
+        &headers,
+        &body,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /payments/local/:reference/complete - Finish a checkout with the local provider
+pub async fn complete_local_payment(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(reference): Path<String>,
+    Json(payload): Json<CompleteLocalPaymentRequest>,
+) -> Result<Json<PaymentResponse>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let payment = payment::complete_local_payment(
+        &state.collections,
+        state.payment_provider.as_ref(),
+        &user_id,
+        &reference,
+        payload.succeeded,
+    )
+    .await?;
+    Ok(Json(payment))
+}
+
+/// GET /payments/mine - The caller's payments
+pub async fn get_user_payments(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PaymentResponse>>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let payments = payment::get_user_payments(&state.collections, &user_id).await?;
+    Ok(Json(payments))
+}
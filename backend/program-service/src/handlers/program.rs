@@ -5,15 +5,16 @@ use axum::{
 };
 
 use crate::error::AppError;
+use crate::handlers::file::read_image_upload;
+use crate::handlers::revision::{etag, expected_revision, ETag};
 use crate::models::{
-    CreateProgramRequest, CreateProgramResponse, PaginatedProgramResponse,
+    CreateProgramRequest, CreateProgramResponse, PaginatedProgramResponse, ProgramFacetsResponse,
     ProgramResponse, ProgramSearchParams, UpdateProgramRequest,
 };
 use crate::services;
+use crate::services::program_image;
 use crate::state::AppState;
 
-const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024; // 5MB
-
 fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
     headers
         .get("X-User-Id")
@@ -62,120 +63,74 @@ pub async fn update_program(
     headers: HeaderMap,
     Path(program_id): Path<String>,
     Json(payload): Json<UpdateProgramRequest>,
-) -> Result<Json<ProgramResponse>, AppError> {
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
     let user_id = extract_user_id(&headers)?;
-    let program =
-        services::update_program(&state.collections, &user_id, &program_id, payload)
-            .await?;
-    Ok(Json(program))
+    let expected = expected_revision(&headers)?;
+    let program = services::update_program(
+        &state.collections,
+        &user_id,
+        &program_id,
+        payload,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
 }
 
-/// DELETE /programs/:id - Delete a program
+/// DELETE /programs/:id - Soft-delete a program
+/// The image is kept since athletes mid-run still display it
 pub async fn delete_program(
     State(state): State<AppState>,
     headers: HeaderMap,
     Path(program_id): Path<String>,
 ) -> Result<StatusCode, AppError> {
     let user_id = extract_user_id(&headers)?;
-    // Get the program first to check if it has an image to delete
-    let program = services::get_program(&state.collections, &user_id, &program_id).await?;
+    services::delete_program(&state.collections, &user_id, &program_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    // Delete S3 image if exists
-    if let Some(ref image_url) = program.image_url {
-        if let Some(key) = services::extract_s3_key_from_url(image_url, &state.s3_bucket) {
-            let _ = services::delete_image(&state.s3_client, &state.s3_bucket, &key).await;
-        }
-    }
+/// POST /programs/:id/fork - Copy a program and its workouts into a new draft owned by the caller
+pub async fn fork_program(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(program_id): Path<String>,
+) -> Result<(StatusCode, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let program = services::fork_program(&state.collections, &user_id, &program_id).await?;
+    Ok((StatusCode::CREATED, Json(program)))
+}
 
-    services::delete_program(&state.collections, &user_id, &program_id).await?;
+/// POST /programs/:id/starts - Count a program start (internal, for started-program-service)
+pub async fn record_program_start(
+    State(state): State<AppState>,
+    Path(program_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    services::record_program_start(&state.collections, &program_id).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
-/// POST /programs/:id/image - Upload program image
+/// POST /programs/:id/image - Upload program image (resized into thumbnail, card and full variants)
 pub async fn upload_program_image(
     State(state): State<AppState>,
     headers: HeaderMap,
     Path(program_id): Path<String>,
-    mut multipart: Multipart,
-) -> Result<Json<ProgramResponse>, AppError> {
+    multipart: Multipart,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
     let user_id = extract_user_id(&headers)?;
-    // Verify program ownership
-    let program = services::get_program(&state.collections, &user_id, &program_id).await?;
-
-    // Extract image from multipart
-    let mut file_data: Option<Vec<u8>> = None;
-    let mut content_type: Option<String> = None;
-
-    while let Some(field) = multipart
-        .next_field()
-        .await
-        .map_err(|e| AppError::BadRequest(format!("Failed to read multipart: {}", e)))?
-    {
-        let name = field.name().unwrap_or("").to_string();
-        if name == "image" {
-            content_type = field.content_type().map(|s| s.to_string());
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| AppError::BadRequest(format!("Failed to read file data: {}", e)))?;
-
-            if data.len() > MAX_IMAGE_SIZE {
-                return Err(AppError::BadRequest("Image too large. Max size: 5MB".to_string()));
-            }
-
-            file_data = Some(data.to_vec());
-            break;
-        }
-    }
-
-    let file_data = file_data.ok_or_else(|| AppError::BadRequest("No image field found".to_string()))?;
-    let content_type = content_type.ok_or_else(|| AppError::BadRequest("Missing content type".to_string()))?;
-
-    // Validate content type
-    let extension = match content_type.as_str() {
-        "image/jpeg" => "jpg",
-        "image/png" => "png",
-        "image/webp" => "webp",
-        _ => return Err(AppError::BadRequest("Invalid image type. Allowed: JPEG, PNG, WebP".to_string())),
-    };
-
-    // Delete old image if exists
-    if let Some(ref old_url) = program.image_url {
-        if let Some(key) = services::extract_s3_key_from_url(old_url, &state.s3_bucket) {
-            let _ = services::delete_image(&state.s3_client, &state.s3_bucket, &key).await;
-        }
-    }
-
-    // Generate S3 key
-    let file_id = uuid::Uuid::new_v4();
-    let s3_key = format!("programs/{}/{}.{}", program_id, file_id, extension);
-
-    // Upload to S3
-    let image_url = services::upload_image(
-        &state.s3_client,
-        &state.s3_bucket,
-        &s3_key,
-        file_data,
-        &content_type,
-    ).await?;
-
-    // Update program with new image URL
-    let updated = services::update_program(
+    let expected = expected_revision(&headers)?;
+    let data = read_image_upload(multipart).await?;
+
+    let updated = program_image::set_program_image(
         &state.collections,
+        state.storage.as_ref(),
         &user_id,
         &program_id,
-        UpdateProgramRequest {
-            name: None,
-            description: None,
-            image_url: Some(image_url),
-            tags: None,
-            total_weeks: None,
-            public: None,
-            created: None,
-        },
-    ).await?;
-
-    Ok(Json(updated))
+        data,
+        expected,
+    )
+    .await?;
+
+    Ok((etag(updated.revision), Json(updated)))
 }
 
 /// GET /programs/search/public - Search public programs with pagination
@@ -199,3 +154,23 @@ pub async fn search_user_programs(
         services::search_user_programs(&state.collections, &user_id, params).await?;
     Ok(Json(result))
 }
+
+/// GET /programs/search/public/facets - Tag, duration and difficulty counts for a public search
+pub async fn public_program_facets(
+    State(state): State<AppState>,
+    Query(params): Query<ProgramSearchParams>,
+) -> Result<Json<ProgramFacetsResponse>, AppError> {
+    let result = services::public_program_facets(&state.collections, params).await?;
+    Ok(Json(result))
+}
+
+/// GET /programs/search/mine/facets - Tag, duration and difficulty counts for the user's programs
+pub async fn user_program_facets(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ProgramSearchParams>,
+) -> Result<Json<ProgramFacetsResponse>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let result = services::user_program_facets(&state.collections, &user_id, params).await?;
+    Ok(Json(result))
+}
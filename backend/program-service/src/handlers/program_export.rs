@@ -0,0 +1,76 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{ExportQuery, ImportQuery, ProgramExport, ProgramResponse};
+use crate::services::program_export;
+use crate::state::AppState;
+
+fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
+    headers
+        .get("X-User-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Unauthorized("Missing X-User-Id header".to_string()))
+}
+
+/// GET /programs/:id/export?format=json|csv - Export a program as a portable document
+pub async fn export_program(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(program_id): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let export = program_export::export_program(&state.collections, &user_id, &program_id).await?;
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(export).into_response()),
+        "csv" => {
+            let body = program_export::export_to_csv(&export)?;
+            let disposition = format!("attachment; filename=\"program-{}.csv\"", program_id);
+            Ok((
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                body,
+            )
+                .into_response())
+        }
+        other => Err(AppError::BadRequest(format!("Unsupported export format: {}", other))),
+    }
+}
+
+/// POST /programs/import?name=... - Create a draft from an export document (JSON or text/csv)
+pub async fn import_program(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+
+    let is_csv = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/csv"));
+
+    let import = if is_csv {
+        let name = query
+            .name
+            .ok_or_else(|| AppError::BadRequest("name is required for CSV imports".to_string()))?;
+        program_export::csv_to_export(name, &body)?
+    } else {
+        serde_json::from_slice::<ProgramExport>(&body)
+            .map_err(|e| AppError::BadRequest(format!("Invalid program document: {}", e)))?
+    };
+
+    let program = program_export::import_program(&state.collections, &user_id, import).await?;
+    Ok((StatusCode::CREATED, Json(program)))
+}
@@ -0,0 +1,206 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+
+use crate::error::AppError;
+use crate::handlers::revision::{etag, expected_revision, ETag};
+use crate::models::{
+    CopyWeekRequest, InsertWeekRequest, ProgramResponse, RepeatWeeksRequest,
+    ReorderWorkoutsRequest, WorkoutTargetRequest,
+};
+use crate::services::program_layout::{apply_layout_operation, LayoutOperation};
+use crate::services::started_program_client::StartedProgramClient;
+use crate::state::AppState;
+
+fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
+    headers
+        .get("X-User-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Unauthorized("Missing X-User-Id header".to_string()))
+}
+
+/// POST /programs/:id/weeks - Insert an empty week, shifting the following weeks
+pub async fn insert_week(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(program_id): Path<String>,
+    Json(payload): Json<InsertWeekRequest>,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let operation = LayoutOperation::InsertWeek { at: payload.at };
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let program = apply_layout_operation(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        operation,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
+}
+
+/// DELETE /programs/:id/weeks/:week - Remove a week, shifting the following weeks
+pub async fn remove_week(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, week)): Path<(String, i32)>,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let operation = LayoutOperation::RemoveWeek { week };
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let program = apply_layout_operation(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        operation,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
+}
+
+/// POST /programs/:id/weeks/:week/copy - Copy a week over another week
+pub async fn copy_week(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, week)): Path<(String, i32)>,
+    Json(payload): Json<CopyWeekRequest>,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let operation = LayoutOperation::CopyWeek {
+        week,
+        to_week: payload.to_week,
+    };
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let program = apply_layout_operation(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        operation,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
+}
+
+/// POST /programs/:id/weeks/:week/repeat - Repeat a block of weeks right after itself
+pub async fn repeat_weeks(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, week)): Path<(String, i32)>,
+    Json(payload): Json<RepeatWeeksRequest>,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let operation = LayoutOperation::RepeatWeeks {
+        week,
+        through_week: payload.through_week.unwrap_or(week),
+        times: payload.times,
+    };
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let program = apply_layout_operation(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        operation,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
+}
+
+/// PUT /programs/:id/weeks/:week/order - Reorder the workouts of a week
+pub async fn reorder_workouts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, week)): Path<(String, i32)>,
+    Json(payload): Json<ReorderWorkoutsRequest>,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let operation = LayoutOperation::ReorderWorkouts {
+        week,
+        workout_numbers: payload.workout_numbers,
+    };
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let program = apply_layout_operation(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        operation,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
+}
+
+/// POST /programs/:id/workouts/:workout_number/copy - Duplicate a workout into a week
+pub async fn copy_workout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, workout_number)): Path<(String, i32)>,
+    Json(payload): Json<WorkoutTargetRequest>,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let operation = LayoutOperation::CopyWorkout {
+        workout_number,
+        to_week: payload.to_week,
+    };
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let program = apply_layout_operation(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        operation,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
+}
+
+/// POST /programs/:id/workouts/:workout_number/move - Move a workout to another week
+pub async fn move_workout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, workout_number)): Path<(String, i32)>,
+    Json(payload): Json<WorkoutTargetRequest>,
+) -> Result<(ETag, Json<ProgramResponse>), AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let operation = LayoutOperation::MoveWorkout {
+        workout_number,
+        to_week: payload.to_week,
+    };
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let program = apply_layout_operation(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        operation,
+        expected,
+    )
+    .await?;
+    Ok((etag(program.revision), Json(program)))
+}
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+
+use crate::error::AppError;
+use crate::handlers::revision::expected_revision;
+use crate::models::{ProgressionRequest, ProgressionResponse};
+use crate::services::progression::generate_progression;
+use crate::services::started_program_client::StartedProgramClient;
+use crate::state::AppState;
+
+fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
+    headers
+        .get("X-User-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Unauthorized("Missing X-User-Id header".to_string()))
+}
+
+/// POST /programs/:id/weeks/:week/progression - Generate the following weeks from a progression rule
+/// With `preview: true` the generated weeks are returned without saving
+pub async fn create_progression(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, week)): Path<(String, i32)>,
+    Json(payload): Json<ProgressionRequest>,
+) -> Result<Json<ProgressionResponse>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let expected = expected_revision(&headers)?;
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let response = generate_progression(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        week,
+        payload,
+        expected,
+    )
+    .await?;
+    Ok(Json(response))
+}
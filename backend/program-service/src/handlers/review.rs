@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{
+    ModerateReviewRequest, PaginatedReviewResponse, ReviewQueryParams, ReviewReplyRequest,
+    ReviewResponse, UpsertReviewRequest,
+};
+use crate::services::review;
+use crate::services::started_program_client::StartedProgramClient;
+use crate::state::AppState;
+
+fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
+    headers
+        .get("X-User-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+        .ok_or_else(|| AppError::Unauthorized("Missing X-User-Id header".to_string()))
+}
+
+/// GET /programs/:id/reviews - List visible reviews with pagination
+pub async fn get_reviews(
+    State(state): State<AppState>,
+    Path(program_id): Path<String>,
+    Query(params): Query<ReviewQueryParams>,
+) -> Result<Json<PaginatedReviewResponse>, AppError> {
+    let result = review::get_reviews(&state.collections, &program_id, params).await?;
+    Ok(Json(result))
+}
+
+/// PUT /programs/:id/reviews/mine - Create or edit the caller's review
+pub async fn upsert_review(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(program_id): Path<String>,
+    Json(payload): Json<UpsertReviewRequest>,
+) -> Result<Json<ReviewResponse>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let review = review::upsert_review(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        payload,
+    )
+    .await?;
+    Ok(Json(review))
+}
+
+/// DELETE /programs/:id/reviews/mine - Remove the caller's review
+pub async fn delete_own_review(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(program_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    review::delete_own_review(&state.collections, &user_id, &program_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /programs/:id/reviews/:review_id/reply - Coach reply on a review of their program
+pub async fn reply_to_review(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((program_id, review_id)): Path<(String, String)>,
+    Json(payload): Json<ReviewReplyRequest>,
+) -> Result<Json<ReviewResponse>, AppError> {
+    let user_id = extract_user_id(&headers)?;
+    let review =
+        review::reply_to_review(&state.collections, &user_id, &program_id, &review_id, payload)
+            .await?;
+    Ok(Json(review))
+}
+
+/// PATCH /reviews/:id - Hide or unhide a review (admin)
+pub async fn moderate_review(
+    State(state): State<AppState>,
+    Path(review_id): Path<String>,
+    Json(payload): Json<ModerateReviewRequest>,
+) -> Result<Json<ReviewResponse>, AppError> {
+    let review = review::moderate_review(&state.collections, &review_id, payload).await?;
+    Ok(Json(review))
+}
+
+/// DELETE /reviews/:id - Remove a review (admin)
+pub async fn delete_review(
+    State(state): State<AppState>,
+    Path(review_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    review::delete_review(&state.collections, &review_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,31 @@
+use axum::http::{header, HeaderMap, HeaderName};
+
+use crate::error::AppError;
+
+/// Response header carrying the program revision after a mutation
+pub type ETag = [(HeaderName, String); 1];
+
+pub fn etag(revision: i64) -> ETag {
+    [(header::ETAG, format!("\"{}\"", revision))]
+}
+
+/// Parse the expected revision from `If-Match` (accepts `"5"`, `W/"5"` or `5`)
+pub fn expected_revision(headers: &HeaderMap) -> Result<Option<i64>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+
+    let raw = value
+        .to_str()
+        .map_err(|_| AppError::BadRequest("Invalid If-Match header".to_string()))?
+        .trim();
+    if raw == "*" {
+        return Ok(None);
+    }
+
+    raw.trim_start_matches("W/")
+        .trim_matches('"')
+        .parse::<i64>()
+        .map(Some)
+        .map_err(|_| AppError::BadRequest("If-Match must be a program revision".to_string()))
+}
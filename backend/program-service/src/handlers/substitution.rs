@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{
+    AlternativeResponse, AlternativesQueryParams, CreateSubstitutionRequest, SubstitutionResponse,
+};
+use crate::services::substitution;
+use crate::state::AppState;
+
+/// GET /exercises/:id/alternatives - Ranked replacements, optionally excluding equipment
+pub async fn get_alternatives(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<String>,
+    Query(params): Query<AlternativesQueryParams>,
+) -> Result<Json<Vec<AlternativeResponse>>, AppError> {
+    let alternatives =
+        substitution::get_alternatives(&state.collections, &exercise_id, params).await?;
+    Ok(Json(alternatives))
+}
+
+/// GET /exercises/:id/substitutions - Curated substitutions of an exercise
+pub async fn get_substitutions(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<String>,
+) -> Result<Json<Vec<SubstitutionResponse>>, AppError> {
+    let substitutions = substitution::get_substitutions(&state.collections, &exercise_id).await?;
+    Ok(Json(substitutions))
+}
+
+/// POST /exercises/:id/substitutions - Curate a substitution (admin)
+pub async fn create_substitution(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<String>,
+    Json(payload): Json<CreateSubstitutionRequest>,
+) -> Result<(StatusCode, Json<SubstitutionResponse>), AppError> {
+    let created =
+        substitution::create_substitution(&state.collections, &exercise_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// DELETE /exercises/:id/substitutions/:substitute_id - Remove a curated substitution (admin)
+pub async fn delete_substitution(
+    State(state): State<AppState>,
+    Path((exercise_id, substitute_id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    substitution::delete_substitution(&state.collections, &exercise_id, &substitute_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::error::AppError;
+use crate::models::{CreateTagRequest, TagResponse, UpdateTagRequest};
+use crate::services::tag;
+use crate::state::AppState;
+
+/// POST /tags - Add a tag to the vocabulary
+pub async fn create_tag(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateTagRequest>,
+) -> Result<(StatusCode, Json<TagResponse>), AppError> {
+    let tag = tag::create_tag(&state.collections, payload).await?;
+    Ok((StatusCode::CREATED, Json(tag)))
+}
+
+/// GET /tags - List the tag vocabulary
+pub async fn get_tags(State(state): State<AppState>) -> Result<Json<Vec<TagResponse>>, AppError> {
+    let tags = tag::get_tags(&state.collections).await?;
+    Ok(Json(tags))
+}
+
+/// GET /tags/:id - Get a specific tag
+pub async fn get_tag(
+    State(state): State<AppState>,
+    Path(tag_id): Path<String>,
+) -> Result<Json<TagResponse>, AppError> {
+    let tag = tag::get_tag(&state.collections, &tag_id).await?;
+    Ok(Json(tag))
+}
+
+/// PATCH /tags/:id - Rename a tag or replace its aliases
+pub async fn update_tag(
+    State(state): State<AppState>,
+    Path(tag_id): Path<String>,
+    Json(payload): Json<UpdateTagRequest>,
+) -> Result<Json<TagResponse>, AppError> {
+    let tag = tag::update_tag(&state.collections, &tag_id, payload).await?;
+    Ok(Json(tag))
+}
+
+/// DELETE /tags/:id - Remove a tag from the vocabulary
+pub async fn delete_tag(
+    State(state): State<AppState>,
+    Path(tag_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    tag::delete_tag(&state.collections, &tag_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
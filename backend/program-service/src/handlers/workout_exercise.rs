@@ -6,21 +6,27 @@ use axum::{
 use serde::Deserialize;
 
 use crate::error::AppError;
+use crate::handlers::revision::{etag, expected_revision, ETag};
 use crate::models::{
     DeleteExercisesRequest, DeleteWorkoutsRequest, NextWorkoutResponse, UpsertExercisesRequest,
     UpsertExercisesResponse, WeekResponse,
 };
 use crate::services;
+use crate::services::started_program_client::StartedProgramClient;
 use crate::state::AppState;
 
 #[derive(Deserialize)]
 pub struct WeekQuery {
     pub week: Option<i32>,
+    /// Read from a published version instead of the live program
+    pub version: Option<i32>,
 }
 
 #[derive(Deserialize)]
 pub struct NextWorkoutQuery {
     pub last_workout_number: i32,
+    /// Read from a published version instead of the live program
+    pub version: Option<i32>,
 }
 
 fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
@@ -31,7 +37,7 @@ fn extract_user_id(headers: &HeaderMap) -> Result<String, AppError> {
         .ok_or_else(|| AppError::Unauthorized("Missing X-User-Id header".to_string()))
 }
 
-/// GET /programs/:id/workouts?week=1 - Get workouts for a week
+/// GET /programs/:id/workouts?week=1&version=2 - Get workouts for a week
 pub async fn get_week(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -40,8 +46,17 @@ pub async fn get_week(
 ) -> Result<Json<WeekResponse>, AppError> {
     let user_id = extract_user_id(&headers)?;
     let week = query.week.unwrap_or(1);
-    let response =
-        services::get_week(&state.collections, &user_id, &program_id, week).await?;
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
+    let response = services::get_week(
+        &state.collections,
+        &started_programs,
+        &user_id,
+        &program_id,
+        week,
+        query.version,
+    )
+    .await?;
     Ok(Json(response))
 }
 
@@ -52,12 +67,18 @@ pub async fn upsert_exercises(
     headers: HeaderMap,
     Path(program_id): Path<String>,
     Json(payload): Json<UpsertExercisesRequest>,
-) -> Result<Json<UpsertExercisesResponse>, AppError> {
+) -> Result<(ETag, Json<UpsertExercisesResponse>), AppError> {
     let user_id = extract_user_id(&headers)?;
-    let response =
-        services::upsert_exercises(&state.collections, &user_id, &program_id, payload)
-            .await?;
-    Ok(Json(response))
+    let expected = expected_revision(&headers)?;
+    let response = services::upsert_exercises(
+        &state.collections,
+        &user_id,
+        &program_id,
+        payload,
+        expected,
+    )
+    .await?;
+    Ok((etag(response.revision), Json(response)))
 }
 
 /// DELETE /programs/:id/workouts - Delete workouts by workout_numbers
@@ -66,10 +87,13 @@ pub async fn delete_workouts(
     headers: HeaderMap,
     Path(program_id): Path<String>,
     Json(payload): Json<DeleteWorkoutsRequest>,
-) -> Result<StatusCode, AppError> {
+) -> Result<(StatusCode, ETag), AppError> {
     let user_id = extract_user_id(&headers)?;
-    services::delete_workouts(&state.collections, &user_id, &program_id, payload).await?;
-    Ok(StatusCode::NO_CONTENT)
+    let expected = expected_revision(&headers)?;
+    let revision =
+        services::delete_workouts(&state.collections, &user_id, &program_id, payload, expected)
+            .await?;
+    Ok((StatusCode::NO_CONTENT, etag(revision)))
 }
 
 /// DELETE /programs/:id/workout-exercises - Delete exercises by IDs
@@ -78,31 +102,35 @@ pub async fn delete_exercises(
     headers: HeaderMap,
     Path(program_id): Path<String>,
     Json(payload): Json<DeleteExercisesRequest>,
-) -> Result<StatusCode, AppError> {
+) -> Result<(StatusCode, ETag), AppError> {
     let user_id = extract_user_id(&headers)?;
-    services::delete_exercises(&state.collections, &user_id, &program_id, payload).await?;
-    Ok(StatusCode::NO_CONTENT)
+    let expected = expected_revision(&headers)?;
+    let revision =
+        services::delete_exercises(&state.collections, &user_id, &program_id, payload, expected)
+            .await?;
+    Ok((StatusCode::NO_CONTENT, etag(revision)))
 }
 
-/// GET /programs/:id/next-workout?last_workout_number=0 - Get next workout
-/// Returns 404 if no more workouts exist
+/// GET /programs/:id/next-workout?last_workout_number=0&version=2 - Get next workout
+/// Returns null if no more workouts exist, 404 if the program itself is gone
 pub async fn get_next_workout(
     State(state): State<AppState>,
     headers: HeaderMap,
     Path(program_id): Path<String>,
     Query(query): Query<NextWorkoutQuery>,
-) -> Result<Json<NextWorkoutResponse>, AppError> {
+) -> Result<Json<Option<NextWorkoutResponse>>, AppError> {
     let user_id = extract_user_id(&headers)?;
+    let started_programs =
+        StartedProgramClient::new(&state.http_client, &state.started_program_service_url);
     let response = services::get_next_workout(
         &state.collections,
+        &started_programs,
         &user_id,
         &program_id,
         query.last_workout_number,
+        query.version,
     )
     .await?;
 
-    match response {
-        Some(workout) => Ok(Json(workout)),
-        None => Err(AppError::NotFound("No more workouts".to_string())),
-    }
+    Ok(Json(response))
 }
@@ -1,8 +1,17 @@
 use axum::{
-    routing::{get, post, put},
+    routing::{delete, get, patch, post, put},
     Router,
 };
-use mongodb::{bson::doc, Client, IndexModel};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Document},
+    options::IndexOptions,
+    Client, IndexModel,
+};
+use reqwest::Client as HttpClient;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tower_http::cors::{Any, CorsLayer};
 
 mod config;
@@ -16,11 +25,21 @@ mod state;
 use config::AppConfig;
 use db::Collections;
 use handlers::{
-    create_exercise, create_program, delete_exercise, delete_exercises, delete_program,
-    delete_workouts, get_exercise, get_exercises, get_next_workout, get_program, get_programs,
-    get_week, search_public_programs, search_user_programs, update_exercise, update_program,
-    upload_program_image, upsert_exercises,
+    complete_local_payment, copy_week, copy_workout, create_exercise, create_program,
+    create_progression, create_substitution, create_tag, delete_exercise, delete_exercises,
+    delete_own_review, delete_program, delete_review, delete_substitution, delete_tag,
+    delete_workouts, export_exercises, export_program, fork_program, get_alternatives,
+    get_entitlement_status, get_exercise, get_exercises, get_file, get_muscles, get_next_workout,
+    get_program, get_programs, get_reviews, get_substitutions, get_tag, get_tags,
+    get_user_entitlements, get_user_payments, get_week, import_exercises, import_program,
+    insert_week, moderate_review, move_workout, payment_webhook, public_program_facets,
+    purchase_program, record_program_start, remove_week, reorder_workouts, repeat_weeks,
+    reply_to_review, resync_exercises, search_public_programs, search_user_programs,
+    update_exercise, update_program, update_tag, upload_exercise_image, upload_program_image,
+    upsert_exercises, upsert_review, user_program_facets,
 };
+use services::payment::{LocalPaymentProvider, PaymentProvider};
+use services::storage::{self, LocalStorage, ObjectStorage, S3Storage};
 use state::AppState;
 
 #[tokio::main]
@@ -41,18 +60,91 @@ async fn main() {
 
     // Create indexes
     create_indexes(&collections).await;
+    migrate_image_urls(&collections).await;
+
+    // Start RabbitMQ consumer for user lifecycle events
+    services::rabbitmq::start_user_events_consumer(&config.rabbitmq_url, collections.clone())
+        .await
+        .expect("Failed to start RabbitMQ consumer");
+
+    // Answer catalog resync requests from services with an empty read model
+    services::rabbitmq::start_catalog_resync_consumer(&config.rabbitmq_url, collections.clone())
+        .await
+        .expect("Failed to start RabbitMQ consumer");
+
+    // Relay exercise catalog events from the outbox to RabbitMQ
+    services::outbox::start_outbox_relay(collections.clone(), config.rabbitmq_url.clone());
+
+    // Setup object storage for program images
+    let (storage, default_public_url): (Arc<dyn ObjectStorage>, String) =
+        match config.storage_backend.as_str() {
+            "s3" => {
+                let aws_config = aws_config::from_env()
+                    .region(aws_config::Region::new(config.aws_region.clone()))
+                    .load()
+                    .await;
+                let mut s3_config = aws_sdk_s3::config::Builder::from(&aws_config);
+                if let Some(endpoint) = &config.s3_endpoint {
+                    s3_config = s3_config.endpoint_url(endpoint).force_path_style(true);
+                }
+                let s3_client = aws_sdk_s3::Client::from_conf(s3_config.build());
 
-    // Setup AWS S3 client
-    let aws_config = aws_config::from_env()
-        .region(aws_config::Region::new(config.aws_region.clone()))
-        .load()
-        .await;
-    let s3_client = aws_sdk_s3::Client::new(&aws_config);
+                let public_url = match &config.s3_endpoint {
+                    Some(endpoint) => {
+                        format!("{}/{}", endpoint.trim_end_matches('/'), config.s3_bucket_name)
+                    }
+                    None => format!(
+                        "https://{}.s3.{}.amazonaws.com",
+                        config.s3_bucket_name, config.aws_region
+                    ),
+                };
+                let storage = S3Storage::new(s3_client, config.s3_bucket_name.clone());
+                (Arc::new(storage), public_url)
+            }
+            "local" => {
+                let storage = LocalStorage::new(PathBuf::from(&config.local_storage_dir));
+                (Arc::new(storage), "http://localhost:3000/files".to_string())
+            }
+            other => panic!("Unsupported storage backend: {}", other),
+        };
+    storage::init_public_base_url(
+        config
+            .storage_public_url
+            .clone()
+            .unwrap_or(default_public_url),
+    );
+
+    let http_client = HttpClient::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("Failed to create HTTP client");
+
+    // The simulated checkout lets buyers mark their own payments paid, so it needs the
+    // local provider and an explicit development flag
+    let local_checkout =
+        config.payment_provider == LocalPaymentProvider::NAME && config.payment_local_checkout;
+    let local_payment_routes = if local_checkout {
+        Router::new().route(
+            "/payments/local/{reference}/complete",
+            post(complete_local_payment),
+        )
+    } else {
+        Router::new()
+    };
+
+    let payment_provider: Arc<dyn PaymentProvider> = match config.payment_provider.as_str() {
+        LocalPaymentProvider::NAME => {
+            Arc::new(LocalPaymentProvider::new(config.payment_webhook_secret, local_checkout))
+        }
+        other => panic!("Unsupported payment provider: {}", other),
+    };
 
     let state = AppState {
         collections,
-        s3_client,
-        s3_bucket: config.s3_bucket_name,
+        storage,
+        http_client,
+        started_program_service_url: config.started_program_service_url,
+        payment_provider,
     };
 
     // CORS configuration
@@ -65,30 +157,102 @@ async fn main() {
         // Program search routes (must be before parameterized routes)
         .route("/programs/search/public", get(search_public_programs))
         .route("/programs/search/mine", get(search_user_programs))
+        .route("/programs/search/public/facets", get(public_program_facets))
+        .route("/programs/search/mine/facets", get(user_program_facets))
         // Program routes
         .route("/programs", get(get_programs).post(create_program))
+        .route("/programs/import", post(import_program))
         .route(
             "/programs/{program_id}",
             get(get_program).patch(update_program).delete(delete_program),
         )
         .route("/programs/{program_id}/image", post(upload_program_image))
+        .route("/programs/{program_id}/fork", post(fork_program))
+        .route("/programs/{program_id}/export", get(export_program))
         // Next workout route (for started-program-service)
         .route(
             "/programs/{program_id}/next-workout",
             get(get_next_workout),
         )
+        // Start counter route (for started-program-service)
+        .route("/programs/{program_id}/starts", post(record_program_start))
+        // Purchase and entitlement routes
+        .route("/programs/{program_id}/purchase", post(purchase_program))
+        .route(
+            "/programs/{program_id}/entitlement",
+            get(get_entitlement_status),
+        )
+        .route("/entitlements/mine", get(get_user_entitlements))
+        .route("/payments/mine", get(get_user_payments))
+        .route("/payments/webhook", post(payment_webhook))
+        .merge(local_payment_routes)
+        // Review routes
+        .route("/programs/{program_id}/reviews", get(get_reviews))
+        .route(
+            "/programs/{program_id}/reviews/mine",
+            put(upsert_review).delete(delete_own_review),
+        )
+        .route(
+            "/programs/{program_id}/reviews/{review_id}/reply",
+            put(reply_to_review),
+        )
+        .route(
+            "/reviews/{review_id}",
+            patch(moderate_review).delete(delete_review),
+        )
         // Workout exercise routes
         .route("/programs/{program_id}/workouts", get(get_week).delete(delete_workouts))
         .route(
             "/programs/{program_id}/workout-exercises",
             put(upsert_exercises).delete(delete_exercises),
         )
+        // Week and workout layout routes (renumbered server-side)
+        .route("/programs/{program_id}/weeks", post(insert_week))
+        .route("/programs/{program_id}/weeks/{week}", delete(remove_week))
+        .route("/programs/{program_id}/weeks/{week}/copy", post(copy_week))
+        .route("/programs/{program_id}/weeks/{week}/repeat", post(repeat_weeks))
+        .route("/programs/{program_id}/weeks/{week}/order", put(reorder_workouts))
+        .route(
+            "/programs/{program_id}/weeks/{week}/progression",
+            post(create_progression),
+        )
+        .route(
+            "/programs/{program_id}/workouts/{workout_number}/copy",
+            post(copy_workout),
+        )
+        .route(
+            "/programs/{program_id}/workouts/{workout_number}/move",
+            post(move_workout),
+        )
+        // Stored files (program and exercise images)
+        .route("/files/{*key}", get(get_file))
         // Exercise catalog routes
         .route("/exercises", get(get_exercises).post(create_exercise))
+        .route("/exercises/export", get(export_exercises))
+        .route("/exercises/import", post(import_exercises))
+        .route("/exercises/resync", post(resync_exercises))
         .route(
             "/exercises/{exercise_id}",
             get(get_exercise).patch(update_exercise).delete(delete_exercise),
         )
+        .route("/exercises/{exercise_id}/image", post(upload_exercise_image))
+        .route("/exercises/{exercise_id}/alternatives", get(get_alternatives))
+        .route(
+            "/exercises/{exercise_id}/substitutions",
+            get(get_substitutions).post(create_substitution),
+        )
+        .route(
+            "/exercises/{exercise_id}/substitutions/{substitute_id}",
+            delete(delete_substitution),
+        )
+        // Muscle registry
+        .route("/muscles", get(get_muscles))
+        // Tag vocabulary routes
+        .route("/tags", get(get_tags).post(create_tag))
+        .route(
+            "/tags/{tag_id}",
+            get(get_tag).patch(update_tag).delete(delete_tag),
+        )
         .layer(cors)
         .with_state(state);
 
@@ -100,6 +264,39 @@ async fn main() {
     axum::serve(listener, app).await.unwrap();
 }
 
+/// Convert image URLs stored before programs kept object keys
+async fn migrate_image_urls(collections: &Collections) {
+    let programs = collections.programs.clone_with_type::<Document>();
+    let Ok(mut cursor) = programs
+        .find(doc! { "image_url": { "$type": "string" } })
+        .await
+    else {
+        return;
+    };
+
+    let mut migrated = 0;
+    while let Ok(Some(program)) = cursor.try_next().await {
+        let Ok(id) = program.get_object_id("_id") else {
+            continue;
+        };
+        let key = program
+            .get_str("image_url")
+            .ok()
+            .and_then(storage::key_from_legacy_url);
+        let update = match key {
+            Some(key) => doc! { "$set": { "image_key": key }, "$unset": { "image_url": "" } },
+            None => doc! { "$unset": { "image_url": "" } },
+        };
+        if programs.update_one(doc! { "_id": id }, update).await.is_ok() {
+            migrated += 1;
+        }
+    }
+
+    if migrated > 0 {
+        println!("Migrated {} program image URLs to object keys", migrated);
+    }
+}
+
 async fn create_indexes(collections: &Collections) {
     // Index for workout_exercises: program_id + week
     let exercise_index = IndexModel::builder()
@@ -134,5 +331,152 @@ async fn create_indexes(collections: &Collections) {
         .await
         .ok();
 
+    // Text index for program search, weighted toward name and tags
+    let program_text_index = IndexModel::builder()
+        .keys(doc! { "name": "text", "tags": "text", "description": "text" })
+        .options(
+            IndexOptions::builder()
+                .weights(doc! { "name": 10, "tags": 5, "description": 1 })
+                .name("program_text".to_string())
+                .build(),
+        )
+        .build();
+
+    collections
+        .programs
+        .create_index(program_text_index)
+        .await
+        .ok();
+
+    // Index for tag filters and facets: tags
+    let program_tags_index = IndexModel::builder().keys(doc! { "tags": 1 }).build();
+
+    collections
+        .programs
+        .create_index(program_tags_index)
+        .await
+        .ok();
+
+    // Index for resolving tag aliases on save
+    let tag_alias_index = IndexModel::builder().keys(doc! { "aliases": 1 }).build();
+
+    collections.tags.create_index(tag_alias_index).await.ok();
+
+    // Indexes for exercise catalog filters: muscle, equipment, movement pattern
+    let exercise_muscle_index = IndexModel::builder()
+        .keys(doc! { "muscles.muscle": 1, "muscles.intensity": 1 })
+        .build();
+    let exercise_equipment_index = IndexModel::builder().keys(doc! { "equipment": 1 }).build();
+    let exercise_pattern_index = IndexModel::builder()
+        .keys(doc! { "movement_pattern": 1 })
+        .build();
+
+    collections
+        .exercises
+        .create_indexes(vec![
+            exercise_muscle_index,
+            exercise_equipment_index,
+            exercise_pattern_index,
+        ])
+        .await
+        .ok();
+
+    // Unique index for curated substitutions, plus the reverse direction for lookups
+    let substitution_index = IndexModel::builder()
+        .keys(doc! { "exercise_id": 1, "substitute_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+    let substitution_reverse_index = IndexModel::builder()
+        .keys(doc! { "substitute_id": 1 })
+        .build();
+
+    collections
+        .substitutions
+        .create_indexes(vec![substitution_index, substitution_reverse_index])
+        .await
+        .ok();
+
+    // Index for the outbox relay: pending events in order
+    let outbox_pending_index = IndexModel::builder()
+        .keys(doc! { "published_at": 1, "_id": 1 })
+        .build();
+
+    collections
+        .outbox_events
+        .create_index(outbox_pending_index)
+        .await
+        .ok();
+
+    // Index for reference checks before deleting an exercise
+    let workout_exercise_catalog_index = IndexModel::builder()
+        .keys(doc! { "exercise_id": 1 })
+        .build();
+
+    collections
+        .workout_exercises
+        .create_index(workout_exercise_catalog_index)
+        .await
+        .ok();
+
+    // Unique index for reviews: one per user per program
+    let review_user_index = IndexModel::builder()
+        .keys(doc! { "program_id": 1, "user_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collections
+        .reviews
+        .create_index(review_user_index)
+        .await
+        .ok();
+
+    // Unique index for entitlements: one per user per program
+    let entitlement_index = IndexModel::builder()
+        .keys(doc! { "program_id": 1, "user_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collections
+        .entitlements
+        .create_index(entitlement_index)
+        .await
+        .ok();
+
+    // Unique index for payments: provider + provider reference (webhook lookup)
+    let payment_reference_index = IndexModel::builder()
+        .keys(doc! { "provider": 1, "provider_reference": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collections
+        .payments
+        .create_index(payment_reference_index)
+        .await
+        .ok();
+
+    // Unique index for program versions: program_id + version
+    let program_version_index = IndexModel::builder()
+        .keys(doc! { "program_id": 1, "version": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collections
+        .program_versions
+        .create_index(program_version_index)
+        .await
+        .ok();
+
+    // Unique index for the program change log: program_id + revision
+    let program_change_index = IndexModel::builder()
+        .keys(doc! { "program_id": 1, "revision": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collections
+        .program_changes
+        .create_index(program_change_index)
+        .await
+        .ok();
+
     println!("Database indexes created");
 }
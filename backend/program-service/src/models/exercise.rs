@@ -1,11 +1,93 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use super::image::ImageVariants;
+use super::program::Difficulty;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct MuscleIntensity {
     pub muscle: String,
     pub intensity: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Equipment {
+    Barbell,
+    Dumbbell,
+    Kettlebell,
+    Machine,
+    Cable,
+    Band,
+    Bodyweight,
+    Other,
+}
+
+impl Equipment {
+    pub const ALL: [Equipment; 8] = [
+        Equipment::Barbell,
+        Equipment::Dumbbell,
+        Equipment::Kettlebell,
+        Equipment::Machine,
+        Equipment::Cable,
+        Equipment::Band,
+        Equipment::Bodyweight,
+        Equipment::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Equipment::Barbell => "barbell",
+            Equipment::Dumbbell => "dumbbell",
+            Equipment::Kettlebell => "kettlebell",
+            Equipment::Machine => "machine",
+            Equipment::Cable => "cable",
+            Equipment::Band => "band",
+            Equipment::Bodyweight => "bodyweight",
+            Equipment::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementPattern {
+    Squat,
+    Hinge,
+    Lunge,
+    Push,
+    Pull,
+    Carry,
+    Rotation,
+    Isolation,
+}
+
+impl MovementPattern {
+    pub const ALL: [MovementPattern; 8] = [
+        MovementPattern::Squat,
+        MovementPattern::Hinge,
+        MovementPattern::Lunge,
+        MovementPattern::Push,
+        MovementPattern::Pull,
+        MovementPattern::Carry,
+        MovementPattern::Rotation,
+        MovementPattern::Isolation,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MovementPattern::Squat => "squat",
+            MovementPattern::Hinge => "hinge",
+            MovementPattern::Lunge => "lunge",
+            MovementPattern::Push => "push",
+            MovementPattern::Pull => "pull",
+            MovementPattern::Carry => "carry",
+            MovementPattern::Rotation => "rotation",
+            MovementPattern::Isolation => "isolation",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Exercise {
     #[serde(rename = "_id")]
@@ -13,6 +95,24 @@ pub struct Exercise {
     pub name: String,
     pub demonstration_link: String,
     pub muscles: Vec<MuscleIntensity>,
+    /// Object storage keys of the resized exercise image
+    #[serde(default)]
+    pub image_variants: Option<ImageVariants>,
+    #[serde(default)]
+    pub equipment: Option<Equipment>,
+    #[serde(default)]
+    pub movement_pattern: Option<MovementPattern>,
+    /// Trained one side at a time
+    #[serde(default)]
+    pub unilateral: bool,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    /// Other names the exercise is searched by (e.g. "RDL")
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Hidden from the catalog but kept so existing programs and history still resolve
+    #[serde(default)]
+    pub deprecated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,6 +121,11 @@ pub struct CreateExerciseRequest {
     pub name: String,
     pub demonstration_link: String,
     pub muscles: Vec<MuscleIntensity>,
+    pub equipment: Option<Equipment>,
+    pub movement_pattern: Option<MovementPattern>,
+    pub unilateral: Option<bool>,
+    pub difficulty: Option<Difficulty>,
+    pub aliases: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,6 +133,13 @@ pub struct UpdateExerciseRequest {
     pub name: Option<String>,
     pub demonstration_link: Option<String>,
     pub muscles: Option<Vec<MuscleIntensity>>,
+    pub equipment: Option<Equipment>,
+    pub movement_pattern: Option<MovementPattern>,
+    pub unilateral: Option<bool>,
+    pub difficulty: Option<Difficulty>,
+    pub aliases: Option<Vec<String>>,
+    /// false restores a deprecated exercise to the catalog
+    pub deprecated: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,13 +148,44 @@ pub struct ExerciseResponse {
     pub name: String,
     pub demonstration_link: String,
     pub muscles: Vec<MuscleIntensity>,
+    /// Full-size image URL
+    pub image_url: Option<String>,
+    pub image_variants: Option<ImageVariants>,
+    pub equipment: Option<Equipment>,
+    pub movement_pattern: Option<MovementPattern>,
+    pub unilateral: bool,
+    pub difficulty: Option<Difficulty>,
+    pub aliases: Vec<String>,
+    pub deprecated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ExerciseQueryParams {
+    /// Matches the name or an alias; results are ranked by how closely the name matches
     pub search: Option<String>,
     pub page: Option<u64>,
     pub limit: Option<u64>,
+    /// Muscle name from the registry
+    pub muscle: Option<String>,
+    /// Minimum intensity for the muscle filter (0.0-1.0)
+    pub min_intensity: Option<f64>,
+    /// Comma-separated equipment, any of which matches
+    pub equipment: Option<String>,
+    /// Comma-separated movement patterns, any of which matches
+    pub pattern: Option<String>,
+    pub unilateral: Option<bool>,
+    pub difficulty: Option<Difficulty>,
+    /// Also list deprecated exercises (admin catalog management)
+    pub include_deprecated: Option<bool>,
+}
+
+/// How DELETE /exercises/{id} treats an exercise that is still referenced
+#[derive(Debug, Deserialize)]
+pub struct DeleteExerciseQuery {
+    /// Hide the exercise from the catalog instead of deleting it
+    pub deprecate: Option<bool>,
+    /// Move every reference to this exercise, then delete it
+    pub merge_into: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -56,11 +199,20 @@ pub struct PaginatedExerciseResponse {
 
 impl From<Exercise> for ExerciseResponse {
     fn from(e: Exercise) -> Self {
+        let image_variants = e.image_variants.map(|image| image.to_urls());
         Self {
             id: e.id,
             name: e.name,
             demonstration_link: e.demonstration_link,
             muscles: e.muscles,
+            image_url: image_variants.as_ref().map(|image| image.full.clone()),
+            image_variants,
+            equipment: e.equipment,
+            movement_pattern: e.movement_pattern,
+            unilateral: e.unilateral,
+            difficulty: e.difficulty,
+            aliases: e.aliases,
+            deprecated_at: e.deprecated_at,
         }
     }
 }
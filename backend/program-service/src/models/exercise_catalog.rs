@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::exercise::{Equipment, Exercise, MovementPattern, MuscleIntensity};
+use super::program::Difficulty;
+
+pub const CATALOG_FORMAT: &str = "exercise-catalog";
+pub const CATALOG_FORMAT_VERSION: i32 = 1;
+
+/// Whole exercise catalog as exported, and accepted back by the import
+#[derive(Debug, Serialize)]
+pub struct ExerciseCatalog {
+    pub format: String,
+    pub format_version: i32,
+    pub exported_at: DateTime<Utc>,
+    pub exercises: Vec<CatalogExercise>,
+}
+
+/// Catalog fields of one exercise (images are not part of the catalog)
+/// On import the ID wins, then the name is matched case-insensitively
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CatalogExercise {
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub demonstration_link: String,
+    pub muscles: Vec<MuscleIntensity>,
+    #[serde(default)]
+    pub equipment: Option<Equipment>,
+    #[serde(default)]
+    pub movement_pattern: Option<MovementPattern>,
+    #[serde(default)]
+    pub unilateral: bool,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// One CSV row per exercise
+/// muscles: "Chest:1.0;Triceps:0.5", aliases: "Bench;Flat Bench"
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExerciseCsvRow {
+    pub id: Option<String>,
+    pub name: String,
+    pub demonstration_link: Option<String>,
+    pub muscles: Option<String>,
+    pub equipment: Option<Equipment>,
+    pub movement_pattern: Option<MovementPattern>,
+    pub unilateral: Option<bool>,
+    pub difficulty: Option<Difficulty>,
+    pub aliases: Option<String>,
+}
+
+/// Result of POST /exercises/resync
+#[derive(Debug, Serialize)]
+pub struct CatalogResyncResponse {
+    /// Exercises included in the published snapshot
+    pub exercises: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExerciseImportQuery {
+    /// Defaults to true: nothing is written until the import is repeated with dry_run=false
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Create,
+    Update,
+    Unchanged,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowResult {
+    /// 1-based; CSV rows count the header like a spreadsheet does
+    pub row: usize,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub action: ImportAction,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExerciseImportReport {
+    pub dry_run: bool,
+    /// False for dry runs and for imports refused because a row failed
+    pub applied: bool,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportRowResult>,
+}
+
+impl From<Exercise> for CatalogExercise {
+    fn from(e: Exercise) -> Self {
+        Self {
+            id: Some(e.id),
+            name: e.name,
+            demonstration_link: e.demonstration_link,
+            muscles: e.muscles,
+            equipment: e.equipment,
+            movement_pattern: e.movement_pattern,
+            unilateral: e.unilateral,
+            difficulty: e.difficulty,
+            aliases: e.aliases,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::exercise_catalog::CatalogExercise;
+
+/// Exercise catalog events published to other services through the outbox
+/// Created/Updated carry the full catalog entry so consumers can keep a read model
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum ExerciseEvent {
+    ExerciseCreated {
+        exercise: CatalogExercise,
+    },
+    ExerciseUpdated {
+        exercise: CatalogExercise,
+    },
+    ExerciseDeleted {
+        exercise_id: String,
+    },
+    /// Every reference to `source_id` now points at `target_id`; the source no longer exists
+    ExerciseMerged {
+        source_id: String,
+        target_id: String,
+    },
+    /// Whole catalog; consumers replace their read model with it (bootstrapping)
+    CatalogSynced {
+        exercises: Vec<CatalogExercise>,
+    },
+}
+
+impl ExerciseEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            ExerciseEvent::ExerciseCreated { .. } => "ExerciseCreated",
+            ExerciseEvent::ExerciseUpdated { .. } => "ExerciseUpdated",
+            ExerciseEvent::ExerciseDeleted { .. } => "ExerciseDeleted",
+            ExerciseEvent::ExerciseMerged { .. } => "ExerciseMerged",
+            ExerciseEvent::CatalogSynced { .. } => "CatalogSynced",
+        }
+    }
+
+    /// Routing key on the exercise events topic exchange
+    pub fn routing_key(&self) -> &'static str {
+        match self {
+            ExerciseEvent::ExerciseCreated { .. } => "exercise.created",
+            ExerciseEvent::ExerciseUpdated { .. } => "exercise.updated",
+            ExerciseEvent::ExerciseDeleted { .. } => "exercise.deleted",
+            ExerciseEvent::ExerciseMerged { .. } => "exercise.merged",
+            ExerciseEvent::CatalogSynced { .. } => "exercise.catalog_synced",
+        }
+    }
+}
+
+/// Event waiting in the outbox to be published
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutboxEvent {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub event_type: String,
+    pub routing_key: String,
+    /// JSON payload as sent to RabbitMQ
+    pub payload: String,
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::storage::public_url;
+
+/// Resized copies of an uploaded image; object keys when stored, URLs in responses
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImageVariants {
+    /// Square crop for list rows
+    pub thumbnail: String,
+    /// Mid-size for cards and previews
+    pub card: String,
+    /// Largest copy, for detail screens
+    pub full: String,
+}
+
+impl ImageVariants {
+    /// A single object used for every size (images uploaded before variants existed)
+    pub fn single(key: &str) -> Self {
+        Self {
+            thumbnail: key.to_string(),
+            card: key.to_string(),
+            full: key.to_string(),
+        }
+    }
+
+    /// Distinct object keys, for cleanup
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys = vec![self.thumbnail.as_str(), self.card.as_str(), self.full.as_str()];
+        keys.dedup();
+        keys
+    }
+
+    /// Map stored keys to public URLs
+    pub fn to_urls(&self) -> Self {
+        Self {
+            thumbnail: public_url(&self.thumbnail),
+            card: public_url(&self.card),
+            full: public_url(&self.full),
+        }
+    }
+}
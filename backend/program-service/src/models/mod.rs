@@ -1,17 +1,65 @@
 pub mod exercise;
+pub mod exercise_catalog;
+pub mod exercise_event;
+pub mod image;
+pub mod muscle;
+pub mod payment;
 pub mod program;
+pub mod program_change;
+pub mod program_export;
+pub mod program_layout;
+pub mod program_version;
+pub mod progression;
+pub mod review;
+pub mod substitution;
+pub mod tag;
+pub mod validation;
 pub mod workout_exercise;
 
 pub use exercise::{
-    CreateExerciseRequest, Exercise, ExerciseQueryParams, ExerciseResponse, MuscleIntensity,
-    PaginatedExerciseResponse, UpdateExerciseRequest,
+    CreateExerciseRequest, DeleteExerciseQuery, Equipment, Exercise, ExerciseQueryParams,
+    ExerciseResponse, MovementPattern, MuscleIntensity, PaginatedExerciseResponse,
+    UpdateExerciseRequest,
+};
+pub use exercise_catalog::{
+    CatalogExercise, CatalogResyncResponse, ExerciseCatalog, ExerciseCsvRow, ExerciseImportQuery,
+    ExerciseImportReport, ImportAction, ImportRowResult,
+};
+pub use exercise_event::{ExerciseEvent, OutboxEvent};
+pub use image::ImageVariants;
+pub use muscle::Muscle;
+pub use payment::{
+    CompleteLocalPaymentRequest, Entitlement, EntitlementResponse, EntitlementStatusResponse,
+    Payment, PaymentResponse, PaymentStatus, Price, PurchaseResponse,
 };
 pub use program::{
-    CreateProgramRequest, CreateProgramResponse, PaginatedProgramResponse, Program,
-    ProgramResponse, ProgramSearchParams, UpdateProgramRequest,
+    CreateProgramRequest, CreateProgramResponse, FacetCount, PaginatedProgramResponse, Program,
+    ProgramFacetsResponse, ProgramResponse, ProgramSearchParams, ProgramSort, TagsMode,
+    UpdateProgramRequest,
+};
+pub use program_change::{ChangeDetail, ProgramChange, ProgramChangeResponse};
+pub use program_export::{
+    ExerciseReference, ExportQuery, ExportedExercise, ExportedProgram, ExportedWeek,
+    ExportedWorkout, ImportQuery, ProgramCsvRow, ProgramExport, EXPORT_FORMAT,
+    EXPORT_FORMAT_VERSION,
+};
+pub use program_layout::{
+    CopyWeekRequest, InsertWeekRequest, RepeatWeeksRequest, ReorderWorkoutsRequest,
+    WorkoutTargetRequest,
+};
+pub use program_version::ProgramVersion;
+pub use progression::{DeloadRule, ProgressionRequest, ProgressionResponse, ProgressionRule};
+pub use review::{
+    ModerateReviewRequest, PaginatedReviewResponse, Review, ReviewQueryParams, ReviewReplyRequest,
+    ReviewResponse, UpsertReviewRequest,
+};
+pub use substitution::{
+    AlternativeResponse, AlternativesQueryParams, CreateSubstitutionRequest, Substitution,
+    SubstitutionResponse,
 };
+pub use tag::{CreateTagRequest, Tag, TagResponse, UpdateTagRequest};
+pub use validation::ValidationError;
 pub use workout_exercise::{
     DeleteExercisesRequest, DeleteWorkoutsRequest, IdMapping, NextWorkoutResponse, Set,
-    UpsertExercisesRequest, UpsertExercisesResponse, WeekResponse, WorkoutExercise,
-    WorkoutExerciseResponse, WorkoutGroup,
+    UpsertExercisesRequest, UpsertExercisesResponse, WeekResponse, WorkoutExercise, WorkoutGroup,
 };
@@ -0,0 +1,34 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyRegion {
+    Neck,
+    Shoulders,
+    Arms,
+    Chest,
+    Core,
+    Back,
+    Legs,
+}
+
+/// Side of the body diagram a muscle is drawn on
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyView {
+    Front,
+    Back,
+    Both,
+}
+
+/// Entry of the canonical muscle registry
+/// Exercises store the display name in `MuscleIntensity.muscle`
+#[derive(Debug, Serialize)]
+pub struct Muscle {
+    pub id: u32,
+    pub name: &'static str,
+    pub region: BodyRegion,
+    pub view: BodyView,
+    /// Body highlighter slugs (left/right and front/back variants)
+    pub slugs: &'static [&'static str],
+}
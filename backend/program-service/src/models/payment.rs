@@ -0,0 +1,117 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Program price in minor units; programs without a price are free
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Price {
+    pub amount_cents: i64,
+    /// ISO 4217 code, e.g. "EUR"
+    pub currency: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+impl PaymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentStatus::Pending => "pending",
+            PaymentStatus::Succeeded => "succeeded",
+            PaymentStatus::Failed => "failed",
+        }
+    }
+}
+
+/// One purchase attempt, tracked through the provider's webhooks
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Payment {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub program_id: String,
+    pub user_id: String,
+    pub provider: String,
+    pub provider_reference: String,
+    pub price: Price,
+    pub status: PaymentStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Permission for a user to start a paid program
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Entitlement {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub program_id: String,
+    pub user_id: String,
+    /// Payment that granted it (None for grants)
+    pub payment_id: Option<String>,
+    pub granted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurchaseResponse {
+    pub payment_id: String,
+    pub status: PaymentStatus,
+    pub price: Price,
+    /// Where the client completes the payment
+    pub checkout_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompleteLocalPaymentRequest {
+    pub succeeded: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaymentResponse {
+    pub id: String,
+    pub program_id: String,
+    pub status: PaymentStatus,
+    pub price: Price,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntitlementStatusResponse {
+    pub program_id: String,
+    pub entitled: bool,
+    pub price: Option<Price>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntitlementResponse {
+    pub program_id: String,
+    pub payment_id: Option<String>,
+    pub granted_at: DateTime<Utc>,
+}
+
+impl From<Payment> for PaymentResponse {
+    fn from(p: Payment) -> Self {
+        Self {
+            id: p.id.to_hex(),
+            program_id: p.program_id,
+            status: p.status,
+            price: p.price,
+            created_at: p.created_at,
+            updated_at: p.updated_at,
+        }
+    }
+}
+
+impl From<Entitlement> for EntitlementResponse {
+    fn from(e: Entitlement) -> Self {
+        Self {
+            program_id: e.program_id,
+            payment_id: e.payment_id,
+            granted_at: e.granted_at,
+        }
+    }
+}
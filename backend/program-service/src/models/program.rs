@@ -2,6 +2,8 @@ use bson::oid::ObjectId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::image::ImageVariants;
+use super::payment::Price;
 use super::workout_exercise::IdMapping;
 
 /// Program model for MongoDB storage - uses native ObjectId
@@ -12,13 +14,67 @@ pub struct Program {
     pub user_id: String,
     pub name: String,
     pub description: Option<String>,
-    pub image_url: Option<String>,
+    /// Object storage key of a cover image uploaded before variants were generated
+    #[serde(default)]
+    pub image_key: Option<String>,
+    /// Object storage keys of the resized cover image
+    #[serde(default)]
+    pub image_variants: Option<ImageVariants>,
     pub tags: Vec<String>,
     pub total_weeks: i32,
     pub last_workout_number: i32,
     pub public: bool,
     pub created_at: Option<DateTime<Utc>>, // None = draft, Some = published
     pub updated_at: DateTime<Utc>,
+    /// Latest published version (None = never published)
+    #[serde(default)]
+    pub current_version: Option<i32>,
+    /// Archived programs are hidden from search and new starts
+    #[serde(default)]
+    pub archived: bool,
+    /// Soft-delete marker; workouts stay readable for athletes already running the program
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Owner's account was disabled or deleted: hidden from search, new starts and purchases
+    /// like an archived program, without touching the owner's own settings
+    #[serde(default)]
+    pub owner_disabled: bool,
+    /// Source program ID when this program was forked, for attribution
+    #[serde(default)]
+    pub forked_from: Option<String>,
+    /// Incremented by every mutation; clients send it back in If-Match
+    #[serde(default)]
+    pub revision: i64,
+    /// Number of times athletes started this program (popularity)
+    #[serde(default)]
+    pub start_count: i64,
+    #[serde(default)]
+    pub rating_average: Option<f64>,
+    #[serde(default)]
+    pub rating_count: i64,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    /// None = free
+    #[serde(default)]
+    pub price: Option<Price>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl Difficulty {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Advanced => "advanced",
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,6 +86,8 @@ pub struct CreateProgramRequest {
     pub total_weeks: Option<i32>,
     pub public: Option<bool>,
     pub created: Option<bool>,
+    pub difficulty: Option<Difficulty>,
+    pub price: Option<Price>,
 }
 
 /// Response for create program with ID mapping
@@ -45,11 +103,17 @@ pub struct CreateProgramResponse {
 pub struct UpdateProgramRequest {
     pub name: Option<String>,
     pub description: Option<String>,
-    pub image_url: Option<String>,
+    /// Set by the image upload, not by clients
+    #[serde(skip)]
+    pub image_variants: Option<ImageVariants>,
     pub tags: Option<Vec<String>>,
     pub total_weeks: Option<i32>,
     pub public: Option<bool>,
-    pub created: Option<bool>, // one-way: draft -> published
+    pub created: Option<bool>, // draft -> published; sending it again republishes a new version
+    pub archived: Option<bool>,
+    pub difficulty: Option<Difficulty>,
+    /// Zero amount makes the program free again
+    pub price: Option<Price>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,28 +121,60 @@ pub struct ProgramResponse {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    /// Full-size cover image URL
     pub image_url: Option<String>,
+    pub image_variants: Option<ImageVariants>,
     pub tags: Vec<String>,
     pub total_weeks: i32,
     pub last_workout_number: i32,
     pub public: bool,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: DateTime<Utc>,
+    pub current_version: Option<i32>,
+    pub archived: bool,
+    pub forked_from: Option<String>,
+    pub revision: i64,
+    pub start_count: i64,
+    pub rating_average: Option<f64>,
+    pub rating_count: i64,
+    pub difficulty: Option<Difficulty>,
+    pub price: Option<Price>,
+}
+
+impl Program {
+    /// Stored cover image keys, treating a legacy single image as every variant
+    pub fn image(&self) -> Option<ImageVariants> {
+        self.image_variants
+            .clone()
+            .or_else(|| self.image_key.as_deref().map(ImageVariants::single))
+    }
 }
 
 impl From<Program> for ProgramResponse {
     fn from(p: Program) -> Self {
+        let image_variants = p.image().map(|image| image.to_urls());
         Self {
             id: p.id.to_hex(),
             name: p.name,
             description: p.description,
-            image_url: p.image_url,
+            image_url: image_variants.as_ref().map(|image| image.full.clone()),
+            image_variants,
             tags: p.tags,
             total_weeks: p.total_weeks,
             last_workout_number: p.last_workout_number,
             public: p.public,
             created_at: p.created_at,
             updated_at: p.updated_at,
+            current_version: p.current_version,
+            // Clients treat both the same way (no new starts)
+            archived: p.archived || p.owner_disabled,
+            forked_from: p.forked_from,
+            revision: p.revision,
+            start_count: p.start_count,
+            rating_average: p.rating_average,
+            rating_count: p.rating_count,
+            difficulty: p.difficulty,
+            price: p.price,
         }
     }
 }
@@ -88,6 +184,34 @@ pub struct ProgramSearchParams {
     pub search: Option<String>,
     pub page: Option<u64>,
     pub limit: Option<u64>,
+    /// Comma-separated tags
+    pub tags: Option<String>,
+    pub tags_mode: Option<TagsMode>,
+    pub min_weeks: Option<i32>,
+    pub max_weeks: Option<i32>,
+    /// Coach (owner username)
+    pub coach: Option<String>,
+    pub min_workouts_per_week: Option<f64>,
+    pub max_workouts_per_week: Option<f64>,
+    pub difficulty: Option<Difficulty>,
+    pub sort: Option<ProgramSort>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TagsMode {
+    All,
+    Any,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgramSort {
+    Relevance,
+    Newest,
+    Updated,
+    Popularity,
+    Rating,
 }
 
 #[derive(Debug, Serialize)]
@@ -98,3 +222,19 @@ pub struct PaginatedProgramResponse {
     pub limit: u64,
     pub total_pages: u64,
 }
+
+/// Counts for filter chips, computed over the current search query
+#[derive(Debug, Serialize)]
+pub struct ProgramFacetsResponse {
+    pub tags: Vec<FacetCount>,
+    pub duration: Vec<FacetCount>,
+    pub difficulty: Vec<FacetCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FacetCount {
+    pub value: String,
+    /// Display name (tag name from the vocabulary)
+    pub label: Option<String>,
+    pub count: u64,
+}
@@ -0,0 +1,45 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a single revision changed, so a client holding an older revision can merge
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChangeDetail {
+    ProgramUpdated { fields: Vec<String> },
+    ExercisesUpserted { exercise_ids: Vec<String> },
+    ExercisesDeleted { exercise_ids: Vec<String> },
+    WorkoutsDeleted { workout_numbers: Vec<i32> },
+    /// Weeks and workouts were renumbered; the client should reload the program
+    LayoutChanged { operation: String },
+}
+
+/// Change log entry for one program revision
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProgramChange {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub program_id: String,
+    pub revision: i64,
+    pub changed_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub detail: ChangeDetail,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProgramChangeResponse {
+    pub revision: i64,
+    pub changed_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub detail: ChangeDetail,
+}
+
+impl From<ProgramChange> for ProgramChangeResponse {
+    fn from(c: ProgramChange) -> Self {
+        Self {
+            revision: c.revision,
+            changed_at: c.changed_at,
+            detail: c.detail,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::program::Difficulty;
+use super::workout_exercise::Set;
+
+pub const EXPORT_FORMAT: &str = "program-export";
+pub const EXPORT_FORMAT_VERSION: i32 = 1;
+
+/// Self-contained program document used by export and import
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgramExport {
+    pub format: String,
+    pub format_version: i32,
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+    pub program: ExportedProgram,
+    pub weeks: Vec<ExportedWeek>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedProgram {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub total_weeks: i32,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedWeek {
+    pub week: i32,
+    pub workouts: Vec<ExportedWorkout>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedWorkout {
+    pub workout_number: i32,
+    pub exercises: Vec<ExportedExercise>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedExercise {
+    pub order: i32,
+    pub exercise: ExerciseReference,
+    pub volume_metric: Option<String>,
+    pub intensity_metric: Option<String>,
+    pub notes: Option<String>,
+    pub sets: Vec<Set>,
+}
+
+/// Catalog reference; on import the ID wins, then the name is matched case-insensitively
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExerciseReference {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+/// One CSV row per set (exercises without sets get one row with empty set columns)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProgramCsvRow {
+    pub week: i32,
+    pub workout: i32,
+    pub order: i32,
+    pub exercise_id: Option<String>,
+    pub exercise_name: Option<String>,
+    pub volume_metric: Option<String>,
+    pub intensity_metric: Option<String>,
+    pub notes: Option<String>,
+    pub set_number: Option<i32>,
+    pub volume_lower: Option<f64>,
+    pub volume_upper: Option<f64>,
+    pub intensity_lower: Option<f64>,
+    pub intensity_upper: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>, // "json" (default) or "csv"
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Program name for CSV imports (JSON carries its own)
+    pub name: Option<String>,
+}
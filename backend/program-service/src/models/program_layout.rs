@@ -0,0 +1,32 @@
+use serde::Deserialize;
+
+/// Copy a week's workouts over another week (target is replaced)
+#[derive(Debug, Deserialize)]
+pub struct CopyWeekRequest {
+    pub to_week: i32,
+}
+
+/// Repeat the block of weeks [week, through_week] `times` times right after the block
+#[derive(Debug, Deserialize)]
+pub struct RepeatWeeksRequest {
+    pub through_week: Option<i32>, // defaults to the single week
+    pub times: i32,
+}
+
+/// Insert an empty week at position `at`, shifting the following weeks
+#[derive(Debug, Deserialize)]
+pub struct InsertWeekRequest {
+    pub at: i32,
+}
+
+/// Copy or move a workout to the end of another week
+#[derive(Debug, Deserialize)]
+pub struct WorkoutTargetRequest {
+    pub to_week: i32,
+}
+
+/// New order of a week's workouts, given as their current workout_numbers
+#[derive(Debug, Deserialize)]
+pub struct ReorderWorkoutsRequest {
+    pub workout_numbers: Vec<i32>,
+}
@@ -0,0 +1,22 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::workout_exercise::WorkoutExercise;
+
+/// Immutable snapshot of a program taken each time it is published.
+/// Started programs pin a version, so later edits by the coach don't affect them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProgramVersion {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub program_id: String,
+    pub version: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub total_weeks: i32,
+    pub last_workout_number: i32,
+    pub exercises: Vec<WorkoutExercise>,
+    pub published_at: DateTime<Utc>,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use super::workout_exercise::WeekResponse;
+
+/// How set prescriptions change from one generated week to the next
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProgressionRule {
+    /// Add `step` to intensity (RPE points or percentage points) every week
+    LinearIntensity { step: f64, max: Option<f64> },
+    /// Raise intensity by `percent` percent every week, compounding on the previous week
+    PercentageIncrease { percent: f64, max: Option<f64> },
+    /// Add `step` to volume every week
+    VolumeRamp { step: f64, max: Option<f64> },
+    /// Climb the rep range by `rep_step`, then add `intensity_step` and restart at the bottom
+    DoubleProgression { rep_step: f64, intensity_step: f64 },
+}
+
+/// Every `every`-th generated week is a deload scaled down from the week before it
+#[derive(Debug, Deserialize)]
+pub struct DeloadRule {
+    pub every: i32,
+    pub volume_factor: Option<f64>,    // default 0.6
+    pub intensity_factor: Option<f64>, // default 0.9
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProgressionRequest {
+    /// Number of weeks to generate after the source week (existing ones are replaced)
+    pub weeks: i32,
+    pub rule: ProgressionRule,
+    pub deload: Option<DeloadRule>,
+    /// Return the generated weeks without saving
+    #[serde(default)]
+    pub preview: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgressionResponse {
+    pub preview: bool,
+    /// Program revision after saving (None in preview mode)
+    pub revision: Option<i64>,
+    pub weeks: Vec<WeekResponse>,
+}
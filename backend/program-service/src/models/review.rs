@@ -0,0 +1,88 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One review per user per program; rating is 1-5
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Review {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub program_id: String,
+    pub user_id: String,
+    pub rating: i32,
+    pub text: Option<String>,
+    /// Coach reply, one per review
+    pub reply: Option<ReviewReply>,
+    /// Hidden by an admin; excluded from listings and the program average
+    #[serde(default)]
+    pub hidden: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewReply {
+    pub user_id: String,
+    pub text: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertReviewRequest {
+    pub rating: i32,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewReplyRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerateReviewRequest {
+    pub hidden: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewResponse {
+    pub id: String,
+    pub program_id: String,
+    pub user_id: String,
+    pub rating: i32,
+    pub text: Option<String>,
+    pub reply: Option<ReviewReply>,
+    pub hidden: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewQueryParams {
+    pub page: Option<u64>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaginatedReviewResponse {
+    pub reviews: Vec<ReviewResponse>,
+    pub total: u64,
+    pub page: u64,
+    pub limit: u64,
+    pub total_pages: u64,
+}
+
+impl From<Review> for ReviewResponse {
+    fn from(r: Review) -> Self {
+        Self {
+            id: r.id.to_hex(),
+            program_id: r.program_id,
+            user_id: r.user_id,
+            rating: r.rating,
+            text: r.text,
+            reply: r.reply,
+            hidden: r.hidden,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
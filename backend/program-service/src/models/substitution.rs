@@ -0,0 +1,60 @@
+use bson::oid::ObjectId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::exercise::ExerciseResponse;
+
+/// Admin-curated substitution between two catalog exercises; applies in both directions
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Substitution {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub exercise_id: String,
+    pub substitute_id: String,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSubstitutionRequest {
+    pub substitute_id: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubstitutionResponse {
+    pub id: String,
+    pub exercise_id: String,
+    pub substitute_id: String,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlternativesQueryParams {
+    /// Comma-separated equipment the user doesn't have
+    pub exclude_equipment: Option<String>,
+    pub limit: Option<u64>,
+}
+
+/// Candidate replacement for an exercise, best first
+#[derive(Debug, Serialize)]
+pub struct AlternativeResponse {
+    pub exercise: ExerciseResponse,
+    /// 0.0-1.0 similarity; curated substitutions rank above computed ones
+    pub score: f64,
+    pub curated: bool,
+    pub note: Option<String>,
+}
+
+impl From<Substitution> for SubstitutionResponse {
+    fn from(s: Substitution) -> Self {
+        Self {
+            id: s.id.to_hex(),
+            exercise_id: s.exercise_id,
+            substitute_id: s.substitute_id,
+            note: s.note,
+            created_at: s.created_at,
+        }
+    }
+}
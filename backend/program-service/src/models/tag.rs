@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// Managed tag; the ID is the canonical slug stored in `Program.tags`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Tag {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    /// Normalized spellings that resolve to this tag on save
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTagRequest {
+    pub name: String,
+    /// Slug to use instead of the one derived from the name
+    pub id: Option<String>,
+    pub aliases: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTagRequest {
+    pub name: Option<String>,
+    pub aliases: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagResponse {
+    pub id: String,
+    pub name: String,
+    pub aliases: Vec<String>,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(t: Tag) -> Self {
+        Self {
+            id: t.id,
+            name: t.name,
+            aliases: t.aliases,
+        }
+    }
+}
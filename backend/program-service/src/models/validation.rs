@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+/// One machine-readable problem with program content
+#[derive(Debug, Serialize, Clone)]
+pub struct ValidationError {
+    /// Workout exercise ID as sent by the client (temp ID for rows not saved yet)
+    pub id: Option<String>,
+    /// Field path within the workout exercise, e.g. "sets[1].volume_lower"
+    pub field: String,
+    /// Stable error code, e.g. "week_out_of_range"
+    pub code: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(
+        id: Option<String>,
+        field: impl Into<String>,
+        code: &str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            id,
+            field: field.into(),
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
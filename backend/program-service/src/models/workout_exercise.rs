@@ -83,6 +83,8 @@ pub struct UpsertExercisesResponse {
     /// Mapping of temporary IDs to real MongoDB ObjectIds
     /// Only includes entries for IDs that were replaced
     pub id_mappings: Vec<IdMapping>,
+    /// Program revision after this save
+    pub revision: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,138 @@
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::TryStreamExt;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{Entitlement, EntitlementResponse, EntitlementStatusResponse, Price, Program};
+
+/// Validate a price from a create/update request; a zero amount means free
+pub fn normalize_price(price: Option<Price>) -> Result<Option<Price>, AppError> {
+    let Some(price) = price else {
+        return Ok(None);
+    };
+    if price.amount_cents < 0 {
+        return Err(AppError::BadRequest("Price cannot be negative".to_string()));
+    }
+    if price.amount_cents == 0 {
+        return Ok(None);
+    }
+
+    let currency = price.currency.trim().to_uppercase();
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(AppError::BadRequest(
+            "Currency must be a three-letter ISO 4217 code".to_string(),
+        ));
+    }
+
+    Ok(Some(Price {
+        amount_cents: price.amount_cents,
+        currency,
+    }))
+}
+
+pub fn is_free(program: &Program) -> bool {
+    program.price.as_ref().is_none_or(|p| p.amount_cents <= 0)
+}
+
+/// Free programs, the owner and entitled users may read a program's workouts
+pub async fn has_access(
+    collections: &Collections,
+    user_id: &str,
+    program: &Program,
+) -> Result<bool, AppError> {
+    if is_free(program) || program.user_id == user_id {
+        return Ok(true);
+    }
+
+    let entitlement = collections
+        .entitlements
+        .find_one(doc! { "program_id": program.id.to_hex(), "user_id": user_id })
+        .await?;
+    Ok(entitlement.is_some())
+}
+
+pub async fn ensure_access(
+    collections: &Collections,
+    user_id: &str,
+    program: &Program,
+) -> Result<(), AppError> {
+    if has_access(collections, user_id, program).await? {
+        Ok(())
+    } else {
+        Err(AppError::PaymentRequired(
+            "Purchase this program to access its workouts".to_string(),
+        ))
+    }
+}
+
+/// Record that a user may start a program (idempotent)
+pub async fn grant_entitlement(
+    collections: &Collections,
+    program_id: &str,
+    user_id: &str,
+    payment_id: Option<String>,
+) -> Result<(), AppError> {
+    collections
+        .entitlements
+        .update_one(
+            doc! { "program_id": program_id, "user_id": user_id },
+            doc! {
+                "$setOnInsert": {
+                    "_id": ObjectId::new(),
+                    "payment_id": payment_id,
+                    "granted_at": Utc::now().to_rfc3339()
+                }
+            },
+        )
+        .upsert(true)
+        .await?;
+
+    Ok(())
+}
+
+/// Whether the caller may start a program (used by started-program-service)
+pub async fn get_entitlement_status(
+    collections: &Collections,
+    user_id: &str,
+    program_id: &str,
+) -> Result<EntitlementStatusResponse, AppError> {
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let program = collections
+        .programs
+        .find_one(doc! {
+            "_id": oid,
+            "deleted_at": null,
+            "$or": [
+                { "user_id": user_id },
+                { "public": true, "created_at": { "$ne": null } }
+            ]
+        })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    let entitled = has_access(collections, user_id, &program).await?;
+
+    Ok(EntitlementStatusResponse {
+        program_id: program_id.to_string(),
+        entitled,
+        price: program.price,
+    })
+}
+
+/// Programs the user has purchased or been granted
+pub async fn get_user_entitlements(
+    collections: &Collections,
+    user_id: &str,
+) -> Result<Vec<EntitlementResponse>, AppError> {
+    let cursor = collections
+        .entitlements
+        .find(doc! { "user_id": user_id })
+        .sort(doc! { "granted_at": -1 })
+        .await?;
+
+    let entitlements: Vec<Entitlement> = cursor.try_collect().await?;
+    Ok(entitlements.into_iter().map(|e| e.into()).collect())
+}
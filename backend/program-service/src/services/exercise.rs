@@ -1,22 +1,111 @@
-use bson::doc;
+use std::collections::BTreeMap;
+
+use bson::{doc, oid::ObjectId, Document};
+use chrono::Utc;
 use futures::TryStreamExt;
+use mongodb::ClientSession;
 
 use crate::db::Collections;
 use crate::error::AppError;
-use crate::models::{CreateExerciseRequest, Exercise, ExerciseQueryParams, ExerciseResponse, PaginatedExerciseResponse, UpdateExerciseRequest};
+use crate::models::{ChangeDetail, CreateExerciseRequest, DeleteExerciseQuery, Equipment, Exercise, ExerciseEvent, ExerciseQueryParams, ExerciseResponse, MovementPattern, PaginatedExerciseResponse, UpdateExerciseRequest, WorkoutExercise};
+use crate::services::muscle::{find_muscle, validate_muscles};
+use crate::services::revision::bump_revision;
+use crate::services::started_program_client::StartedProgramClient;
+use crate::services::storage::ObjectStorage;
+use crate::services::substitution::delete_exercise_substitutions;
+use crate::services::{image, outbox};
+use crate::services::tag::escape_regex;
+
+/// Trim aliases and drop blanks, duplicates and copies of the name
+pub fn clean_aliases(name: &str, aliases: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for alias in aliases {
+        let alias = alias.trim().to_string();
+        let duplicate = alias.eq_ignore_ascii_case(name.trim())
+            || cleaned.iter().any(|a| a.eq_ignore_ascii_case(&alias));
+        if !alias.is_empty() && !duplicate {
+            cleaned.push(alias);
+        }
+    }
+    cleaned
+}
+
+/// Read an exercise back inside a transaction and record its new state in the outbox
+async fn enqueue_updated(
+    collections: &Collections,
+    session: &mut ClientSession,
+    exercise_id: &str,
+) -> Result<Exercise, AppError> {
+    let exercise = collections
+        .exercises
+        .find_one(doc! { "_id": exercise_id })
+        .session(&mut *session)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+
+    let event = ExerciseEvent::ExerciseUpdated {
+        exercise: exercise.clone().into(),
+    };
+    outbox::enqueue_event(collections, session, &event).await?;
+
+    Ok(exercise)
+}
+
+/// Parse a comma-separated filter of enum values into their stored strings
+pub fn parse_filter<T: Copy>(
+    value: Option<&str>,
+    all: &[T],
+    as_str: fn(&T) -> &'static str,
+    label: &str,
+) -> Result<Vec<&'static str>, AppError> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            all.iter()
+                .map(as_str)
+                .find(|s| s.eq_ignore_ascii_case(v))
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown {}: {}", label, v)))
+        })
+        .collect()
+}
 
 pub async fn create_exercise(
     collections: &Collections,
     req: CreateExerciseRequest,
 ) -> Result<ExerciseResponse, AppError> {
+    let aliases = clean_aliases(&req.name, req.aliases.unwrap_or_default());
     let exercise = Exercise {
         id: req.id,
         name: req.name,
         demonstration_link: req.demonstration_link,
-        muscles: req.muscles,
+        muscles: validate_muscles(req.muscles)?,
+        image_variants: None,
+        equipment: req.equipment,
+        movement_pattern: req.movement_pattern,
+        unilateral: req.unilateral.unwrap_or(false),
+        difficulty: req.difficulty,
+        aliases,
+        deprecated_at: None,
     };
 
-    collections.exercises.insert_one(&exercise).await?;
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    collections
+        .exercises
+        .insert_one(&exercise)
+        .session(&mut session)
+        .await?;
+
+    let event = ExerciseEvent::ExerciseCreated {
+        exercise: exercise.clone().into(),
+    };
+    outbox::enqueue_event(collections, &mut session, &event).await?;
+
+    session.commit_transaction().await?;
 
     Ok(exercise.into())
 }
@@ -45,29 +134,138 @@ pub async fn get_all_exercises(
     let limit = params.limit.unwrap_or(20).min(100);
     let skip = ((page - 1) * limit) as i64;
 
-    // Build match filter based on search query
-    let match_filter = if let Some(search) = &params.search {
-        if search.trim().is_empty() {
-            doc! {}
-        } else {
-            // Case-insensitive regex search on name
-            doc! { "name": { "$regex": search, "$options": "i" } }
-        }
-    } else {
-        doc! {}
-    };
+    let mut match_filter = Document::new();
+    if !params.include_deprecated.unwrap_or(false) {
+        match_filter.insert("deprecated_at", bson::Bson::Null);
+    }
+
+    if let Some(muscle) = params.muscle.as_deref().filter(|m| !m.trim().is_empty()) {
+        let muscle = find_muscle(muscle)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown muscle: {}", muscle)))?;
+        let min_intensity = params.min_intensity.unwrap_or(0.0);
+        match_filter.insert(
+            "muscles",
+            doc! {
+                "$elemMatch": {
+                    "muscle": muscle.name,
+                    "intensity": { "$gte": min_intensity }
+                }
+            },
+        );
+    } else if params.min_intensity.is_some() {
+        return Err(AppError::BadRequest(
+            "min_intensity requires a muscle".to_string(),
+        ));
+    }
+
+    let equipment = parse_filter(
+        params.equipment.as_deref(),
+        &Equipment::ALL,
+        Equipment::as_str,
+        "equipment",
+    )?;
+    if !equipment.is_empty() {
+        match_filter.insert("equipment", doc! { "$in": equipment });
+    }
+    let patterns = parse_filter(
+        params.pattern.as_deref(),
+        &MovementPattern::ALL,
+        MovementPattern::as_str,
+        "movement pattern",
+    )?;
+    if !patterns.is_empty() {
+        match_filter.insert("movement_pattern", doc! { "$in": patterns });
+    }
+    if let Some(unilateral) = params.unilateral {
+        match_filter.insert("unilateral", unilateral);
+    }
+    if let Some(difficulty) = params.difficulty {
+        match_filter.insert("difficulty", difficulty.as_str());
+    }
+
+    let search = params
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
 
-    // Build aggregation pipeline with $facet for efficient pagination
-    let pipeline = vec![
-        doc! { "$match": match_filter },
-        doc! { "$sort": { "name": 1 } },
-        doc! {
-            "$facet": {
-                "metadata": [{ "$count": "totalCount" }],
-                "data": [{ "$skip": skip }, { "$limit": limit as i64 }]
+    let mut pipeline = Vec::new();
+    if let Some(search) = search {
+        // Case-insensitive substring match on the name or any alias
+        let escaped = escape_regex(search);
+        match_filter.insert(
+            "$or",
+            vec![
+                doc! { "name": { "$regex": &escaped, "$options": "i" } },
+                doc! { "aliases": { "$regex": &escaped, "$options": "i" } },
+            ],
+        );
+        pipeline.push(doc! { "$match": match_filter });
+
+        // Rank: exact name, exact alias, name prefix, word in name, anything else
+        let search_lower = search.to_lowercase();
+        pipeline.push(doc! {
+            "$addFields": {
+                "search_rank": {
+                    "$switch": {
+                        "branches": [
+                            {
+                                "case": { "$eq": [{ "$toLower": "$name" }, &search_lower] },
+                                "then": 0
+                            },
+                            {
+                                "case": {
+                                    "$in": [
+                                        &search_lower,
+                                        {
+                                            "$map": {
+                                                "input": { "$ifNull": ["$aliases", []] },
+                                                "in": { "$toLower": "$$this" }
+                                            }
+                                        }
+                                    ]
+                                },
+                                "then": 1
+                            },
+                            {
+                                "case": {
+                                    "$regexMatch": {
+                                        "input": "$name",
+                                        "regex": format!("^{}", escaped),
+                                        "options": "i"
+                                    }
+                                },
+                                "then": 2
+                            },
+                            {
+                                "case": {
+                                    "$regexMatch": {
+                                        "input": "$name",
+                                        "regex": format!("\\b{}", escaped),
+                                        "options": "i"
+                                    }
+                                },
+                                "then": 3
+                            }
+                        ],
+                        "default": 4
+                    }
+                }
             }
-        },
-    ];
+        });
+        pipeline.push(doc! { "$sort": { "search_rank": 1, "name": 1 } });
+    } else {
+        pipeline.push(doc! { "$match": match_filter });
+        pipeline.push(doc! { "$sort": { "name": 1 } });
+    }
+
+    // $facet returns both the total count and the requested page
+    pipeline.push(doc! {
+        "$facet": {
+            "metadata": [{ "$count": "totalCount" }],
+            "data": [{ "$skip": skip }, { "$limit": limit as i64 }]
+        }
+    });
 
     let mut cursor = collections.exercises.aggregate(pipeline).await?;
 
@@ -122,7 +320,7 @@ pub async fn update_exercise(
     req: UpdateExerciseRequest,
 ) -> Result<ExerciseResponse, AppError> {
     // Verify exercise exists
-    collections
+    let exercise = collections
         .exercises
         .find_one(doc! { "_id": exercise_id })
         .await?
@@ -130,13 +328,39 @@ pub async fn update_exercise(
 
     let mut update_doc = doc! {};
 
+    if let Some(aliases) = req.aliases {
+        let name = req.name.as_deref().unwrap_or(&exercise.name);
+        update_doc.insert("aliases", clean_aliases(name, aliases));
+    }
     if let Some(name) = req.name {
         update_doc.insert("name", name);
     }
     if let Some(demonstration_link) = req.demonstration_link {
         update_doc.insert("demonstration_link", demonstration_link);
     }
+    if let Some(equipment) = req.equipment {
+        update_doc.insert("equipment", equipment.as_str());
+    }
+    if let Some(pattern) = req.movement_pattern {
+        update_doc.insert("movement_pattern", pattern.as_str());
+    }
+    if let Some(unilateral) = req.unilateral {
+        update_doc.insert("unilateral", unilateral);
+    }
+    if let Some(difficulty) = req.difficulty {
+        update_doc.insert("difficulty", difficulty.as_str());
+    }
+    match req.deprecated {
+        Some(true) if exercise.deprecated_at.is_none() => {
+            update_doc.insert("deprecated_at", Utc::now().to_rfc3339());
+        }
+        Some(false) => {
+            update_doc.insert("deprecated_at", bson::Bson::Null);
+        }
+        _ => {}
+    }
     if let Some(muscles) = req.muscles {
+        let muscles = validate_muscles(muscles)?;
         let muscles_bson: Vec<bson::Document> = muscles
             .iter()
             .map(|m| doc! { "muscle": &m.muscle, "intensity": m.intensity })
@@ -144,27 +368,292 @@ pub async fn update_exercise(
         update_doc.insert("muscles", muscles_bson);
     }
 
-    if !update_doc.is_empty() {
-        collections
-            .exercises
-            .update_one(doc! { "_id": exercise_id }, doc! { "$set": update_doc })
-            .await?;
+    if update_doc.is_empty() {
+        return Ok(exercise.into());
+    }
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    collections
+        .exercises
+        .update_one(doc! { "_id": exercise_id }, doc! { "$set": update_doc })
+        .session(&mut session)
+        .await?;
+    let exercise = enqueue_updated(collections, &mut session, exercise_id).await?;
+
+    session.commit_transaction().await?;
+
+    Ok(exercise.into())
+}
+
+/// Process an uploaded exercise image, store its variants and replace the previous image
+pub async fn set_exercise_image(
+    collections: &Collections,
+    storage: &dyn ObjectStorage,
+    exercise_id: &str,
+    data: Vec<u8>,
+) -> Result<ExerciseResponse, AppError> {
+    collections
+        .exercises
+        .find_one(doc! { "_id": exercise_id })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+
+    let processed = image::process_image(data).await?;
+    let prefix = format!("exercises/{}/{}", exercise_id, uuid::Uuid::new_v4());
+    let variants = image::store_image(storage, &prefix, processed).await?;
+
+    let previous = collections
+        .exercises
+        .find_one_and_update(
+            doc! { "_id": exercise_id },
+            doc! {
+                "$set": {
+                    "image_variants": {
+                        "thumbnail": &variants.thumbnail,
+                        "card": &variants.card,
+                        "full": &variants.full,
+                    }
+                }
+            },
+        )
+        .await;
+
+    let previous = match previous {
+        Ok(Some(previous)) => previous,
+        Ok(None) => {
+            image::delete_image(storage, &variants).await;
+            return Err(AppError::NotFound("Exercise not found".to_string()));
+        }
+        Err(e) => {
+            image::delete_image(storage, &variants).await;
+            return Err(e.into());
+        }
+    };
+
+    if let Some(old) = previous.image_variants {
+        image::delete_image(storage, &old).await;
     }
 
     get_exercise(collections, exercise_id).await
 }
 
+/// Program rows and published version snapshots that reference an exercise
+async fn program_references(
+    collections: &Collections,
+    session: &mut ClientSession,
+    exercise_id: &str,
+) -> Result<u64, AppError> {
+    let workout_exercises = collections
+        .workout_exercises
+        .count_documents(doc! { "exercise_id": exercise_id })
+        .session(&mut *session)
+        .await?;
+    let versions = collections
+        .program_versions
+        .count_documents(doc! { "exercises.exercise_id": exercise_id })
+        .session(&mut *session)
+        .await?;
+    Ok(workout_exercises + versions)
+}
+
+/// Delete, deprecate or merge an exercise (admin)
+/// A plain delete is refused while programs or athletes' workout logs still reference it
 pub async fn delete_exercise(
     collections: &Collections,
+    storage: &dyn ObjectStorage,
+    started_programs: &StartedProgramClient<'_>,
     exercise_id: &str,
+    query: DeleteExerciseQuery,
 ) -> Result<(), AppError> {
-    let result = collections
+    let deprecate = query.deprecate.unwrap_or(false);
+    if deprecate && query.merge_into.is_some() {
+        return Err(AppError::BadRequest(
+            "Choose either deprecate or merge_into".to_string(),
+        ));
+    }
+
+    let exercise = collections
+        .exercises
+        .find_one(doc! { "_id": exercise_id })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))?;
+
+    if let Some(target_id) = query.merge_into {
+        return merge_exercise(collections, storage, exercise, &target_id).await;
+    }
+
+    if deprecate {
+        if exercise.deprecated_at.is_none() {
+            let mut session = collections.client.start_session().await?;
+            session.start_transaction().await?;
+
+            collections
+                .exercises
+                .update_one(
+                    doc! { "_id": exercise_id },
+                    doc! { "$set": { "deprecated_at": Utc::now().to_rfc3339() } },
+                )
+                .session(&mut session)
+                .await?;
+            enqueue_updated(collections, &mut session, exercise_id).await?;
+
+            session.commit_transaction().await?;
+        }
+        return Ok(());
+    }
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    collections
         .exercises
         .delete_one(doc! { "_id": exercise_id })
+        .session(&mut session)
+        .await?;
+    delete_exercise_substitutions(collections, &mut session, exercise_id).await?;
+
+    let event = ExerciseEvent::ExerciseDeleted {
+        exercise_id: exercise_id.to_string(),
+    };
+    outbox::enqueue_event(collections, &mut session, &event).await?;
+
+    // Counted in the transaction so a program saved meanwhile can't be left dangling
+    let program_refs = program_references(collections, &mut session, exercise_id).await?;
+    // Asked right before committing so workouts logged while the delete ran are seen.
+    // Fails closed: if started-program-service can't answer, the transaction is dropped
+    let athlete_refs = started_programs.exercise_usage(exercise_id).await?;
+    if program_refs + athlete_refs > 0 {
+        return Err(AppError::Conflict(format!(
+            "Exercise is used by {} program exercise(s) and {} logged athlete exercise(s); \
+             deprecate it or merge it into another exercise",
+            program_refs, athlete_refs
+        )));
+    }
+
+    session.commit_transaction().await?;
+
+    if let Some(image) = exercise.image_variants {
+        image::delete_image(storage, &image).await;
+    }
+
+    Ok(())
+}
+
+/// Point every reference to `source` at the target exercise and delete the source.
+/// Program data is rewritten in one transaction; started-program-service and analytics
+/// follow through the ExerciseMerged event stored in the outbox by that transaction.
+async fn merge_exercise(
+    collections: &Collections,
+    storage: &dyn ObjectStorage,
+    source: Exercise,
+    target_id: &str,
+) -> Result<(), AppError> {
+    if source.id == target_id {
+        return Err(AppError::BadRequest(
+            "An exercise cannot be merged into itself".to_string(),
+        ));
+    }
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    let target = collections
+        .exercises
+        .find_one(doc! { "_id": target_id })
+        .session(&mut session)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Merge target exercise not found".to_string()))?;
+    if target.deprecated_at.is_some() {
+        return Err(AppError::BadRequest(
+            "Cannot merge into a deprecated exercise".to_string(),
+        ));
+    }
+
+    // Programs whose workouts change get a new revision so open editors reload them
+    let mut cursor = collections
+        .workout_exercises
+        .find(doc! { "exercise_id": &source.id })
+        .session(&mut session)
         .await?;
+    let affected: Vec<WorkoutExercise> = cursor.stream(&mut session).try_collect().await?;
+    let mut rows_by_program: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for row in affected {
+        rows_by_program
+            .entry(row.program_id)
+            .or_default()
+            .push(row.id.to_hex());
+    }
+    let program_oids: Vec<ObjectId> = rows_by_program
+        .keys()
+        .filter_map(|id| ObjectId::parse_str(id).ok())
+        .collect();
+    // Rows left behind by a removed program have nothing to bump
+    let existing = collections
+        .programs
+        .distinct("_id", doc! { "_id": { "$in": &program_oids } })
+        .session(&mut session)
+        .await?;
+    for program_oid in existing.iter().filter_map(|id| id.as_object_id()) {
+        let exercise_ids = rows_by_program
+            .remove(&program_oid.to_hex())
+            .unwrap_or_default();
+        let detail = ChangeDetail::ExercisesUpserted { exercise_ids };
+        bump_revision(collections, &mut session, program_oid, None, detail).await?;
+    }
+
+    collections
+        .workout_exercises
+        .update_many(
+            doc! { "exercise_id": &source.id },
+            doc! { "$set": { "exercise_id": target_id } },
+        )
+        .session(&mut session)
+        .await?;
+
+    collections
+        .program_versions
+        .update_many(
+            doc! { "exercises.exercise_id": &source.id },
+            doc! { "$set": { "exercises.$[e].exercise_id": target_id } },
+        )
+        .array_filters(vec![doc! { "e.exercise_id": &source.id }])
+        .session(&mut session)
+        .await?;
+
+    // Keep the merged exercise findable by its old names
+    let mut aliases = target.aliases;
+    aliases.push(source.name);
+    aliases.extend(source.aliases);
+    collections
+        .exercises
+        .update_one(
+            doc! { "_id": target_id },
+            doc! { "$set": { "aliases": clean_aliases(&target.name, aliases) } },
+        )
+        .session(&mut session)
+        .await?;
+    enqueue_updated(collections, &mut session, target_id).await?;
+
+    delete_exercise_substitutions(collections, &mut session, &source.id).await?;
+
+    collections
+        .exercises
+        .delete_one(doc! { "_id": &source.id })
+        .session(&mut session)
+        .await?;
+
+    let event = ExerciseEvent::ExerciseMerged {
+        source_id: source.id.clone(),
+        target_id: target_id.to_string(),
+    };
+    outbox::enqueue_event(collections, &mut session, &event).await?;
+
+    session.commit_transaction().await?;
 
-    if result.deleted_count == 0 {
-        return Err(AppError::NotFound("Exercise not found".to_string()));
+    if let Some(image) = source.image_variants {
+        image::delete_image(storage, &image).await;
     }
 
     Ok(())
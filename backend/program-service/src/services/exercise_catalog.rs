@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+
+use bson::doc;
+use chrono::Utc;
+use futures::TryStreamExt;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::exercise_catalog::{CATALOG_FORMAT, CATALOG_FORMAT_VERSION};
+use crate::models::{
+    CatalogExercise, CatalogResyncResponse, Exercise, ExerciseCatalog, ExerciseCsvRow,
+    ExerciseEvent, ExerciseImportReport, ImportAction, ImportRowResult, MuscleIntensity,
+};
+use crate::services::exercise::clean_aliases;
+use crate::services::muscle::validate_muscles;
+use crate::services::outbox;
+
+/// Parsed import row with its 1-based row number, or why it could not be read
+pub type ImportRow = (usize, Result<CatalogExercise, String>);
+
+/// The whole catalog, ordered by ID so exports from two environments diff cleanly
+pub async fn export_catalog(collections: &Collections) -> Result<ExerciseCatalog, AppError> {
+    let exercises: Vec<Exercise> = collections
+        .exercises
+        .find(doc! {})
+        .sort(doc! { "_id": 1 })
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(ExerciseCatalog {
+        format: CATALOG_FORMAT.to_string(),
+        format_version: CATALOG_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        exercises: exercises.into_iter().map(|e| e.into()).collect(),
+    })
+}
+
+/// Publish the whole catalog so other services can rebuild their read models (admin)
+/// The snapshot is read in the transaction that enqueues it, so it sits in event order
+pub async fn resync_catalog(collections: &Collections) -> Result<CatalogResyncResponse, AppError> {
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    let mut cursor = collections
+        .exercises
+        .find(doc! {})
+        .sort(doc! { "_id": 1 })
+        .session(&mut session)
+        .await?;
+    let exercises: Vec<Exercise> = cursor.stream(&mut session).try_collect().await?;
+    let count = exercises.len();
+
+    let event = ExerciseEvent::CatalogSynced {
+        exercises: exercises.into_iter().map(|e| e.into()).collect(),
+    };
+    outbox::enqueue_event(collections, &mut session, &event).await?;
+
+    session.commit_transaction().await?;
+
+    Ok(CatalogResyncResponse { exercises: count })
+}
+
+/// Flatten the catalog into CSV with one row per exercise
+pub fn catalog_to_csv(catalog: &ExerciseCatalog) -> Result<String, AppError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    for exercise in &catalog.exercises {
+        let muscles: Vec<String> = exercise
+            .muscles
+            .iter()
+            .map(|m| format!("{}:{}", m.muscle, m.intensity))
+            .collect();
+        let row = ExerciseCsvRow {
+            id: exercise.id.clone(),
+            name: exercise.name.clone(),
+            demonstration_link: Some(exercise.demonstration_link.clone()),
+            muscles: Some(muscles.join(";")),
+            equipment: exercise.equipment,
+            movement_pattern: exercise.movement_pattern,
+            unilateral: Some(exercise.unilateral),
+            difficulty: exercise.difficulty,
+            aliases: Some(exercise.aliases.join(";")),
+        };
+        writer
+            .serialize(row)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to write CSV: {}", e)))?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| AppError::InternalServerError(format!("Failed to write CSV: {}", e)))?;
+    String::from_utf8(bytes)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to write CSV: {}", e)))
+}
+
+/// Read a JSON import: an exported catalog document or a bare array of exercises
+pub fn parse_json(data: &[u8]) -> Result<Vec<ImportRow>, AppError> {
+    let value: serde_json::Value = serde_json::from_slice(data)
+        .map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?;
+
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        serde_json::Value::Object(mut document) => match document.remove("exercises") {
+            Some(serde_json::Value::Array(items)) => items,
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Expected an exercises array".to_string(),
+                ))
+            }
+        },
+        _ => {
+            return Err(AppError::BadRequest(
+                "Expected an exercises array".to_string(),
+            ))
+        }
+    };
+
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let parsed = serde_json::from_value::<CatalogExercise>(item).map_err(|e| e.to_string());
+            (index + 1, parsed)
+        })
+        .collect())
+}
+
+/// Parse "Chest:1.0;Triceps:0.5"
+fn parse_csv_muscles(value: &str) -> Result<Vec<MuscleIntensity>, String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(|m| {
+            let (muscle, intensity) = m
+                .rsplit_once(':')
+                .ok_or_else(|| format!("Expected muscle:intensity, got '{}'", m))?;
+            let intensity = intensity
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid intensity for {}: '{}'", muscle.trim(), intensity))?;
+            Ok(MuscleIntensity {
+                muscle: muscle.trim().to_string(),
+                intensity,
+            })
+        })
+        .collect()
+}
+
+/// Read a CSV import in the export's one-row-per-exercise layout
+pub fn parse_csv(data: &[u8]) -> Result<Vec<ImportRow>, AppError> {
+    let mut reader = csv::Reader::from_reader(data);
+    let rows = reader
+        .deserialize::<ExerciseCsvRow>()
+        .enumerate()
+        .map(|(index, result)| {
+            // Row numbers as a spreadsheet shows them (header is row 1)
+            let parsed = result.map_err(|e| e.to_string()).and_then(|row| {
+                Ok(CatalogExercise {
+                    id: row.id,
+                    name: row.name,
+                    demonstration_link: row.demonstration_link.unwrap_or_default(),
+                    muscles: parse_csv_muscles(row.muscles.as_deref().unwrap_or_default())?,
+                    equipment: row.equipment,
+                    movement_pattern: row.movement_pattern,
+                    unilateral: row.unilateral.unwrap_or(false),
+                    difficulty: row.difficulty,
+                    aliases: row
+                        .aliases
+                        .as_deref()
+                        .unwrap_or_default()
+                        .split(';')
+                        .map(str::to_string)
+                        .collect(),
+                })
+            });
+            (index + 2, parsed)
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/// Normalize and validate one row the way create_exercise/update_exercise would
+fn validate_row(row: CatalogExercise) -> Result<CatalogExercise, Vec<String>> {
+    let mut errors = Vec::new();
+
+    let name = row.name.trim().to_string();
+    if name.is_empty() {
+        errors.push("Name is required".to_string());
+    }
+    let muscles = match validate_muscles(row.muscles) {
+        Ok(muscles) => muscles,
+        Err(AppError::BadRequest(message)) => {
+            errors.push(message);
+            Vec::new()
+        }
+        Err(_) => Vec::new(),
+    };
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(CatalogExercise {
+        id: row.id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty()),
+        aliases: clean_aliases(&name, row.aliases),
+        name,
+        demonstration_link: row.demonstration_link.trim().to_string(),
+        muscles,
+        equipment: row.equipment,
+        movement_pattern: row.movement_pattern,
+        unilateral: row.unilateral,
+        difficulty: row.difficulty,
+    })
+}
+
+/// Validate every row against the catalog and, unless this is a dry run, upsert them
+/// Rows match existing exercises by ID, then by name; nothing is written if any row fails
+pub async fn import_exercises(
+    collections: &Collections,
+    rows: Vec<ImportRow>,
+    dry_run: bool,
+) -> Result<ExerciseImportReport, AppError> {
+    let existing: Vec<Exercise> = collections
+        .exercises
+        .find(doc! {})
+        .await?
+        .try_collect()
+        .await?;
+    let mut id_by_name: HashMap<String, String> = existing
+        .iter()
+        .map(|e| (e.name.to_lowercase(), e.id.clone()))
+        .collect();
+    let by_id: HashMap<String, Exercise> = existing.into_iter().map(|e| (e.id.clone(), e)).collect();
+
+    // Exercise IDs already claimed by an earlier row of this import
+    let mut claimed: HashMap<String, usize> = HashMap::new();
+    let mut results = Vec::with_capacity(rows.len());
+    let mut writes: Vec<(ImportAction, CatalogExercise)> = Vec::new();
+
+    for (row_number, parsed) in rows {
+        let validated = parsed.map_err(|e| vec![e]).and_then(validate_row);
+        let exercise = match validated {
+            Ok(exercise) => exercise,
+            Err(errors) => {
+                results.push(ImportRowResult {
+                    row: row_number,
+                    id: None,
+                    name: None,
+                    action: ImportAction::Error,
+                    errors,
+                });
+                continue;
+            }
+        };
+
+        let name_key = exercise.name.to_lowercase();
+        let target_id = match &exercise.id {
+            Some(id) => id.clone(),
+            None => id_by_name
+                .get(&name_key)
+                .cloned()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        };
+
+        let mut errors = Vec::new();
+        if let Some(first) = claimed.get(&target_id) {
+            errors.push(format!("Same exercise as row {}", first));
+        }
+        if let Some(owner) = id_by_name.get(&name_key).filter(|owner| **owner != target_id) {
+            errors.push(format!("Name is already used by exercise {}", owner));
+        }
+
+        let exercise = CatalogExercise {
+            id: Some(target_id.clone()),
+            ..exercise
+        };
+        let action = if !errors.is_empty() {
+            ImportAction::Error
+        } else {
+            match by_id.get(&target_id) {
+                None => ImportAction::Create,
+                Some(current) if CatalogExercise::from(current.clone()) == exercise => {
+                    ImportAction::Unchanged
+                }
+                Some(_) => ImportAction::Update,
+            }
+        };
+
+        if action != ImportAction::Error {
+            claimed.insert(target_id.clone(), row_number);
+            // Renames free the old name for later rows
+            if let Some(current) = by_id.get(&target_id) {
+                id_by_name.remove(&current.name.to_lowercase());
+            }
+            id_by_name.insert(name_key, target_id.clone());
+        }
+
+        results.push(ImportRowResult {
+            row: row_number,
+            id: Some(target_id),
+            name: Some(exercise.name.clone()),
+            action,
+            errors,
+        });
+        if matches!(action, ImportAction::Create | ImportAction::Update) {
+            writes.push((action, exercise));
+        }
+    }
+
+    let count = |action: ImportAction| results.iter().filter(|r| r.action == action).count();
+    let failed = count(ImportAction::Error);
+    let applied = !dry_run && failed == 0;
+
+    if applied {
+        // All rows and their catalog events are written together or not at all
+        let mut session = collections.client.start_session().await?;
+        session.start_transaction().await?;
+
+        for (action, exercise) in writes {
+            let event = if action == ImportAction::Create {
+                ExerciseEvent::ExerciseCreated {
+                    exercise: exercise.clone(),
+                }
+            } else {
+                ExerciseEvent::ExerciseUpdated {
+                    exercise: exercise.clone(),
+                }
+            };
+            let id = exercise.id.unwrap_or_default();
+            if action == ImportAction::Create {
+                collections
+                    .exercises
+                    .insert_one(Exercise {
+                        id,
+                        name: exercise.name,
+                        demonstration_link: exercise.demonstration_link,
+                        muscles: exercise.muscles,
+                        image_variants: None,
+                        equipment: exercise.equipment,
+                        movement_pattern: exercise.movement_pattern,
+                        unilateral: exercise.unilateral,
+                        difficulty: exercise.difficulty,
+                        aliases: exercise.aliases,
+                        deprecated_at: None,
+                    })
+                    .session(&mut session)
+                    .await?;
+            } else {
+                let muscles: Vec<bson::Document> = exercise
+                    .muscles
+                    .iter()
+                    .map(|m| doc! { "muscle": &m.muscle, "intensity": m.intensity })
+                    .collect();
+                collections
+                    .exercises
+                    .update_one(
+                        doc! { "_id": id },
+                        doc! {
+                            "$set": {
+                                "name": exercise.name,
+                                "demonstration_link": exercise.demonstration_link,
+                                "muscles": muscles,
+                                "equipment": exercise.equipment.map(|e| e.as_str()),
+                                "movement_pattern": exercise.movement_pattern.map(|p| p.as_str()),
+                                "unilateral": exercise.unilateral,
+                                "difficulty": exercise.difficulty.map(|d| d.as_str()),
+                                "aliases": exercise.aliases,
+                            }
+                        },
+                    )
+                    .session(&mut session)
+                    .await?;
+            }
+            outbox::enqueue_event(collections, &mut session, &event).await?;
+        }
+
+        session.commit_transaction().await?;
+    }
+
+    Ok(ExerciseImportReport {
+        dry_run,
+        applied,
+        created: count(ImportAction::Create),
+        updated: count(ImportAction::Update),
+        unchanged: count(ImportAction::Unchanged),
+        failed,
+        rows: results,
+    })
+}
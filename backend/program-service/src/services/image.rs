@@ -0,0 +1,138 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+
+use crate::error::AppError;
+use crate::models::ImageVariants;
+use crate::services::storage::ObjectStorage;
+
+const MAX_SOURCE_DIMENSION: u32 = 10_000;
+const THUMBNAIL_SIZE: u32 = 200;
+const CARD_SIZE: u32 = 640;
+const FULL_SIZE: u32 = 1600;
+const JPEG_QUALITY: u8 = 85;
+
+/// Uploaded image re-encoded into every variant
+pub struct ProcessedImage {
+    extension: &'static str,
+    content_type: &'static str,
+    thumbnail: Vec<u8>,
+    card: Vec<u8>,
+    full: Vec<u8>,
+}
+
+/// Check the file signature, decode and re-encode into thumbnail, card and full sizes
+/// Re-encoding drops EXIF/GPS metadata; the EXIF orientation is applied first so photos stay upright
+pub async fn process_image(data: Vec<u8>) -> Result<ProcessedImage, AppError> {
+    tokio::task::spawn_blocking(move || process_image_blocking(&data))
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Image processing failed: {}", e)))?
+}
+
+fn process_image_blocking(data: &[u8]) -> Result<ProcessedImage, AppError> {
+    let invalid = || AppError::BadRequest("Invalid image type. Allowed: JPEG, PNG, WebP".to_string());
+
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|_| invalid())?;
+    match reader.format() {
+        Some(ImageFormat::Jpeg) | Some(ImageFormat::Png) | Some(ImageFormat::WebP) => {}
+        _ => return Err(invalid()),
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    reader.limits(limits);
+
+    let unreadable = |e: image::ImageError| AppError::BadRequest(format!("Unreadable image: {}", e));
+    let mut decoder = reader.into_decoder().map_err(unreadable)?;
+    let orientation = decoder.orientation().map_err(unreadable)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(unreadable)?;
+    image.apply_orientation(orientation);
+
+    // Keep transparency as PNG; everything else becomes JPEG
+    let (extension, content_type) = if image.color().has_alpha() {
+        ("png", "image/png")
+    } else {
+        ("jpg", "image/jpeg")
+    };
+
+    let thumbnail = image.resize_to_fill(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Lanczos3);
+    let card = fit_within(&image, CARD_SIZE);
+    let full = fit_within(&image, FULL_SIZE);
+
+    Ok(ProcessedImage {
+        extension,
+        content_type,
+        thumbnail: encode(&thumbnail, extension)?,
+        card: encode(&card, extension)?,
+        full: encode(&full, extension)?,
+    })
+}
+
+/// Downscale so neither side exceeds `max`; smaller images are not enlarged
+fn fit_within(image: &DynamicImage, max: u32) -> DynamicImage {
+    if image.width() <= max && image.height() <= max {
+        image.clone()
+    } else {
+        image.resize(max, max, FilterType::Lanczos3)
+    }
+}
+
+fn encode(image: &DynamicImage, extension: &str) -> Result<Vec<u8>, AppError> {
+    let failed = |e: image::ImageError| {
+        AppError::InternalServerError(format!("Image encoding failed: {}", e))
+    };
+
+    let mut data = Vec::new();
+    if extension == "png" {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .map_err(failed)?;
+    } else {
+        let encoder = JpegEncoder::new_with_quality(&mut data, JPEG_QUALITY);
+        DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(encoder)
+            .map_err(failed)?;
+    }
+
+    Ok(data)
+}
+
+/// Upload every variant under `prefix` (e.g. "programs/<id>/<uuid>") and return their keys
+/// Nothing is left behind if one of the uploads fails
+pub async fn store_image(
+    storage: &dyn ObjectStorage,
+    prefix: &str,
+    image: ProcessedImage,
+) -> Result<ImageVariants, AppError> {
+    let keys = ImageVariants {
+        thumbnail: format!("{}/thumbnail.{}", prefix, image.extension),
+        card: format!("{}/card.{}", prefix, image.extension),
+        full: format!("{}/full.{}", prefix, image.extension),
+    };
+
+    let uploads = [
+        (&keys.thumbnail, image.thumbnail),
+        (&keys.card, image.card),
+        (&keys.full, image.full),
+    ];
+    for (key, data) in uploads {
+        if let Err(e) = storage.put(key, data, image.content_type).await {
+            delete_image(storage, &keys).await;
+            return Err(e);
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Best-effort removal of every variant of an image
+pub async fn delete_image(storage: &dyn ObjectStorage, image: &ImageVariants) {
+    for key in image.keys() {
+        let _ = storage.delete(key).await;
+    }
+}
@@ -1,9 +1,26 @@
+pub mod entitlement;
 pub mod exercise;
+pub mod exercise_catalog;
+pub mod image;
+pub mod muscle;
+pub mod outbox;
+pub mod payment;
 pub mod program;
-pub mod s3;
+pub mod program_export;
+pub mod program_image;
+pub mod program_layout;
+pub mod program_version;
+pub mod progression;
+pub mod rabbitmq;
+pub mod review;
+pub mod revision;
+pub mod started_program_client;
+pub mod storage;
+pub mod substitution;
+pub mod tag;
+pub mod validation;
 pub mod workout_exercise;
 
 pub use exercise::*;
 pub use program::*;
-pub use s3::*;
 pub use workout_exercise::*;
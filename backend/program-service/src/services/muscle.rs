@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+
+use crate::error::AppError;
+use crate::models::muscle::{BodyRegion, BodyView};
+use crate::models::{Muscle, MuscleIntensity};
+
+/// Canonical muscles; IDs match utils/muscle.sql
+pub const MUSCLES: &[Muscle] = &[
+    Muscle {
+        id: 1,
+        name: "Neck",
+        region: BodyRegion::Neck,
+        view: BodyView::Both,
+        slugs: &["neck-left-front", "neck-right-front", "neck-left-back", "neck-right-back"],
+    },
+    Muscle {
+        id: 2,
+        name: "Front Deltoid",
+        region: BodyRegion::Shoulders,
+        view: BodyView::Front,
+        slugs: &["deltoids-left-front", "deltoids-right-front"],
+    },
+    Muscle {
+        id: 3,
+        name: "Side Deltoid",
+        region: BodyRegion::Shoulders,
+        view: BodyView::Front,
+        slugs: &["deltoids-left-front", "deltoids-right-front"],
+    },
+    Muscle {
+        id: 4,
+        name: "Triceps",
+        region: BodyRegion::Arms,
+        view: BodyView::Both,
+        slugs: &["triceps-left-front", "triceps-right-front", "triceps-left-back", "triceps-right-back"],
+    },
+    Muscle {
+        id: 5,
+        name: "Tibialis",
+        region: BodyRegion::Legs,
+        view: BodyView::Front,
+        slugs: &["tibialis-left", "tibialis-right"],
+    },
+    Muscle {
+        id: 6,
+        name: "Calves",
+        region: BodyRegion::Legs,
+        view: BodyView::Both,
+        slugs: &["calves-left-front", "calves-right-front", "calves-left-back", "calves-right-back"],
+    },
+    Muscle {
+        id: 7,
+        name: "Chest",
+        region: BodyRegion::Chest,
+        view: BodyView::Front,
+        slugs: &["chest-left", "chest-right"],
+    },
+    Muscle {
+        id: 8,
+        name: "Biceps",
+        region: BodyRegion::Arms,
+        view: BodyView::Front,
+        slugs: &["biceps-left", "biceps-right"],
+    },
+    Muscle {
+        id: 9,
+        name: "Trapezius",
+        region: BodyRegion::Back,
+        view: BodyView::Both,
+        slugs: &[
+            "trapezius-left-front",
+            "trapezius-right-front",
+            "trapezius-left-back",
+            "trapezius-right-back",
+        ],
+    },
+    Muscle {
+        id: 10,
+        name: "Forearms",
+        region: BodyRegion::Arms,
+        view: BodyView::Both,
+        slugs: &["forearm-left-front", "forearm-right-front", "forearm-left-back", "forearm-right-back"],
+    },
+    Muscle {
+        id: 11,
+        name: "Abs",
+        region: BodyRegion::Core,
+        view: BodyView::Front,
+        slugs: &["abs-upper", "abs-lower"],
+    },
+    Muscle {
+        id: 12,
+        name: "Obliques",
+        region: BodyRegion::Core,
+        view: BodyView::Front,
+        slugs: &["obliques-left", "obliques-right"],
+    },
+    Muscle {
+        id: 13,
+        name: "Quads",
+        region: BodyRegion::Legs,
+        view: BodyView::Front,
+        slugs: &["quadriceps-left", "quadriceps-right"],
+    },
+    Muscle {
+        id: 14,
+        name: "Adductors",
+        region: BodyRegion::Legs,
+        view: BodyView::Both,
+        slugs: &[
+            "adductors-left-front",
+            "adductors-right-front",
+            "adductors-left-back",
+            "adductors-right-back",
+        ],
+    },
+    Muscle {
+        id: 15,
+        name: "Abductors",
+        region: BodyRegion::Legs,
+        view: BodyView::Back,
+        slugs: &["hips-left", "hips-right"],
+    },
+    Muscle {
+        id: 16,
+        name: "Knees",
+        region: BodyRegion::Legs,
+        view: BodyView::Front,
+        slugs: &["knees-left", "knees-right"],
+    },
+    Muscle {
+        id: 17,
+        name: "Lats",
+        region: BodyRegion::Back,
+        view: BodyView::Back,
+        slugs: &["upper-back-left", "upper-back-right"],
+    },
+    Muscle {
+        id: 18,
+        name: "Upper Back",
+        region: BodyRegion::Back,
+        view: BodyView::Back,
+        slugs: &["upper-back-left", "upper-back-right"],
+    },
+    Muscle {
+        id: 19,
+        name: "Rear Deltoids",
+        region: BodyRegion::Shoulders,
+        view: BodyView::Back,
+        slugs: &["deltoids-left-back", "deltoids-right-back"],
+    },
+    Muscle {
+        id: 20,
+        name: "Lower Back",
+        region: BodyRegion::Back,
+        view: BodyView::Back,
+        slugs: &["lower-back-left", "lower-back-right"],
+    },
+    Muscle {
+        id: 21,
+        name: "Glutes",
+        region: BodyRegion::Legs,
+        view: BodyView::Back,
+        slugs: &["gluteal-left", "gluteal-right"],
+    },
+    Muscle {
+        id: 22,
+        name: "Hamstrings",
+        region: BodyRegion::Legs,
+        view: BodyView::Back,
+        slugs: &["hamstring-left", "hamstring-right"],
+    },
+];
+
+/// Look up a muscle by display name (case-insensitive)
+pub fn find_muscle(name: &str) -> Option<&'static Muscle> {
+    let name = name.trim();
+    MUSCLES.iter().find(|m| m.name.eq_ignore_ascii_case(name))
+}
+
+/// Check exercise muscles against the registry and rewrite names to their canonical spelling
+/// Intensities must be between 0.0 and 1.0 and each muscle may appear once
+pub fn validate_muscles(muscles: Vec<MuscleIntensity>) -> Result<Vec<MuscleIntensity>, AppError> {
+    let mut seen = HashSet::new();
+    muscles
+        .into_iter()
+        .map(|m| {
+            let muscle = find_muscle(&m.muscle)
+                .ok_or_else(|| AppError::BadRequest(format!("Unknown muscle: {}", m.muscle)))?;
+            if !(0.0..=1.0).contains(&m.intensity) {
+                return Err(AppError::BadRequest(format!(
+                    "Intensity for {} must be between 0.0 and 1.0",
+                    muscle.name
+                )));
+            }
+            if !seen.insert(muscle.id) {
+                return Err(AppError::BadRequest(format!(
+                    "Muscle {} is listed more than once",
+                    muscle.name
+                )));
+            }
+            Ok(MuscleIntensity {
+                muscle: muscle.name.to_string(),
+                intensity: m.intensity,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::ClientSession;
+
+use crate::config::{OUTBOX_BATCH_SIZE, OUTBOX_POLL_INTERVAL_SECS};
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{ExerciseEvent, OutboxEvent};
+use crate::services::rabbitmq::RabbitMQPublisher;
+
+/// Store an event in the outbox. Call this inside the transaction that changes the
+/// catalog, so the event is only published if that change is committed.
+pub async fn enqueue_event(
+    collections: &Collections,
+    session: &mut ClientSession,
+    event: &ExerciseEvent,
+) -> Result<(), AppError> {
+    let payload = serde_json::to_string(event).map_err(|e| {
+        AppError::InternalServerError(format!("Failed to serialize exercise event: {}", e))
+    })?;
+
+    collections
+        .outbox_events
+        .insert_one(OutboxEvent {
+            id: ObjectId::new(),
+            event_type: event.event_type().to_string(),
+            routing_key: event.routing_key().to_string(),
+            payload,
+            created_at: Utc::now(),
+            published_at: None,
+        })
+        .session(session)
+        .await?;
+
+    Ok(())
+}
+
+/// Publish one batch of pending events in order.
+/// A crash between publishing and marking resends the event, so consumers must be idempotent.
+async fn relay_batch(
+    collections: &Collections,
+    publisher: &RabbitMQPublisher,
+) -> Result<usize, AppError> {
+    let events: Vec<OutboxEvent> = collections
+        .outbox_events
+        .find(doc! { "published_at": null })
+        .sort(doc! { "_id": 1 })
+        .limit(OUTBOX_BATCH_SIZE)
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut published: Vec<ObjectId> = Vec::new();
+    let mut publish_error = None;
+
+    for event in &events {
+        match publisher.publish_event(event).await {
+            Ok(()) => published.push(event.id),
+            Err(e) => {
+                // Stop at the first failure to keep events in order
+                publish_error = Some(e);
+                break;
+            }
+        }
+    }
+
+    if !published.is_empty() {
+        collections
+            .outbox_events
+            .update_many(
+                doc! { "_id": { "$in": &published } },
+                doc! { "$set": { "published_at": Utc::now().to_rfc3339() } },
+            )
+            .await?;
+    }
+
+    match publish_error {
+        Some(e) => Err(e),
+        None => Ok(published.len()),
+    }
+}
+
+/// Spawn the background task that moves events from the outbox to RabbitMQ.
+/// The broker connection is (re)established lazily, so program-service keeps working
+/// while RabbitMQ is down and catches up once it is back.
+pub fn start_outbox_relay(collections: Collections, rabbitmq_url: String) {
+    tokio::spawn(async move {
+        let mut publisher: Option<RabbitMQPublisher> = None;
+
+        loop {
+            if publisher.is_none() {
+                match RabbitMQPublisher::new(&rabbitmq_url).await {
+                    Ok(p) => publisher = Some(p),
+                    Err(e) => eprintln!("Outbox relay: RabbitMQ unavailable, retrying: {:?}", e),
+                }
+            }
+
+            let mut sent_full_batch = false;
+            if let Some(p) = &publisher {
+                match relay_batch(&collections, p).await {
+                    Ok(count) => sent_full_batch = count as i64 == OUTBOX_BATCH_SIZE,
+                    Err(e) => {
+                        eprintln!("Outbox relay: failed to publish events, reconnecting: {:?}", e);
+                        publisher = None;
+                    }
+                }
+            }
+
+            // Keep draining without waiting while there is a backlog
+            if !sent_full_batch {
+                tokio::time::sleep(Duration::from_secs(OUTBOX_POLL_INTERVAL_SECS)).await;
+            }
+        }
+    });
+}
@@ -0,0 +1,296 @@
+use axum::http::HeaderMap;
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{Payment, PaymentResponse, PaymentStatus, Price, PurchaseResponse};
+use crate::services::entitlement::{grant_entitlement, has_access, is_free};
+
+/// Checkout opened with a provider for a pending payment
+pub struct Checkout {
+    pub provider_reference: String,
+    pub checkout_url: Option<String>,
+}
+
+/// Payment outcome reported by a provider webhook
+pub struct PaymentEvent {
+    pub provider_reference: String,
+    pub status: PaymentStatus,
+}
+
+/// Payment gateway integration; implementations must verify webhook authenticity
+pub trait PaymentProvider: Send + Sync {
+    /// Stored on payments so webhooks only settle their own provider's payments
+    fn name(&self) -> &'static str;
+
+    fn create_checkout<'a>(
+        &'a self,
+        payment_id: &'a str,
+        price: &'a Price,
+    ) -> BoxFuture<'a, Result<Checkout, AppError>>;
+
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<PaymentEvent, AppError>;
+}
+
+/// Webhook body accepted by the local provider
+#[derive(Debug, Deserialize)]
+struct LocalWebhookPayload {
+    reference: String,
+    status: PaymentStatus,
+}
+
+/// Fake provider for development: checkouts complete through a webhook carrying the
+/// shared secret, or through POST /payments/local/:reference/complete when the simulated
+/// checkout is enabled
+pub struct LocalPaymentProvider {
+    webhook_secret: String,
+    simulated_checkout: bool,
+}
+
+impl LocalPaymentProvider {
+    pub const NAME: &'static str = "local";
+
+    pub fn new(webhook_secret: String, simulated_checkout: bool) -> Self {
+        Self {
+            webhook_secret,
+            simulated_checkout,
+        }
+    }
+}
+
+impl PaymentProvider for LocalPaymentProvider {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn create_checkout<'a>(
+        &'a self,
+        _payment_id: &'a str,
+        _price: &'a Price,
+    ) -> BoxFuture<'a, Result<Checkout, AppError>> {
+        Box::pin(async move {
+            let reference = format!("local_{}", Uuid::new_v4().simple());
+            let checkout_url = self
+                .simulated_checkout
+                .then(|| format!("/payments/local/{}/complete", reference));
+            Ok(Checkout {
+                checkout_url,
+                provider_reference: reference,
+            })
+        })
+    }
+
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<PaymentEvent, AppError> {
+        let secret = headers
+            .get("X-Webhook-Secret")
+            .map(|v| v.as_bytes())
+            .unwrap_or_default();
+        // Constant-time so response timing doesn't reveal how much of the secret matched
+        if !bool::from(secret.ct_eq(self.webhook_secret.as_bytes())) {
+            return Err(AppError::Unauthorized("Invalid webhook secret".to_string()));
+        }
+
+        let payload: LocalWebhookPayload = serde_json::from_slice(body)
+            .map_err(|e| AppError::BadRequest(format!("Invalid webhook payload: {}", e)))?;
+
+        Ok(PaymentEvent {
+            provider_reference: payload.reference,
+            status: payload.status,
+        })
+    }
+}
+
+/// Start a purchase of a paid public program and open a checkout with the provider
+pub async fn purchase_program(
+    collections: &Collections,
+    provider: &dyn PaymentProvider,
+    user_id: &str,
+    program_id: &str,
+) -> Result<PurchaseResponse, AppError> {
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let program = collections
+        .programs
+        .find_one(doc! {
+            "_id": oid,
+            "deleted_at": null,
+            "public": true,
+            "created_at": { "$ne": null }
+        })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    if program.archived || program.owner_disabled {
+        return Err(AppError::BadRequest(
+            "Program is archived and cannot be purchased".to_string(),
+        ));
+    }
+    let price = match &program.price {
+        Some(price) if !is_free(&program) => price.clone(),
+        _ => return Err(AppError::BadRequest("Program is free".to_string())),
+    };
+    if has_access(collections, user_id, &program).await? {
+        return Err(AppError::Conflict(
+            "You already have access to this program".to_string(),
+        ));
+    }
+
+    let payment_id = ObjectId::new();
+    let checkout = provider
+        .create_checkout(&payment_id.to_hex(), &price)
+        .await?;
+
+    let now = Utc::now();
+    let payment = Payment {
+        id: payment_id,
+        program_id: program_id.to_string(),
+        user_id: user_id.to_string(),
+        provider: provider.name().to_string(),
+        provider_reference: checkout.provider_reference,
+        price,
+        status: PaymentStatus::Pending,
+        created_at: now,
+        updated_at: now,
+    };
+    collections.payments.insert_one(&payment).await?;
+
+    Ok(PurchaseResponse {
+        payment_id: payment.id.to_hex(),
+        status: payment.status,
+        price: payment.price,
+        checkout_url: checkout.checkout_url,
+    })
+}
+
+/// Settle a pending payment; a successful one grants the entitlement
+/// Redelivered events for settled payments are ignored
+pub async fn apply_payment_event(
+    collections: &Collections,
+    provider: &dyn PaymentProvider,
+    event: PaymentEvent,
+) -> Result<(), AppError> {
+    if event.status == PaymentStatus::Pending {
+        return Ok(());
+    }
+
+    let filter = doc! {
+        "provider": provider.name(),
+        "provider_reference": &event.provider_reference,
+    };
+
+    let mut pending_filter = filter.clone();
+    pending_filter.insert("status", PaymentStatus::Pending.as_str());
+
+    let settled = collections
+        .payments
+        .find_one_and_update(
+            pending_filter,
+            doc! {
+                "$set": {
+                    "status": event.status.as_str(),
+                    "updated_at": Utc::now().to_rfc3339()
+                }
+            },
+        )
+        .await?;
+
+    let Some(payment) = settled else {
+        // Already settled, or unknown to this provider
+        return match collections.payments.find_one(filter).await? {
+            Some(_) => Ok(()),
+            None => Err(AppError::NotFound("Payment not found".to_string())),
+        };
+    };
+
+    if event.status == PaymentStatus::Succeeded {
+        grant_entitlement(
+            collections,
+            &payment.program_id,
+            &payment.user_id,
+            Some(payment.id.to_hex()),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Verify and apply a provider webhook delivery
+pub async fn handle_webhook(
+    collections: &Collections,
+    provider: &dyn PaymentProvider,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<(), AppError> {
+    let event = provider.parse_webhook(headers, body)?;
+    apply_payment_event(collections, provider, event).await
+}
+
+/// Finish a local checkout as the buyer, standing in for the provider's hosted page
+pub async fn complete_local_payment(
+    collections: &Collections,
+    provider: &dyn PaymentProvider,
+    user_id: &str,
+    reference: &str,
+    succeeded: bool,
+) -> Result<PaymentResponse, AppError> {
+    if provider.name() != LocalPaymentProvider::NAME {
+        return Err(AppError::NotFound("Payment not found".to_string()));
+    }
+
+    let filter = doc! {
+        "provider": provider.name(),
+        "provider_reference": reference,
+        "user_id": user_id,
+    };
+    collections
+        .payments
+        .find_one(filter.clone())
+        .await?
+        .ok_or_else(|| AppError::NotFound("Payment not found".to_string()))?;
+
+    let status = if succeeded {
+        PaymentStatus::Succeeded
+    } else {
+        PaymentStatus::Failed
+    };
+    apply_payment_event(
+        collections,
+        provider,
+        PaymentEvent {
+            provider_reference: reference.to_string(),
+            status,
+        },
+    )
+    .await?;
+
+    let payment = collections
+        .payments
+        .find_one(filter)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Payment not found".to_string()))?;
+
+    Ok(payment.into())
+}
+
+/// The caller's payments, newest first
+pub async fn get_user_payments(
+    collections: &Collections,
+    user_id: &str,
+) -> Result<Vec<PaymentResponse>, AppError> {
+    let cursor = collections
+        .payments
+        .find(doc! { "user_id": user_id })
+        .sort(doc! { "created_at": -1 })
+        .await?;
+
+    let payments: Vec<Payment> = cursor.try_collect().await?;
+    Ok(payments.into_iter().map(|p| p.into()).collect())
+}
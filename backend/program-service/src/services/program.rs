@@ -1,13 +1,21 @@
-use bson::{doc, oid::ObjectId};
+use std::collections::HashMap;
+
+use bson::{doc, oid::ObjectId, Bson, Document};
 use chrono::Utc;
 use futures::TryStreamExt;
 
 use crate::db::Collections;
 use crate::error::AppError;
 use crate::models::{
-    CreateProgramRequest, CreateProgramResponse, IdMapping, PaginatedProgramResponse, Program,
-    ProgramResponse, ProgramSearchParams, UpdateProgramRequest,
+    ChangeDetail, CreateProgramRequest, CreateProgramResponse, IdMapping,
+    FacetCount, PaginatedProgramResponse, Program, ProgramFacetsResponse, ProgramResponse,
+    ProgramSearchParams, ProgramSort, TagsMode, UpdateProgramRequest, WorkoutExercise,
 };
+use crate::services::entitlement::{ensure_access, normalize_price};
+use crate::services::program_version::{create_program_version, get_program_version};
+use crate::services::revision::bump_revision;
+use crate::services::tag::canonicalize_tags;
+use crate::services::validation::validate_for_publish;
 
 /// Check if an ID is a temporary ID (starts with "temp-")
 fn is_temp_id(id: &str) -> bool {
@@ -42,21 +50,56 @@ pub async fn create_program(
         (oid, None)
     };
 
+    let tags = canonicalize_tags(collections, &req.tags.unwrap_or_default()).await?;
+    let price = normalize_price(req.price)?;
+
     let program = Program {
         id: real_id,
         user_id: user_id.to_string(),
         name: req.name,
         description: req.description,
-        image_url: None,
-        tags: req.tags.unwrap_or_default(),
+        image_key: None,
+        image_variants: None,
+        tags,
         total_weeks: req.total_weeks.unwrap_or(1),
         last_workout_number: 0,
         public: req.public.unwrap_or(false),
         created_at,
         updated_at: now,
+        current_version: None,
+        archived: false,
+        deleted_at: None,
+        owner_disabled: false,
+        forked_from: None,
+        revision: 0,
+        start_count: 0,
+        rating_average: None,
+        rating_count: 0,
+        difficulty: req.difficulty,
+        price,
     };
 
-    collections.programs.insert_one(&program).await?;
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    collections
+        .programs
+        .insert_one(&program)
+        .session(&mut session)
+        .await?;
+
+    // Programs created as published get their first version right away
+    let program = if program.created_at.is_some() {
+        let version = create_program_version(collections, &mut session, &program).await?;
+        Program {
+            current_version: Some(version),
+            ..program
+        }
+    } else {
+        program
+    };
+
+    session.commit_transaction().await?;
 
     Ok(CreateProgramResponse {
         program: program.into(),
@@ -77,6 +120,7 @@ pub async fn get_program(
         .programs
         .find_one(doc! {
             "_id": oid,
+            "deleted_at": null,
             "$or": [
                 { "user_id": user_id },
                 { "public": true, "created_at": { "$ne": null } }
@@ -94,7 +138,7 @@ pub async fn get_user_programs(
 ) -> Result<Vec<ProgramResponse>, AppError> {
     let cursor = collections
         .programs
-        .find(doc! { "user_id": user_id })
+        .find(doc! { "user_id": user_id, "deleted_at": null })
         .await?;
 
     let programs: Vec<Program> = cursor.try_collect().await?;
@@ -106,14 +150,24 @@ pub async fn update_program(
     user_id: &str,
     program_id: &str,
     req: UpdateProgramRequest,
+    expected_revision: Option<i64>,
 ) -> Result<ProgramResponse, AppError> {
     let oid = ObjectId::parse_str(program_id)
         .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
 
+    let tags = match &req.tags {
+        Some(tags) => Some(canonicalize_tags(collections, tags).await?),
+        None => None,
+    };
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
     // Fetch current program
     let program = collections
         .programs
-        .find_one(doc! { "_id": oid, "user_id": user_id })
+        .find_one(doc! { "_id": oid, "user_id": user_id, "deleted_at": null })
+        .session(&mut session)
         .await?
         .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
 
@@ -126,10 +180,18 @@ pub async fn update_program(
     if let Some(description) = req.description {
         update_doc.insert("description", description);
     }
-    if let Some(image_url) = req.image_url {
-        update_doc.insert("image_url", image_url);
+    if let Some(image_variants) = req.image_variants {
+        update_doc.insert(
+            "image_variants",
+            doc! {
+                "thumbnail": image_variants.thumbnail,
+                "card": image_variants.card,
+                "full": image_variants.full,
+            },
+        );
+        update_doc.insert("image_key", Bson::Null);
     }
-    if let Some(tags) = req.tags {
+    if let Some(tags) = tags {
         update_doc.insert("tags", tags);
     }
     if let Some(total_weeks) = req.total_weeks {
@@ -138,24 +200,195 @@ pub async fn update_program(
     if let Some(public) = req.public {
         update_doc.insert("public", public);
     }
+    if let Some(archived) = req.archived {
+        update_doc.insert("archived", archived);
+    }
+    if let Some(difficulty) = req.difficulty {
+        update_doc.insert("difficulty", difficulty.as_str());
+    }
+    if let Some(price) = req.price {
+        let price = normalize_price(Some(price))?.map(|p| {
+            doc! { "amount_cents": p.amount_cents, "currency": p.currency }
+        });
+        update_doc.insert("price", price);
+    }
+
+    // Publishing (or republishing) is refused while the content has errors
+    if req.created == Some(true) {
+        let total_weeks = req.total_weeks.unwrap_or(program.total_weeks);
+        validate_for_publish(collections, &mut session, program_id, total_weeks).await?;
+    }
 
     // One-way transition: draft -> published
     if req.created == Some(true) && program.created_at.is_none() {
         update_doc.insert("created_at", now.to_rfc3339());
     }
 
+    let mut fields: Vec<String> = update_doc
+        .keys()
+        .filter(|k| k.as_str() != "updated_at")
+        .cloned()
+        .collect();
+    // Publishing or republishing snapshots the program as a new immutable version
+    if req.created == Some(true) {
+        fields.push("current_version".to_string());
+    }
+    bump_revision(
+        collections,
+        &mut session,
+        oid,
+        expected_revision,
+        ChangeDetail::ProgramUpdated { fields },
+    )
+    .await?;
+
     collections
         .programs
         .update_one(
             doc! { "_id": oid, "user_id": user_id },
             doc! { "$set": update_doc },
         )
+        .session(&mut session)
         .await?;
 
+    if req.created == Some(true) {
+        let updated = collections
+            .programs
+            .find_one(doc! { "_id": oid, "user_id": user_id, "deleted_at": null })
+            .session(&mut session)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+        create_program_version(collections, &mut session, &updated).await?;
+    }
+
+    session.commit_transaction().await?;
+
     // Return updated program
     get_program(collections, user_id, program_id).await
 }
 
+/// Deep-copy a program and all its workout exercises into a new draft owned by the caller
+/// Public programs can be forked by anyone, drafts and private programs only by their owner
+pub async fn fork_program(
+    collections: &Collections,
+    user_id: &str,
+    program_id: &str,
+) -> Result<ProgramResponse, AppError> {
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let source = collections
+        .programs
+        .find_one(doc! {
+            "_id": oid,
+            "deleted_at": null,
+            "$or": [
+                { "user_id": user_id },
+                { "public": true, "created_at": { "$ne": null } }
+            ]
+        })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    // Forking copies the workouts, so paid programs need a purchase first
+    ensure_access(collections, user_id, &source).await?;
+
+    // Others fork what was published, not the owner's unpublished edits
+    let (name, description, tags, total_weeks, last_workout_number, exercises) =
+        if source.user_id == user_id {
+            let cursor = collections
+                .workout_exercises
+                .find(doc! { "program_id": program_id })
+                .await?;
+            let exercises: Vec<WorkoutExercise> = cursor.try_collect().await?;
+            (
+                source.name,
+                source.description,
+                source.tags,
+                source.total_weeks,
+                source.last_workout_number,
+                exercises,
+            )
+        } else {
+            let current_version = source
+                .current_version
+                .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+            let version = get_program_version(collections, program_id, current_version).await?;
+            (
+                version.name,
+                version.description,
+                version.tags,
+                version.total_weeks,
+                version.last_workout_number,
+                version.exercises,
+            )
+        };
+
+    let now = Utc::now();
+    let fork_id = ObjectId::new();
+
+    // Image is left out: the stored object belongs to the source and is removed when it changes
+    let fork = Program {
+        id: fork_id,
+        user_id: user_id.to_string(),
+        name,
+        description,
+        image_key: None,
+        image_variants: None,
+        tags,
+        total_weeks,
+        last_workout_number,
+        public: false,
+        created_at: None,
+        updated_at: now,
+        current_version: None,
+        archived: false,
+        deleted_at: None,
+        owner_disabled: false,
+        forked_from: Some(program_id.to_string()),
+        revision: 0,
+        start_count: 0,
+        rating_average: None,
+        rating_count: 0,
+        difficulty: source.difficulty,
+        price: None,
+    };
+
+    let fork_program_id = fork_id.to_hex();
+    let copies: Vec<WorkoutExercise> = exercises
+        .into_iter()
+        .map(|e| WorkoutExercise {
+            id: ObjectId::new(),
+            program_id: fork_program_id.clone(),
+            ..e
+        })
+        .collect();
+
+    // The fork and its workouts land together or not at all
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    collections
+        .programs
+        .insert_one(&fork)
+        .session(&mut session)
+        .await?;
+
+    if !copies.is_empty() {
+        collections
+            .workout_exercises
+            .insert_many(&copies)
+            .session(&mut session)
+            .await?;
+    }
+
+    session.commit_transaction().await?;
+
+    Ok(fork.into())
+}
+
+/// Soft-delete a program: hidden from its owner, search and new starts, but its
+/// workouts and versions stay readable for athletes already running it
 pub async fn delete_program(
     collections: &Collections,
     user_id: &str,
@@ -164,55 +397,180 @@ pub async fn delete_program(
     let oid = ObjectId::parse_str(program_id)
         .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
 
-    // Verify ownership and delete
+    // Verify ownership and mark as deleted
     let result = collections
         .programs
-        .delete_one(doc! { "_id": oid, "user_id": user_id })
+        .update_one(
+            doc! { "_id": oid, "user_id": user_id, "deleted_at": null },
+            doc! { "$set": { "deleted_at": Utc::now().to_rfc3339() } },
+        )
         .await?;
 
-    if result.deleted_count == 0 {
+    if result.matched_count == 0 {
         return Err(AppError::NotFound("Program not found".to_string()));
     }
 
-    // Cascade delete all workout_exercises (program_id is stored as string)
-    collections
-        .workout_exercises
-        .delete_many(doc! { "program_id": program_id })
-        .await?;
-
     Ok(())
 }
 
+/// Published public programs that are not archived, deleted or owned by a disabled user
+fn public_filter() -> Document {
+    doc! {
+        "public": true,
+        "created_at": { "$ne": null },
+        "archived": { "$ne": true },
+        "owner_disabled": { "$ne": true },
+        "deleted_at": null
+    }
+}
+
+/// The caller's own programs that are not deleted
+fn user_filter(user_id: &str) -> Document {
+    doc! { "user_id": user_id, "deleted_at": null }
+}
+
 /// Search public programs (public=true and created_at is not null)
-/// Uses MongoDB $facet aggregation for efficient pagination
 pub async fn search_public_programs(
     collections: &Collections,
     params: ProgramSearchParams,
+) -> Result<PaginatedProgramResponse, AppError> {
+    search_programs(collections, public_filter(), params).await
+}
+
+/// Search user's own programs with pagination
+pub async fn search_user_programs(
+    collections: &Collections,
+    user_id: &str,
+    params: ProgramSearchParams,
+) -> Result<PaginatedProgramResponse, AppError> {
+    search_programs(collections, user_filter(user_id), params).await
+}
+
+/// Facet counts for a public program search
+pub async fn public_program_facets(
+    collections: &Collections,
+    params: ProgramSearchParams,
+) -> Result<ProgramFacetsResponse, AppError> {
+    program_facets(collections, public_filter(), params).await
+}
+
+/// Facet counts for a search over the user's own programs
+pub async fn user_program_facets(
+    collections: &Collections,
+    user_id: &str,
+    params: ProgramSearchParams,
+) -> Result<ProgramFacetsResponse, AppError> {
+    program_facets(collections, user_filter(user_id), params).await
+}
+
+/// Add the search term and filters from `params` to `base_filter`
+/// Returns the match filter and the search term, if any
+async fn search_filter(
+    collections: &Collections,
+    base_filter: Document,
+    params: &ProgramSearchParams,
+) -> Result<(Document, Option<String>), AppError> {
+    let mut match_filter = base_filter;
+
+    // Full-text search over name, tags and description (text index)
+    let search = params
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    if let Some(search) = &search {
+        match_filter.insert("$text", doc! { "$search": search });
+    }
+
+    // Filter tags go through the same normalization and aliases as saved tags
+    let tags: Vec<String> = params
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::to_string)
+        .collect();
+    let tags = canonicalize_tags(collections, &tags).await?;
+    if !tags.is_empty() {
+        let operator = match params.tags_mode.unwrap_or(TagsMode::All) {
+            TagsMode::All => "$all",
+            TagsMode::Any => "$in",
+        };
+        match_filter.insert("tags", doc! { operator: tags });
+    }
+
+    let mut weeks = Document::new();
+    if let Some(min) = params.min_weeks {
+        weeks.insert("$gte", min);
+    }
+    if let Some(max) = params.max_weeks {
+        weeks.insert("$lte", max);
+    }
+    if !weeks.is_empty() {
+        match_filter.insert("total_weeks", weeks);
+    }
+
+    // ANDed with the base filter so it can never replace the owner constraint of "mine"
+    if let Some(coach) = params.coach.as_deref().filter(|c| !c.is_empty()) {
+        match_filter.insert("$and", vec![doc! { "user_id": coach }]);
+    }
+
+    if let Some(difficulty) = params.difficulty {
+        match_filter.insert("difficulty", difficulty.as_str());
+    }
+
+    // Workouts per week = last_workout_number / total_weeks
+    let per_week = doc! {
+        "$cond": [
+            { "$gt": ["$total_weeks", 0] },
+            { "$divide": ["$last_workout_number", "$total_weeks"] },
+            0
+        ]
+    };
+    let mut per_week_bounds = Vec::new();
+    if let Some(min) = params.min_workouts_per_week {
+        per_week_bounds.push(doc! { "$gte": [per_week.clone(), min] });
+    }
+    if let Some(max) = params.max_workouts_per_week {
+        per_week_bounds.push(doc! { "$lte": [per_week, max] });
+    }
+    if !per_week_bounds.is_empty() {
+        match_filter.insert("$expr", doc! { "$and": per_week_bounds });
+    }
+
+    Ok((match_filter, search))
+}
+
+/// Shared search over `base_filter` with text relevance, filters and sorting
+/// Uses MongoDB $facet aggregation for efficient pagination
+async fn search_programs(
+    collections: &Collections,
+    base_filter: Document,
+    params: ProgramSearchParams,
 ) -> Result<PaginatedProgramResponse, AppError> {
     let page = params.page.unwrap_or(1).max(1);
     let limit = params.limit.unwrap_or(20).min(100);
     let skip = ((page - 1) * limit) as i64;
 
-    // Build match filter: must be public AND have created_at set
-    let mut match_filter = doc! {
-        "public": true,
-        "created_at": { "$ne": null }
-    };
-
-    // Add search by name OR tags (case-insensitive regex)
-    if let Some(search) = &params.search {
-        if !search.trim().is_empty() {
-            match_filter.insert("$or", vec![
-                doc! { "name": { "$regex": search, "$options": "i" } },
-                doc! { "tags": { "$regex": search, "$options": "i" } },
-            ]);
+    let (match_filter, search) = search_filter(collections, base_filter, &params).await?;
+    // Relevance is the default when searching, otherwise most recently updated first
+    let sort = match params.sort {
+        Some(ProgramSort::Relevance) | None if search.is_some() => {
+            doc! { "score": { "$meta": "textScore" }, "updated_at": -1 }
         }
-    }
+        Some(ProgramSort::Newest) => doc! { "created_at": -1 },
+        Some(ProgramSort::Popularity) => doc! { "start_count": -1, "updated_at": -1 },
+        Some(ProgramSort::Rating) => {
+            doc! { "rating_average": -1, "rating_count": -1, "updated_at": -1 }
+        }
+        _ => doc! { "updated_at": -1 },
+    };
 
     // Build aggregation pipeline with $facet for efficient pagination
     let pipeline = vec![
         doc! { "$match": match_filter },
-        doc! { "$sort": { "updated_at": -1 } },
+        doc! { "$sort": sort },
         doc! {
             "$facet": {
                 "metadata": [{ "$count": "totalCount" }],
@@ -268,85 +626,156 @@ pub async fn search_public_programs(
     }
 }
 
-/// Search user's own programs with pagination
-/// Uses MongoDB $facet aggregation for efficient pagination
-pub async fn search_user_programs(
+/// Duration buckets as (lowest total_weeks, label)
+const DURATION_BUCKETS: [(i32, &str); 4] = [(1, "1-4"), (5, "5-8"), (9, "9-12"), (13, "13+")];
+
+/// Most frequent tags returned as facets
+const MAX_TAG_FACETS: i64 = 50;
+
+/// (_id, count) pairs from one branch of a $facet result
+fn facet_entries(result: &Document, key: &str) -> Vec<(Bson, u64)> {
+    result
+        .get_array(key)
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|b| b.as_document())
+                .map(|d| {
+                    let count = match d.get("count") {
+                        Some(Bson::Int32(n)) => *n as u64,
+                        Some(Bson::Int64(n)) => *n as u64,
+                        _ => 0,
+                    };
+                    (d.get("_id").cloned().unwrap_or(Bson::Null), count)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Tag, duration and difficulty counts for the same query `search_programs` runs
+async fn program_facets(
     collections: &Collections,
-    user_id: &str,
+    base_filter: Document,
     params: ProgramSearchParams,
-) -> Result<PaginatedProgramResponse, AppError> {
-    let page = params.page.unwrap_or(1).max(1);
-    let limit = params.limit.unwrap_or(20).min(100);
-    let skip = ((page - 1) * limit) as i64;
-
-    // Build match filter: must belong to user
-    let mut match_filter = doc! { "user_id": user_id };
+) -> Result<ProgramFacetsResponse, AppError> {
+    let (match_filter, _) = search_filter(collections, base_filter, &params).await?;
 
-    // Add search by name OR tags (case-insensitive regex)
-    if let Some(search) = &params.search {
-        if !search.trim().is_empty() {
-            match_filter.insert("$or", vec![
-                doc! { "name": { "$regex": search, "$options": "i" } },
-                doc! { "tags": { "$regex": search, "$options": "i" } },
-            ]);
-        }
-    }
+    let mut boundaries: Vec<i32> = DURATION_BUCKETS.iter().map(|(lower, _)| *lower).collect();
+    boundaries.push(i32::MAX);
 
-    // Build aggregation pipeline with $facet for efficient pagination
     let pipeline = vec![
         doc! { "$match": match_filter },
-        doc! { "$sort": { "updated_at": -1 } },
         doc! {
             "$facet": {
-                "metadata": [{ "$count": "totalCount" }],
-                "data": [{ "$skip": skip }, { "$limit": limit as i64 }]
+                "tags": [
+                    { "$unwind": "$tags" },
+                    { "$group": { "_id": "$tags", "count": { "$sum": 1 } } },
+                    { "$sort": { "count": -1, "_id": 1 } },
+                    { "$limit": MAX_TAG_FACETS }
+                ],
+                "duration": [
+                    {
+                        "$bucket": {
+                            "groupBy": "$total_weeks",
+                            "boundaries": boundaries,
+                            "default": "other",
+                            "output": { "count": { "$sum": 1 } }
+                        }
+                    }
+                ],
+                "difficulty": [
+                    { "$match": { "difficulty": { "$ne": null } } },
+                    { "$group": { "_id": "$difficulty", "count": { "$sum": 1 } } },
+                    { "$sort": { "count": -1, "_id": 1 } }
+                ]
             }
         },
     ];
 
     let mut cursor = collections.programs.aggregate(pipeline).await?;
+    let Some(result) = cursor.try_next().await? else {
+        return Ok(ProgramFacetsResponse {
+            tags: vec![],
+            duration: vec![],
+            difficulty: vec![],
+        });
+    };
 
-    // Parse the aggregation result
-    if let Some(result) = cursor.try_next().await? {
-        let metadata = result.get_array("metadata").ok();
-        let data = result.get_array("data").ok();
+    let tag_counts: Vec<(String, u64)> = facet_entries(&result, "tags")
+        .into_iter()
+        .filter_map(|(id, count)| id.as_str().map(|t| (t.to_string(), count)))
+        .collect();
 
-        let total = metadata
-            .and_then(|m| m.first())
-            .and_then(|d| d.as_document())
-            .and_then(|d| d.get_i32("totalCount").ok())
-            .unwrap_or(0) as u64;
+    // Display names for tags in the vocabulary
+    let tag_ids: Vec<&String> = tag_counts.iter().map(|(t, _)| t).collect();
+    let cursor = collections
+        .tags
+        .find(doc! { "_id": { "$in": tag_ids } })
+        .await?;
+    let names: HashMap<String, String> = cursor
+        .map_ok(|t| (t.id, t.name))
+        .try_collect()
+        .await?;
 
-        let programs: Vec<ProgramResponse> = data
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|b| b.as_document())
-                    .filter_map(|d| bson::from_document::<Program>(d.clone()).ok())
-                    .map(|p| p.into())
-                    .collect()
+    let tags = tag_counts
+        .into_iter()
+        .map(|(value, count)| FacetCount {
+            label: names.get(&value).cloned(),
+            value,
+            count,
+        })
+        .collect();
+
+    let duration = facet_entries(&result, "duration")
+        .into_iter()
+        .filter_map(|(id, count)| {
+            let lower = id.as_i32()?;
+            let (_, label) = DURATION_BUCKETS.iter().find(|(l, _)| *l == lower)?;
+            Some(FacetCount {
+                value: label.to_string(),
+                label: None,
+                count,
             })
-            .unwrap_or_default();
-
-        let total_pages = if total == 0 {
-            0
-        } else {
-            (total as f64 / limit as f64).ceil() as u64
-        };
-
-        Ok(PaginatedProgramResponse {
-            programs,
-            total,
-            page,
-            limit,
-            total_pages,
         })
-    } else {
-        Ok(PaginatedProgramResponse {
-            programs: vec![],
-            total: 0,
-            page,
-            limit,
-            total_pages: 0,
+        .collect();
+
+    let difficulty = facet_entries(&result, "difficulty")
+        .into_iter()
+        .filter_map(|(id, count)| {
+            id.as_str().map(|d| FacetCount {
+                value: d.to_string(),
+                label: None,
+                count,
+            })
         })
+        .collect();
+
+    Ok(ProgramFacetsResponse {
+        tags,
+        duration,
+        difficulty,
+    })
+}
+
+/// Count a program start (popularity); called by started-program-service
+pub async fn record_program_start(
+    collections: &Collections,
+    program_id: &str,
+) -> Result<(), AppError> {
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let result = collections
+        .programs
+        .update_one(
+            doc! { "_id": oid, "deleted_at": null },
+            doc! { "$inc": { "start_count": 1 } },
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppError::NotFound("Program not found".to_string()));
     }
+
+    Ok(())
 }
@@ -0,0 +1,567 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::TryStreamExt;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{
+    ExerciseReference, ExportedExercise, ExportedProgram, ExportedWeek, ExportedWorkout, Program,
+    ProgramCsvRow, ProgramExport, ProgramResponse, Set, ValidationError, WorkoutExercise,
+    EXPORT_FORMAT, EXPORT_FORMAT_VERSION,
+};
+use crate::services::entitlement::ensure_access;
+use crate::services::program_layout::{check_total_weeks, group_into_weeks, renumber, Workout};
+use crate::services::program_version::get_program_version;
+use crate::services::tag::canonicalize_tags;
+use crate::services::validation::validate_exercises;
+
+/// Build the portable document for a program the caller owns or that is public
+/// Non-owners get the currently published version
+pub async fn export_program(
+    collections: &Collections,
+    user_id: &str,
+    program_id: &str,
+) -> Result<ProgramExport, AppError> {
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let program = collections
+        .programs
+        .find_one(doc! {
+            "_id": oid,
+            "deleted_at": null,
+            "$or": [
+                { "user_id": user_id },
+                { "public": true, "created_at": { "$ne": null } }
+            ]
+        })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    ensure_access(collections, user_id, &program).await?;
+
+    // Others get what was published, not the owner's unpublished edits
+    let (name, description, tags, total_weeks, exercises) = if program.user_id == user_id {
+        let cursor = collections
+            .workout_exercises
+            .find(doc! { "program_id": program_id })
+            .sort(doc! { "week": 1, "workout_number": 1, "order": 1 })
+            .await?;
+        let exercises: Vec<WorkoutExercise> = cursor.try_collect().await?;
+        (program.name, program.description, program.tags, program.total_weeks, exercises)
+    } else {
+        let current_version = program
+            .current_version
+            .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+        let version = get_program_version(collections, program_id, current_version).await?;
+        let mut exercises = version.exercises;
+        exercises.sort_by_key(|e| (e.week, e.workout_number, e.order));
+        (version.name, version.description, version.tags, version.total_weeks, exercises)
+    };
+
+    // Resolve catalog names so the document is readable without the catalog
+    let exercise_ids: Vec<&str> = exercises
+        .iter()
+        .map(|e| e.exercise_id.as_str())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let cursor = collections
+        .exercises
+        .find(doc! { "_id": { "$in": exercise_ids } })
+        .await?;
+    let names: HashMap<String, String> = cursor
+        .map_ok(|e| (e.id, e.name))
+        .try_collect()
+        .await?;
+
+    let weeks: Vec<ExportedWeek> = group_into_weeks(total_weeks, exercises)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, workouts)| ExportedWeek {
+            week: index as i32 + 1,
+            workouts: workouts
+                .into_iter()
+                .map(|workout| ExportedWorkout {
+                    workout_number: workout[0].workout_number,
+                    exercises: workout
+                        .into_iter()
+                        .map(|exercise| ExportedExercise {
+                            order: exercise.order,
+                            exercise: ExerciseReference {
+                                name: names.get(&exercise.exercise_id).cloned(),
+                                id: Some(exercise.exercise_id),
+                            },
+                            volume_metric: exercise.volume_metric,
+                            intensity_metric: exercise.intensity_metric,
+                            notes: exercise.notes,
+                            sets: exercise.sets,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect();
+    let total_weeks = weeks.len() as i32;
+
+    Ok(ProgramExport {
+        format: EXPORT_FORMAT.to_string(),
+        format_version: EXPORT_FORMAT_VERSION,
+        exported_at: Some(Utc::now()),
+        program: ExportedProgram {
+            name,
+            description,
+            tags,
+            total_weeks,
+            difficulty: program.difficulty,
+        },
+        weeks,
+    })
+}
+
+/// Flatten an export into CSV with one row per set
+pub fn export_to_csv(export: &ProgramExport) -> Result<String, AppError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    for week in &export.weeks {
+        for workout in &week.workouts {
+            for exercise in &workout.exercises {
+                let row = |set: Option<&Set>| ProgramCsvRow {
+                    week: week.week,
+                    workout: workout.workout_number,
+                    order: exercise.order,
+                    exercise_id: exercise.exercise.id.clone(),
+                    exercise_name: exercise.exercise.name.clone(),
+                    volume_metric: exercise.volume_metric.clone(),
+                    intensity_metric: exercise.intensity_metric.clone(),
+                    notes: exercise.notes.clone(),
+                    set_number: set.map(|s| s.number),
+                    volume_lower: set.and_then(|s| s.volume_lower),
+                    volume_upper: set.and_then(|s| s.volume_upper),
+                    intensity_lower: set.and_then(|s| s.intensity_lower),
+                    intensity_upper: set.and_then(|s| s.intensity_upper),
+                };
+
+                let rows: Vec<ProgramCsvRow> = if exercise.sets.is_empty() {
+                    vec![row(None)]
+                } else {
+                    exercise.sets.iter().map(|s| row(Some(s))).collect()
+                };
+                for r in rows {
+                    writer.serialize(r).map_err(|e| {
+                        AppError::InternalServerError(format!("Failed to write CSV: {}", e))
+                    })?;
+                }
+            }
+        }
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| AppError::InternalServerError(format!("Failed to write CSV: {}", e)))?;
+    String::from_utf8(bytes)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to write CSV: {}", e)))
+}
+
+/// Parse the one-row-per-set CSV layout back into an export document
+pub fn csv_to_export(name: String, data: &[u8]) -> Result<ProgramExport, AppError> {
+    // week -> workout -> order -> exercise
+    let mut grouped: BTreeMap<i32, BTreeMap<i32, BTreeMap<i32, ExportedExercise>>> =
+        BTreeMap::new();
+
+    let mut reader = csv::Reader::from_reader(data);
+    for (index, result) in reader.deserialize::<ProgramCsvRow>().enumerate() {
+        // Row numbers as a spreadsheet shows them (header is row 1)
+        let row = result
+            .map_err(|e| AppError::BadRequest(format!("Invalid CSV row {}: {}", index + 2, e)))?;
+
+        let exercise = grouped
+            .entry(row.week)
+            .or_default()
+            .entry(row.workout)
+            .or_default()
+            .entry(row.order)
+            .or_insert_with(|| ExportedExercise {
+                order: row.order,
+                exercise: ExerciseReference {
+                    id: row.exercise_id.clone(),
+                    name: row.exercise_name.clone(),
+                },
+                volume_metric: row.volume_metric.clone(),
+                intensity_metric: row.intensity_metric.clone(),
+                notes: row.notes.clone(),
+                sets: Vec::new(),
+            });
+
+        let has_set = row.set_number.is_some()
+            || row.volume_lower.is_some()
+            || row.volume_upper.is_some()
+            || row.intensity_lower.is_some()
+            || row.intensity_upper.is_some();
+        if has_set {
+            exercise.sets.push(Set {
+                number: row.set_number.unwrap_or(exercise.sets.len() as i32 + 1),
+                volume_lower: row.volume_lower,
+                volume_upper: row.volume_upper,
+                intensity_lower: row.intensity_lower,
+                intensity_upper: row.intensity_upper,
+            });
+        }
+    }
+
+    let total_weeks = grouped.keys().max().copied().unwrap_or(1).max(1);
+
+    Ok(ProgramExport {
+        format: EXPORT_FORMAT.to_string(),
+        format_version: EXPORT_FORMAT_VERSION,
+        exported_at: None,
+        program: ExportedProgram {
+            name,
+            description: None,
+            tags: Vec::new(),
+            total_weeks,
+            difficulty: None,
+        },
+        weeks: grouped
+            .into_iter()
+            .map(|(week, workouts)| ExportedWeek {
+                week,
+                workouts: workouts
+                    .into_iter()
+                    .map(|(workout_number, exercises)| ExportedWorkout {
+                        workout_number,
+                        exercises: exercises.into_values().collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    })
+}
+
+/// Create a draft program owned by the caller from an export document
+/// Exercise references are resolved against the catalog by ID, then by name
+pub async fn import_program(
+    collections: &Collections,
+    user_id: &str,
+    import: ProgramExport,
+) -> Result<ProgramResponse, AppError> {
+    if import.format != EXPORT_FORMAT || import.format_version > EXPORT_FORMAT_VERSION {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported format: {} v{}",
+            import.format, import.format_version
+        )));
+    }
+    if import.program.name.trim().is_empty() {
+        return Err(AppError::BadRequest("Program name is required".to_string()));
+    }
+
+    // Catalog lookup tables: IDs as-is, names lowercased
+    let cursor = collections.exercises.find(doc! {}).await?;
+    let catalog: Vec<(String, String)> = cursor.map_ok(|e| (e.id, e.name)).try_collect().await?;
+    let catalog_ids: HashSet<String> = catalog.iter().map(|(id, _)| id.clone()).collect();
+    let catalog_names: HashMap<String, String> = catalog
+        .into_iter()
+        .map(|(id, name)| (name.to_lowercase(), id))
+        .collect();
+
+    let program_oid = ObjectId::new();
+    let program_id = program_oid.to_hex();
+
+    let week_count = import
+        .weeks
+        .iter()
+        .map(|w| w.week)
+        .max()
+        .unwrap_or(0)
+        .max(import.program.total_weeks)
+        .max(1);
+    check_total_weeks(week_count as usize)?;
+
+    let mut errors: Vec<ValidationError> = Vec::new();
+    let mut weeks: Vec<Vec<Workout>> = vec![Vec::new(); week_count as usize];
+    // Workout exercise ID -> path in the document, for reporting validation errors
+    let mut paths: HashMap<String, String> = HashMap::new();
+
+    for (week_index, week) in import.weeks.into_iter().enumerate() {
+        if week.week < 1 {
+            errors.push(ValidationError::new(
+                None,
+                format!("weeks[{}].week", week_index),
+                "week_out_of_range",
+                "Week must be at least 1",
+            ));
+            continue;
+        }
+
+        let mut workouts = week.workouts;
+        workouts.sort_by_key(|w| w.workout_number);
+
+        for (workout_index, workout) in workouts.into_iter().enumerate() {
+            let mut exercises = workout.exercises;
+            exercises.sort_by_key(|e| e.order);
+
+            let mut resolved: Workout = Vec::new();
+            for (exercise_index, exercise) in exercises.into_iter().enumerate() {
+                let path = format!(
+                    "weeks[{}].workouts[{}].exercises[{}]",
+                    week_index, workout_index, exercise_index
+                );
+
+                let exercise_id = exercise
+                    .exercise
+                    .id
+                    .as_ref()
+                    .filter(|id| catalog_ids.contains(*id))
+                    .cloned()
+                    .or_else(|| {
+                        exercise
+                            .exercise
+                            .name
+                            .as_ref()
+                            .and_then(|name| catalog_names.get(&name.trim().to_lowercase()))
+                            .cloned()
+                    });
+                let Some(exercise_id) = exercise_id else {
+                    errors.push(ValidationError::new(
+                        None,
+                        format!("{}.exercise", path),
+                        "unknown_exercise",
+                        format!(
+                            "No catalog exercise matches {}",
+                            exercise
+                                .exercise
+                                .name
+                                .or(exercise.exercise.id)
+                                .unwrap_or_else(|| "an empty reference".to_string())
+                        ),
+                    ));
+                    continue;
+                };
+
+                let id = ObjectId::new();
+                paths.insert(id.to_hex(), path);
+                resolved.push(WorkoutExercise {
+                    id,
+                    program_id: program_id.clone(),
+                    week: week.week,
+                    workout_number: 0, // assigned by renumber
+                    order: exercise.order,
+                    exercise_id,
+                    volume_metric: exercise.volume_metric,
+                    intensity_metric: exercise.intensity_metric,
+                    notes: exercise.notes,
+                    sets: exercise.sets,
+                });
+            }
+
+            if !resolved.is_empty() {
+                weeks[week.week as usize - 1].push(resolved);
+            }
+        }
+    }
+
+    let (exercises, last_workout_number) = renumber(weeks);
+
+    // Structural checks, reported against the document paths
+    errors.extend(
+        validate_exercises(week_count, &exercises, &catalog_ids)
+            .into_iter()
+            .map(|e| {
+                let path = e.id.as_ref().and_then(|id| paths.get(id));
+                ValidationError {
+                    id: None,
+                    field: path.map_or(e.field.clone(), |p| format!("{}.{}", p, e.field)),
+                    ..e
+                }
+            }),
+    );
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+
+    let tags = canonicalize_tags(collections, &import.program.tags).await?;
+
+    let now = Utc::now();
+    let program = Program {
+        id: program_oid,
+        user_id: user_id.to_string(),
+        name: import.program.name,
+        description: import.program.description,
+        image_key: None,
+        image_variants: None,
+        tags,
+        total_weeks: week_count,
+        last_workout_number,
+        public: false,
+        created_at: None,
+        updated_at: now,
+        current_version: None,
+        archived: false,
+        deleted_at: None,
+        owner_disabled: false,
+        forked_from: None,
+        revision: 0,
+        start_count: 0,
+        rating_average: None,
+        rating_count: 0,
+        difficulty: import.program.difficulty,
+        price: None,
+    };
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    collections
+        .programs
+        .insert_one(&program)
+        .session(&mut session)
+        .await?;
+    if !exercises.is_empty() {
+        collections
+            .workout_exercises
+            .insert_many(&exercises)
+            .session(&mut session)
+            .await?;
+    }
+
+    session.commit_transaction().await?;
+
+    Ok(program.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(order: i32, id: &str, notes: Option<&str>, sets: Vec<Set>) -> ExportedExercise {
+        ExportedExercise {
+            order,
+            exercise: ExerciseReference {
+                id: Some(id.to_string()),
+                name: Some(format!("{} name", id)),
+            },
+            volume_metric: Some("reps".to_string()),
+            intensity_metric: Some("RPE".to_string()),
+            notes: notes.map(str::to_string),
+            sets,
+        }
+    }
+
+    fn set(number: i32, reps: f64, rpe: Option<f64>) -> Set {
+        Set {
+            number,
+            volume_lower: Some(reps),
+            volume_upper: Some(reps),
+            intensity_lower: rpe,
+            intensity_upper: rpe,
+        }
+    }
+
+    fn export(weeks: Vec<ExportedWeek>) -> ProgramExport {
+        ProgramExport {
+            format: EXPORT_FORMAT.to_string(),
+            format_version: EXPORT_FORMAT_VERSION,
+            exported_at: None,
+            program: ExportedProgram {
+                name: "Strength".to_string(),
+                description: None,
+                tags: Vec::new(),
+                total_weeks: 3,
+                difficulty: None,
+            },
+            weeks,
+        }
+    }
+
+    fn json(export: &ProgramExport) -> serde_json::Value {
+        serde_json::to_value(export).unwrap()
+    }
+
+    #[test]
+    fn csv_round_trip_keeps_workouts_and_sets() {
+        let original = export(vec![
+            ExportedWeek {
+                week: 1,
+                workouts: vec![ExportedWorkout {
+                    workout_number: 1,
+                    exercises: vec![
+                        exercise(
+                            0,
+                            "squat",
+                            None,
+                            vec![set(1, 5.0, Some(7.0)), set(2, 5.0, Some(8.5))],
+                        ),
+                        exercise(
+                            1,
+                            "plank",
+                            Some("Hold, don't \"sag\"\nsecond line"),
+                            Vec::new(),
+                        ),
+                    ],
+                }],
+            },
+            ExportedWeek {
+                week: 3,
+                workouts: vec![
+                    ExportedWorkout {
+                        workout_number: 2,
+                        exercises: vec![exercise(0, "bench", None, vec![set(1, 8.0, None)])],
+                    },
+                    ExportedWorkout {
+                        workout_number: 3,
+                        exercises: vec![exercise(0, "row", None, vec![set(1, 10.0, Some(6.0))])],
+                    },
+                ],
+            },
+        ]);
+
+        let csv = export_to_csv(&original).unwrap();
+        // Header plus one row per set, and one for the exercise without sets
+        assert_eq!(csv.lines().filter(|l| !l.is_empty()).count(), 1 + 2 + 1 + 1 + 1 + 1);
+
+        let parsed = csv_to_export("Strength".to_string(), csv.as_bytes()).unwrap();
+        assert_eq!(json(&parsed), json(&original));
+    }
+
+    #[test]
+    fn csv_rows_are_grouped_regardless_of_order() {
+        let csv = "\
+week,workout,order,exercise_id,exercise_name,volume_metric,intensity_metric,notes,set_number,volume_lower,volume_upper,intensity_lower,intensity_upper
+2,2,0,bench,,,,,,8,8,,
+1,1,1,row,,,,,,10,10,,
+1,1,0,squat,,,,,,5,5,,
+1,1,0,squat,,,,,,3,3,,
+";
+        let parsed = csv_to_export("Imported".to_string(), csv.as_bytes()).unwrap();
+        assert_eq!(parsed.program.total_weeks, 2);
+
+        let week = &parsed.weeks[0];
+        let ids: Vec<&str> = week.workouts[0]
+            .exercises
+            .iter()
+            .map(|e| e.exercise.id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["squat", "row"]);
+
+        // Missing set numbers continue the exercise's sequence
+        let squat = &week.workouts[0].exercises[0];
+        let sets: Vec<(i32, Option<f64>)> =
+            squat.sets.iter().map(|s| (s.number, s.volume_lower)).collect();
+        assert_eq!(sets, [(1, Some(5.0)), (2, Some(3.0))]);
+    }
+
+    #[test]
+    fn csv_errors_report_spreadsheet_row() {
+        let csv = "\
+week,workout,order,exercise_id,exercise_name,volume_metric,intensity_metric,notes,set_number,volume_lower,volume_upper,intensity_lower,intensity_upper
+1,1,0,squat,,,,,,5,5,,
+1,one,0,squat,,,,,,5,5,,
+";
+        match csv_to_export("Imported".to_string(), csv.as_bytes()) {
+            Err(AppError::BadRequest(message)) => assert!(message.contains("row 3"), "{}", message),
+            other => panic!("expected a CSV error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use bson::{doc, oid::ObjectId};
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{ProgramResponse, UpdateProgramRequest};
+use crate::services::image;
+use crate::services::program::update_program;
+use crate::services::storage::{ObjectStorage, StoredObject};
+
+/// Process an uploaded cover image, store its variants and point the program at them
+/// The previous image is removed once the program references the new one
+pub async fn set_program_image(
+    collections: &Collections,
+    storage: &dyn ObjectStorage,
+    user_id: &str,
+    program_id: &str,
+    data: Vec<u8>,
+    expected_revision: Option<i64>,
+) -> Result<ProgramResponse, AppError> {
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    // Verify program ownership
+    let program = collections
+        .programs
+        .find_one(doc! { "_id": oid, "user_id": user_id, "deleted_at": null })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    let processed = image::process_image(data).await?;
+    let prefix = format!("programs/{}/{}", program_id, uuid::Uuid::new_v4());
+    let variants = image::store_image(storage, &prefix, processed).await?;
+
+    let updated = update_program(
+        collections,
+        user_id,
+        program_id,
+        UpdateProgramRequest {
+            name: None,
+            description: None,
+            image_variants: Some(variants.clone()),
+            tags: None,
+            total_weeks: None,
+            public: None,
+            created: None,
+            archived: None,
+            difficulty: None,
+            price: None,
+        },
+        expected_revision,
+    )
+    .await;
+
+    // Don't leave the new objects behind if the program could not be updated
+    let updated = match updated {
+        Ok(updated) => updated,
+        Err(e) => {
+            image::delete_image(storage, &variants).await;
+            return Err(e);
+        }
+    };
+
+    if let Some(old) = program.image() {
+        image::delete_image(storage, &old).await;
+    }
+
+    Ok(updated)
+}
+
+/// Read a stored object for the static file route
+pub async fn get_file(
+    storage: &dyn ObjectStorage,
+    key: &str,
+) -> Result<StoredObject, AppError> {
+    storage
+        .get(key)
+        .await?
+        .ok_or_else(|| AppError::NotFound("File not found".to_string()))
+}
@@ -0,0 +1,602 @@
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::ClientSession;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{ChangeDetail, ProgramResponse, WeekResponse, WorkoutExercise, WorkoutGroup};
+use crate::services::program::get_program;
+use crate::services::revision::bump_revision;
+use crate::services::started_program_client::StartedProgramClient;
+
+/// Upper bound on program length so repeats can't blow up a program
+pub const MAX_TOTAL_WEEKS: usize = 104;
+
+/// A workout is the ordered list of its exercises
+pub type Workout = Vec<WorkoutExercise>;
+
+/// Structural edit applied to a program's weeks and workouts
+#[derive(Debug)]
+pub enum LayoutOperation {
+    CopyWeek { week: i32, to_week: i32 },
+    RepeatWeeks { week: i32, through_week: i32, times: i32 },
+    InsertWeek { at: i32 },
+    RemoveWeek { week: i32 },
+    CopyWorkout { workout_number: i32, to_week: i32 },
+    MoveWorkout { workout_number: i32, to_week: i32 },
+    ReorderWorkouts { week: i32, workout_numbers: Vec<i32> },
+}
+
+impl LayoutOperation {
+    /// Name recorded in the program change log
+    pub fn name(&self) -> &'static str {
+        match self {
+            LayoutOperation::CopyWeek { .. } => "copy_week",
+            LayoutOperation::RepeatWeeks { .. } => "repeat_weeks",
+            LayoutOperation::InsertWeek { .. } => "insert_week",
+            LayoutOperation::RemoveWeek { .. } => "remove_week",
+            LayoutOperation::CopyWorkout { .. } => "copy_workout",
+            LayoutOperation::MoveWorkout { .. } => "move_workout",
+            LayoutOperation::ReorderWorkouts { .. } => "reorder_workouts",
+        }
+    }
+}
+
+/// Apply a layout operation and renumber `week`, `workout_number`, `total_weeks`
+/// and `last_workout_number` in a single transaction
+pub async fn apply_layout_operation(
+    collections: &Collections,
+    started_programs: &StartedProgramClient<'_>,
+    user_id: &str,
+    program_id: &str,
+    operation: LayoutOperation,
+    expected_revision: Option<i64>,
+) -> Result<ProgramResponse, AppError> {
+    ensure_no_live_runs(started_programs, program_id).await?;
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    let (program_oid, mut weeks) = load_layout(collections, &mut session, user_id, program_id).await?;
+    let detail = ChangeDetail::LayoutChanged {
+        operation: operation.name().to_string(),
+    };
+    apply(&mut weeks, operation)?;
+    bump_revision(collections, &mut session, program_oid, expected_revision, detail).await?;
+    save_layout(collections, &mut session, program_oid, program_id, weeks).await?;
+
+    commit_without_live_runs(started_programs, &mut session, program_id).await?;
+
+    get_program(collections, user_id, program_id).await
+}
+
+/// Refuse to renumber workouts while athletes run the program from a draft:
+/// their `current_workout_number` points into the live numbering
+pub async fn ensure_no_live_runs(
+    started_programs: &StartedProgramClient<'_>,
+    program_id: &str,
+) -> Result<(), AppError> {
+    if started_programs.program_usage(program_id).await? > 0 {
+        return Err(AppError::Conflict(
+            "Workouts can't be renumbered while athletes follow this program's unpublished workouts"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Commit a renumbering transaction after checking again for live runs, so a run
+/// started from the draft while the layout was rewritten aborts the transaction instead
+/// of being left pointing at the old numbering.
+/// Pinned runs are unaffected: they read workouts from their version snapshot
+pub async fn commit_without_live_runs(
+    started_programs: &StartedProgramClient<'_>,
+    session: &mut ClientSession,
+    program_id: &str,
+) -> Result<(), AppError> {
+    // Dropping the session on error aborts the transaction
+    ensure_no_live_runs(started_programs, program_id).await?;
+    session.commit_transaction().await?;
+    Ok(())
+}
+
+/// Load an owned program's workouts grouped into weeks, inside the caller's transaction
+pub async fn load_layout(
+    collections: &Collections,
+    session: &mut ClientSession,
+    user_id: &str,
+    program_id: &str,
+) -> Result<(ObjectId, Vec<Vec<Workout>>), AppError> {
+    let program_oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    // Verify program ownership
+    let program = collections
+        .programs
+        .find_one(doc! { "_id": program_oid, "user_id": user_id, "deleted_at": null })
+        .session(&mut *session)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    let mut cursor = collections
+        .workout_exercises
+        .find(doc! { "program_id": program_id })
+        .sort(doc! { "workout_number": 1, "order": 1 })
+        .session(&mut *session)
+        .await?;
+    let exercises: Vec<WorkoutExercise> = cursor.stream(session).try_collect().await?;
+
+    Ok((program_oid, group_into_weeks(program.total_weeks, exercises)?))
+}
+
+/// Renumber and rewrite a program's workouts and totals inside the caller's transaction
+/// Returns the exercises as saved
+pub async fn save_layout(
+    collections: &Collections,
+    session: &mut ClientSession,
+    program_oid: ObjectId,
+    program_id: &str,
+    weeks: Vec<Vec<Workout>>,
+) -> Result<Vec<WorkoutExercise>, AppError> {
+    check_total_weeks(weeks.len())?;
+
+    let total_weeks = weeks.len() as i32;
+    let (exercises, last_workout_number) = renumber(weeks);
+
+    // Rewrite the whole program so every renumbered row lands together
+    collections
+        .workout_exercises
+        .delete_many(doc! { "program_id": program_id })
+        .session(&mut *session)
+        .await?;
+
+    if !exercises.is_empty() {
+        collections
+            .workout_exercises
+            .insert_many(&exercises)
+            .session(&mut *session)
+            .await?;
+    }
+
+    collections
+        .programs
+        .update_one(
+            doc! { "_id": program_oid },
+            doc! { "$set": {
+                "total_weeks": total_weeks,
+                "last_workout_number": last_workout_number,
+                "updated_at": Utc::now().to_rfc3339()
+            } },
+        )
+        .session(&mut *session)
+        .await?;
+
+    Ok(exercises)
+}
+
+pub fn check_total_weeks(total_weeks: usize) -> Result<(), AppError> {
+    if total_weeks > MAX_TOTAL_WEEKS {
+        return Err(AppError::BadRequest(format!(
+            "A program can have at most {} weeks",
+            MAX_TOTAL_WEEKS
+        )));
+    }
+    Ok(())
+}
+
+/// Group exercises (sorted by workout_number, order) into weeks of workouts
+/// Rows with a week below 1 are rejected rather than moved, so nothing is silently rewritten
+pub fn group_into_weeks(
+    total_weeks: i32,
+    exercises: Vec<WorkoutExercise>,
+) -> Result<Vec<Vec<Workout>>, AppError> {
+    if let Some(exercise) = exercises.iter().find(|e| e.week < 1) {
+        return Err(AppError::BadRequest(format!(
+            "Exercise {} has invalid week {}",
+            exercise.id.to_hex(),
+            exercise.week
+        )));
+    }
+
+    let week_count = exercises
+        .iter()
+        .map(|e| e.week)
+        .max()
+        .unwrap_or(0)
+        .max(total_weeks)
+        .max(1) as usize;
+
+    let mut weeks: Vec<Vec<Workout>> = vec![Vec::new(); week_count];
+    for workout in group_workouts(exercises) {
+        weeks[(workout[0].week - 1) as usize].push(workout);
+    }
+
+    Ok(weeks)
+}
+
+/// Split exercises sorted by workout_number and order into workouts
+pub fn group_workouts(exercises: impl IntoIterator<Item = WorkoutExercise>) -> Vec<Workout> {
+    let mut workouts: Vec<Workout> = Vec::new();
+    for exercise in exercises {
+        match workouts.last_mut() {
+            Some(workout) if workout[0].workout_number == exercise.workout_number => {
+                workout.push(exercise)
+            }
+            _ => workouts.push(vec![exercise]),
+        }
+    }
+    workouts
+}
+
+/// Build a week's response from its exercises, sorted by workout_number and order
+pub fn week_response(
+    program_id: &str,
+    total_weeks: i32,
+    week: i32,
+    exercises: impl IntoIterator<Item = WorkoutExercise>,
+) -> WeekResponse {
+    let workouts = group_workouts(exercises)
+        .into_iter()
+        .map(|workout| WorkoutGroup {
+            workout_number: workout[0].workout_number,
+            exercises: workout.into_iter().map(|e| e.into()).collect(),
+        })
+        .collect();
+
+    WeekResponse {
+        program_id: program_id.to_string(),
+        week,
+        total_weeks,
+        workouts,
+    }
+}
+
+/// Flatten weeks back into exercises with contiguous week and workout numbers
+/// Returns the exercises and the last workout number
+pub fn renumber(weeks: Vec<Vec<Workout>>) -> (Vec<WorkoutExercise>, i32) {
+    let mut exercises = Vec::new();
+    let mut workout_number = 0;
+
+    for (week_index, week) in weeks.into_iter().enumerate() {
+        for workout in week {
+            workout_number += 1;
+            for exercise in workout {
+                exercises.push(WorkoutExercise {
+                    week: week_index as i32 + 1,
+                    workout_number,
+                    ..exercise
+                });
+            }
+        }
+    }
+
+    (exercises, workout_number)
+}
+
+fn apply(weeks: &mut Vec<Vec<Workout>>, operation: LayoutOperation) -> Result<(), AppError> {
+    match operation {
+        LayoutOperation::CopyWeek { week, to_week } => {
+            let source = week_index(weeks, week)?;
+            let copy = weeks[source].iter().map(|w| duplicate(w)).collect();
+            let target = target_week_index(weeks, to_week)?;
+            weeks[target] = copy;
+        }
+        LayoutOperation::RepeatWeeks { week, through_week, times } => {
+            let start = week_index(weeks, week)?;
+            let end = week_index(weeks, through_week)?;
+            if end < start {
+                return Err(AppError::BadRequest(
+                    "through_week must not be before week".to_string(),
+                ));
+            }
+            if times < 1 {
+                return Err(AppError::BadRequest("times must be at least 1".to_string()));
+            }
+            check_total_weeks((end - start + 1) * times as usize + weeks.len())?;
+
+            let block: Vec<Vec<Workout>> = weeks[start..=end].to_vec();
+            let copies: Vec<Vec<Workout>> = (0..times)
+                .flat_map(|_| block.iter())
+                .map(|week| week.iter().map(|w| duplicate(w)).collect())
+                .collect();
+            weeks.splice(end + 1..end + 1, copies);
+        }
+        LayoutOperation::InsertWeek { at } => {
+            if at < 1 || at as usize > weeks.len() + 1 {
+                return Err(AppError::BadRequest(format!("Invalid week position: {}", at)));
+            }
+            weeks.insert(at as usize - 1, Vec::new());
+        }
+        LayoutOperation::RemoveWeek { week } => {
+            let index = week_index(weeks, week)?;
+            if weeks.len() == 1 {
+                return Err(AppError::BadRequest(
+                    "A program must have at least one week".to_string(),
+                ));
+            }
+            weeks.remove(index);
+        }
+        LayoutOperation::CopyWorkout { workout_number, to_week } => {
+            let (week, position) = find_workout(weeks, workout_number)?;
+            let copy = duplicate(&weeks[week][position]);
+            let target = target_week_index(weeks, to_week)?;
+            weeks[target].push(copy);
+        }
+        LayoutOperation::MoveWorkout { workout_number, to_week } => {
+            let (week, position) = find_workout(weeks, workout_number)?;
+            let target = target_week_index(weeks, to_week)?;
+            let workout = weeks[week].remove(position);
+            weeks[target].push(workout);
+        }
+        LayoutOperation::ReorderWorkouts { week, workout_numbers } => {
+            let index = week_index(weeks, week)?;
+            let mut current: Vec<i32> = weeks[index].iter().map(|w| w[0].workout_number).collect();
+            let mut requested = workout_numbers.clone();
+            current.sort_unstable();
+            requested.sort_unstable();
+            if current != requested {
+                return Err(AppError::BadRequest(
+                    "workout_numbers must list every workout of the week exactly once".to_string(),
+                ));
+            }
+
+            let mut workouts = std::mem::take(&mut weeks[index]);
+            for number in workout_numbers {
+                let position = workouts
+                    .iter()
+                    .position(|w| w[0].workout_number == number)
+                    .expect("validated above");
+                weeks[index].push(workouts.remove(position));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Index of an existing week
+pub fn week_index(weeks: &[Vec<Workout>], week: i32) -> Result<usize, AppError> {
+    if week < 1 || week as usize > weeks.len() {
+        return Err(AppError::BadRequest(format!("Week {} does not exist", week)));
+    }
+    Ok(week as usize - 1)
+}
+
+/// Index of a target week, appending empty weeks when it lies past the end
+pub fn target_week_index(weeks: &mut Vec<Vec<Workout>>, week: i32) -> Result<usize, AppError> {
+    if week < 1 || week as usize > MAX_TOTAL_WEEKS {
+        return Err(AppError::BadRequest(format!("Invalid week: {}", week)));
+    }
+    if week as usize > weeks.len() {
+        weeks.resize(week as usize, Vec::new());
+    }
+    Ok(week as usize - 1)
+}
+
+/// Locate a workout by number as (week index, position within week)
+fn find_workout(weeks: &[Vec<Workout>], workout_number: i32) -> Result<(usize, usize), AppError> {
+    weeks
+        .iter()
+        .enumerate()
+        .find_map(|(week, workouts)| {
+            workouts
+                .iter()
+                .position(|w| w[0].workout_number == workout_number)
+                .map(|position| (week, position))
+        })
+        .ok_or_else(|| AppError::NotFound(format!("Workout {} not found", workout_number)))
+}
+
+/// Clone a workout with fresh exercise IDs
+pub fn duplicate(workout: &[WorkoutExercise]) -> Workout {
+    workout
+        .iter()
+        .map(|e| WorkoutExercise {
+            id: ObjectId::new(),
+            ..e.clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(week: i32, workout_number: i32, order: i32) -> WorkoutExercise {
+        WorkoutExercise {
+            id: ObjectId::new(),
+            program_id: "program".to_string(),
+            week,
+            workout_number,
+            order,
+            exercise_id: format!("exercise-{}-{}", workout_number, order),
+            volume_metric: None,
+            intensity_metric: None,
+            notes: None,
+            sets: Vec::new(),
+        }
+    }
+
+    /// Two weeks: workouts 1 and 2 in week 1, workout 3 in week 2
+    fn layout() -> Vec<Vec<Workout>> {
+        let exercises = vec![
+            exercise(1, 1, 0),
+            exercise(1, 1, 1),
+            exercise(1, 2, 0),
+            exercise(2, 3, 0),
+        ];
+        group_into_weeks(2, exercises).unwrap()
+    }
+
+    /// (week, workout_number, exercise_id) of every row after renumbering
+    fn flattened(weeks: Vec<Vec<Workout>>) -> Vec<(i32, i32, String)> {
+        renumber(weeks)
+            .0
+            .into_iter()
+            .map(|e| (e.week, e.workout_number, e.exercise_id))
+            .collect()
+    }
+
+    fn row(week: i32, workout_number: i32, exercise_id: &str) -> (i32, i32, String) {
+        (week, workout_number, exercise_id.to_string())
+    }
+
+    #[test]
+    fn groups_workouts_into_weeks_and_pads_empty_weeks() {
+        let weeks = group_into_weeks(3, vec![exercise(1, 1, 0), exercise(1, 1, 1)]).unwrap();
+        assert_eq!(weeks.len(), 3);
+        assert_eq!(weeks[0].len(), 1);
+        assert_eq!(weeks[0][0].len(), 2);
+        assert!(weeks[1].is_empty() && weeks[2].is_empty());
+
+        // Rows past total_weeks extend the layout instead of being dropped
+        assert_eq!(group_into_weeks(1, vec![exercise(2, 1, 0)]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rejects_rows_with_invalid_week() {
+        assert!(matches!(
+            group_into_weeks(1, vec![exercise(0, 1, 0)]),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn renumbers_weeks_and_workouts_contiguously() {
+        let weeks = vec![
+            vec![vec![exercise(4, 7, 0)]],
+            Vec::new(),
+            vec![vec![exercise(9, 2, 0), exercise(9, 2, 1)], vec![exercise(9, 5, 0)]],
+        ];
+        let (exercises, last_workout_number) = renumber(weeks);
+        let numbers: Vec<(i32, i32, i32)> = exercises
+            .iter()
+            .map(|e| (e.week, e.workout_number, e.order))
+            .collect();
+        assert_eq!(numbers, [(1, 1, 0), (3, 2, 0), (3, 2, 1), (3, 3, 0)]);
+        assert_eq!(last_workout_number, 3);
+    }
+
+    #[test]
+    fn copy_week_replaces_target_with_fresh_ids() {
+        let mut weeks = layout();
+        let source_ids: Vec<ObjectId> = weeks[0].iter().flatten().map(|e| e.id).collect();
+        apply(&mut weeks, LayoutOperation::CopyWeek { week: 1, to_week: 2 }).unwrap();
+
+        assert!(weeks[1].iter().flatten().all(|e| !source_ids.contains(&e.id)));
+        assert_eq!(
+            flattened(weeks),
+            [
+                row(1, 1, "exercise-1-0"),
+                row(1, 1, "exercise-1-1"),
+                row(1, 2, "exercise-2-0"),
+                row(2, 3, "exercise-1-0"),
+                row(2, 3, "exercise-1-1"),
+                row(2, 4, "exercise-2-0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn repeat_weeks_inserts_copies_after_block() {
+        let mut weeks = layout();
+        let operation = LayoutOperation::RepeatWeeks { week: 2, through_week: 2, times: 2 };
+        apply(&mut weeks, operation).unwrap();
+        let rows = flattened(weeks);
+        assert_eq!(rows.len(), 6);
+        assert_eq!(
+            rows[3..],
+            [
+                row(2, 3, "exercise-3-0"),
+                row(3, 4, "exercise-3-0"),
+                row(4, 5, "exercise-3-0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn repeat_weeks_respects_week_limit() {
+        let mut weeks = layout();
+        let operation = LayoutOperation::RepeatWeeks { week: 1, through_week: 2, times: 52 };
+        assert!(matches!(apply(&mut weeks, operation), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn insert_and_remove_week_shift_following_weeks() {
+        let mut weeks = layout();
+        apply(&mut weeks, LayoutOperation::InsertWeek { at: 1 }).unwrap();
+        assert_eq!(weeks.len(), 3);
+        assert!(weeks[0].is_empty());
+
+        apply(&mut weeks, LayoutOperation::RemoveWeek { week: 2 }).unwrap();
+        assert_eq!(flattened(weeks), [row(2, 1, "exercise-3-0")]);
+    }
+
+    #[test]
+    fn remove_week_keeps_at_least_one_week() {
+        let mut weeks = group_into_weeks(1, Vec::new()).unwrap();
+        let result = apply(&mut weeks, LayoutOperation::RemoveWeek { week: 1 });
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn move_workout_appends_to_target_week() {
+        let mut weeks = layout();
+        apply(&mut weeks, LayoutOperation::MoveWorkout { workout_number: 1, to_week: 3 }).unwrap();
+        assert_eq!(
+            flattened(weeks),
+            [
+                row(1, 1, "exercise-2-0"),
+                row(2, 2, "exercise-3-0"),
+                row(3, 3, "exercise-1-0"),
+                row(3, 3, "exercise-1-1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn copy_workout_keeps_source() {
+        let mut weeks = layout();
+        apply(&mut weeks, LayoutOperation::CopyWorkout { workout_number: 3, to_week: 1 }).unwrap();
+        assert_eq!(
+            flattened(weeks),
+            [
+                row(1, 1, "exercise-1-0"),
+                row(1, 1, "exercise-1-1"),
+                row(1, 2, "exercise-2-0"),
+                row(1, 3, "exercise-3-0"),
+                row(2, 4, "exercise-3-0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reorder_workouts_requires_every_workout_once() {
+        let mut weeks = layout();
+        let partial = LayoutOperation::ReorderWorkouts { week: 1, workout_numbers: vec![2] };
+        assert!(matches!(apply(&mut weeks, partial), Err(AppError::BadRequest(_))));
+
+        let reorder = LayoutOperation::ReorderWorkouts { week: 1, workout_numbers: vec![2, 1] };
+        apply(&mut weeks, reorder).unwrap();
+        assert_eq!(
+            flattened(weeks)[..3],
+            [
+                row(1, 1, "exercise-2-0"),
+                row(1, 2, "exercise-1-0"),
+                row(1, 2, "exercise-1-1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_weeks_and_workouts_are_rejected() {
+        let mut weeks = layout();
+        let copy = LayoutOperation::CopyWeek { week: 3, to_week: 1 };
+        assert!(matches!(apply(&mut weeks, copy), Err(AppError::BadRequest(_))));
+        let moved = LayoutOperation::MoveWorkout { workout_number: 9, to_week: 1 };
+        assert!(matches!(apply(&mut weeks, moved), Err(AppError::NotFound(_))));
+        let past_limit = LayoutOperation::CopyWorkout { workout_number: 1, to_week: 105 };
+        assert!(matches!(apply(&mut weeks, past_limit), Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,116 @@
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::ClientSession;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{NextWorkoutResponse, Program, ProgramVersion, WeekResponse, WorkoutExercise};
+use crate::services::program_layout::week_response;
+
+/// Snapshot the program and all its workout exercises as a new immutable version
+/// Runs in the caller's transaction so concurrent publishes cannot both claim the same version
+pub async fn create_program_version(
+    collections: &Collections,
+    session: &mut ClientSession,
+    program: &Program,
+) -> Result<i32, AppError> {
+    let program_id = program.id.to_hex();
+
+    let mut cursor = collections
+        .workout_exercises
+        .find(doc! { "program_id": &program_id })
+        .sort(doc! { "workout_number": 1, "order": 1 })
+        .session(&mut *session)
+        .await?;
+
+    let exercises: Vec<WorkoutExercise> = cursor.stream(&mut *session).try_collect().await?;
+
+    let version = program.current_version.unwrap_or(0) + 1;
+
+    let snapshot = ProgramVersion {
+        id: ObjectId::new(),
+        program_id,
+        version,
+        name: program.name.clone(),
+        description: program.description.clone(),
+        tags: program.tags.clone(),
+        total_weeks: program.total_weeks,
+        last_workout_number: program.last_workout_number,
+        exercises,
+        published_at: Utc::now(),
+    };
+
+    collections
+        .program_versions
+        .insert_one(&snapshot)
+        .session(&mut *session)
+        .await?;
+
+    collections
+        .programs
+        .update_one(
+            doc! { "_id": program.id },
+            doc! { "$set": { "current_version": version } },
+        )
+        .session(&mut *session)
+        .await?;
+
+    Ok(version)
+}
+
+pub async fn get_program_version(
+    collections: &Collections,
+    program_id: &str,
+    version: i32,
+) -> Result<ProgramVersion, AppError> {
+    collections
+        .program_versions
+        .find_one(doc! { "program_id": program_id, "version": version })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program version not found".to_string()))
+}
+
+/// Get the next workout after a given workout_number from a published version
+/// Returns None if there are no more workouts
+pub fn next_workout_in_version(
+    version: &ProgramVersion,
+    last_workout_number: i32,
+) -> Option<NextWorkoutResponse> {
+    let next_workout_number = version
+        .exercises
+        .iter()
+        .map(|e| e.workout_number)
+        .filter(|n| *n > last_workout_number)
+        .min()?;
+
+    let mut exercises: Vec<&WorkoutExercise> = version
+        .exercises
+        .iter()
+        .filter(|e| e.workout_number == next_workout_number)
+        .collect();
+    exercises.sort_by_key(|e| e.order);
+
+    Some(NextWorkoutResponse {
+        workout_number: next_workout_number,
+        week: exercises[0].week,
+        exercises: exercises.into_iter().map(|e| e.clone().into()).collect(),
+    })
+}
+
+/// Get a week's workouts (grouped) from a published version
+pub fn week_in_version(version: &ProgramVersion, week: i32) -> WeekResponse {
+    let mut exercises: Vec<&WorkoutExercise> = version
+        .exercises
+        .iter()
+        .filter(|e| e.week == week)
+        .collect();
+    exercises.sort_by_key(|e| (e.workout_number, e.order));
+
+    week_response(
+        &version.program_id,
+        version.total_weeks,
+        week,
+        exercises.into_iter().cloned(),
+    )
+}
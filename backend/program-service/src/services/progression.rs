@@ -0,0 +1,384 @@
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{
+    ChangeDetail, DeloadRule, ProgressionRequest, ProgressionResponse, ProgressionRule, Set,
+    WorkoutExercise,
+};
+use crate::services::program_layout::{
+    check_total_weeks, commit_without_live_runs, duplicate, ensure_no_live_runs, load_layout,
+    renumber, save_layout, target_week_index, week_index, week_response, Workout,
+};
+use crate::services::revision::bump_revision;
+use crate::services::started_program_client::StartedProgramClient;
+use crate::services::validation::{known_exercise_ids, validate_exercises};
+
+const DEFAULT_DELOAD_VOLUME_FACTOR: f64 = 0.6;
+const DEFAULT_DELOAD_INTENSITY_FACTOR: f64 = 0.9;
+/// Smallest rep increment for double progression; finer steps make no sense in a prescription
+const MIN_REP_STEP: f64 = 0.5;
+
+/// Generate the weeks following `source_week` by applying a progression rule to its sets
+/// The generated weeks are validated like published content; in preview mode the result
+/// is returned without saving
+pub async fn generate_progression(
+    collections: &Collections,
+    started_programs: &StartedProgramClient<'_>,
+    user_id: &str,
+    program_id: &str,
+    source_week: i32,
+    req: ProgressionRequest,
+    expected_revision: Option<i64>,
+) -> Result<ProgressionResponse, AppError> {
+    validate_request(&req)?;
+    if !req.preview {
+        ensure_no_live_runs(started_programs, program_id).await?;
+    }
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    let (program_oid, mut weeks) = load_layout(collections, &mut session, user_id, program_id).await?;
+
+    let source_index = week_index(&weeks, source_week)?;
+    check_total_weeks(source_index + 1 + req.weeks as usize)?;
+
+    let source = weeks[source_index].clone();
+    let mut progressed = source.clone();
+    let mut step = 0;
+
+    for offset in 1..=req.weeks {
+        let generated = match &req.deload {
+            Some(deload) if offset % deload.every == 0 => map_sets(&progressed, |set| deload_set(set, deload)),
+            _ => {
+                step += 1;
+                progressed = map_sets(&source, |set| progress_set(set, &req.rule, step));
+                progressed.clone()
+            }
+        };
+
+        let target = target_week_index(&mut weeks, source_week + offset)?;
+        weeks[target] = generated.iter().map(|w| duplicate(w)).collect();
+    }
+
+    let total_weeks = weeks.len() as i32;
+    let (exercises, revision) = if req.preview {
+        // Session is dropped without committing, so nothing is written
+        (renumber(weeks).0, None)
+    } else {
+        let detail = ChangeDetail::LayoutChanged {
+            operation: "progression".to_string(),
+        };
+        let revision =
+            bump_revision(collections, &mut session, program_oid, expected_revision, detail).await?;
+        let saved = save_layout(collections, &mut session, program_oid, program_id, weeks).await?;
+        (saved, Some(revision))
+    };
+
+    let generated_weeks = source_week + 1..=source_week + req.weeks;
+    let generated: Vec<WorkoutExercise> = exercises
+        .into_iter()
+        .filter(|e| generated_weeks.contains(&e.week))
+        .collect();
+    let known = known_exercise_ids(collections, &mut session, &generated).await?;
+    let errors = validate_exercises(total_weeks, &generated, &known);
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
+    }
+
+    if !req.preview {
+        commit_without_live_runs(started_programs, &mut session, program_id).await?;
+    }
+
+    Ok(ProgressionResponse {
+        preview: req.preview,
+        revision,
+        weeks: generated_weeks
+            .map(|week| {
+                let exercises = generated.iter().filter(|e| e.week == week).cloned();
+                week_response(program_id, total_weeks, week, exercises)
+            })
+            .collect(),
+    })
+}
+
+fn validate_request(req: &ProgressionRequest) -> Result<(), AppError> {
+    if req.weeks < 1 {
+        return Err(AppError::BadRequest("weeks must be at least 1".to_string()));
+    }
+
+    let (steps, max) = match &req.rule {
+        ProgressionRule::LinearIntensity { step, max } | ProgressionRule::VolumeRamp { step, max } => {
+            (vec![*step], *max)
+        }
+        ProgressionRule::PercentageIncrease { percent, max } => (vec![*percent], *max),
+        ProgressionRule::DoubleProgression { rep_step, intensity_step } => {
+            if *rep_step < MIN_REP_STEP {
+                return Err(AppError::BadRequest(format!(
+                    "rep_step must be at least {}",
+                    MIN_REP_STEP
+                )));
+            }
+            (vec![*rep_step, *intensity_step], None)
+        }
+    };
+    if !steps.iter().all(|s| s.is_finite()) || !max.is_none_or(f64::is_finite) {
+        return Err(AppError::BadRequest("Progression steps must be finite numbers".to_string()));
+    }
+    if steps.iter().any(|s| *s < 0.0) {
+        return Err(AppError::BadRequest(
+            "Progression steps must not be negative".to_string(),
+        ));
+    }
+
+    if let Some(deload) = &req.deload {
+        if deload.every < 2 {
+            return Err(AppError::BadRequest("deload.every must be at least 2".to_string()));
+        }
+        let factors = [deload.volume_factor, deload.intensity_factor];
+        if factors.iter().flatten().any(|f| !(*f > 0.0 && *f <= 1.0)) {
+            return Err(AppError::BadRequest(
+                "Deload factors must be between 0 and 1".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a function to every set of every workout in a week
+fn map_sets(week: &[Workout], f: impl Fn(&Set) -> Set) -> Vec<Workout> {
+    week.iter()
+        .map(|workout| {
+            workout
+                .iter()
+                .map(|exercise| WorkoutExercise {
+                    sets: exercise.sets.iter().map(&f).collect(),
+                    ..exercise.clone()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Prescription for a set `step` weeks after the source week
+fn progress_set(set: &Set, rule: &ProgressionRule, step: i32) -> Set {
+    let step_f = step as f64;
+    match rule {
+        ProgressionRule::LinearIntensity { step: delta, max } => Set {
+            intensity_lower: shift(set.intensity_lower, delta * step_f, *max),
+            intensity_upper: shift(set.intensity_upper, delta * step_f, *max),
+            ..set.clone()
+        },
+        ProgressionRule::PercentageIncrease { percent, max } => {
+            let factor = (1.0 + percent / 100.0).powf(step_f);
+            Set {
+                intensity_lower: scale(set.intensity_lower, factor, *max),
+                intensity_upper: scale(set.intensity_upper, factor, *max),
+                ..set.clone()
+            }
+        }
+        ProgressionRule::VolumeRamp { step: delta, max } => Set {
+            volume_lower: shift(set.volume_lower, delta * step_f, *max),
+            volume_upper: shift(set.volume_upper, delta * step_f, *max),
+            ..set.clone()
+        },
+        ProgressionRule::DoubleProgression { rep_step, intensity_step } => {
+            match (set.volume_lower, set.volume_upper) {
+                (Some(lower), Some(upper)) if upper > lower => {
+                    // Positions in the rep range before intensity goes up and reps reset
+                    let positions = ((upper - lower) / rep_step).floor() + 1.0;
+                    let cycle = (step_f / positions).floor();
+                    let position = step_f % positions;
+                    Set {
+                        volume_lower: Some(round(lower + position * rep_step)),
+                        intensity_lower: shift(set.intensity_lower, cycle * intensity_step, None),
+                        intensity_upper: shift(set.intensity_upper, cycle * intensity_step, None),
+                        ..set.clone()
+                    }
+                }
+                // No rep range to climb: progress intensity every week
+                _ => Set {
+                    intensity_lower: shift(set.intensity_lower, step_f * intensity_step, None),
+                    intensity_upper: shift(set.intensity_upper, step_f * intensity_step, None),
+                    ..set.clone()
+                },
+            }
+        }
+    }
+}
+
+fn deload_set(set: &Set, deload: &DeloadRule) -> Set {
+    let volume_factor = deload.volume_factor.unwrap_or(DEFAULT_DELOAD_VOLUME_FACTOR);
+    let intensity_factor = deload.intensity_factor.unwrap_or(DEFAULT_DELOAD_INTENSITY_FACTOR);
+    Set {
+        volume_lower: set.volume_lower.map(|v| round(v * volume_factor)),
+        volume_upper: set.volume_upper.map(|v| round(v * volume_factor)),
+        intensity_lower: set.intensity_lower.map(|v| round(v * intensity_factor)),
+        intensity_upper: set.intensity_upper.map(|v| round(v * intensity_factor)),
+        ..set.clone()
+    }
+}
+
+fn shift(value: Option<f64>, delta: f64, max: Option<f64>) -> Option<f64> {
+    value.map(|v| {
+        let shifted = v + delta;
+        round(max.map_or(shifted, |m| shifted.min(m)))
+    })
+}
+
+fn scale(value: Option<f64>, factor: f64, max: Option<f64>) -> Option<f64> {
+    value.map(|v| {
+        let scaled = v * factor;
+        round(max.map_or(scaled, |m| scaled.min(m)))
+    })
+}
+
+/// Round to two decimals so repeated steps don't accumulate float noise
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(volume: (f64, f64), intensity: (f64, f64)) -> Set {
+        Set {
+            number: 1,
+            volume_lower: Some(volume.0),
+            volume_upper: Some(volume.1),
+            intensity_lower: Some(intensity.0),
+            intensity_upper: Some(intensity.1),
+        }
+    }
+
+    fn volume(set: &Set) -> (Option<f64>, Option<f64>) {
+        (set.volume_lower, set.volume_upper)
+    }
+
+    fn intensity(set: &Set) -> (Option<f64>, Option<f64>) {
+        (set.intensity_lower, set.intensity_upper)
+    }
+
+    fn request(rule: ProgressionRule, deload: Option<DeloadRule>) -> ProgressionRequest {
+        ProgressionRequest {
+            weeks: 4,
+            rule,
+            deload,
+            preview: true,
+        }
+    }
+
+    #[test]
+    fn linear_intensity_adds_step_up_to_max() {
+        let rule = ProgressionRule::LinearIntensity { step: 0.5, max: Some(9.0) };
+        let source = set((5.0, 5.0), (7.5, 8.0));
+        assert_eq!(intensity(&progress_set(&source, &rule, 1)), (Some(8.0), Some(8.5)));
+        assert_eq!(intensity(&progress_set(&source, &rule, 3)), (Some(9.0), Some(9.0)));
+        assert_eq!(volume(&progress_set(&source, &rule, 3)), (Some(5.0), Some(5.0)));
+    }
+
+    #[test]
+    fn percentage_increase_compounds() {
+        let rule = ProgressionRule::PercentageIncrease { percent: 10.0, max: None };
+        let source = set((5.0, 5.0), (100.0, 100.0));
+        assert_eq!(intensity(&progress_set(&source, &rule, 1)), (Some(110.0), Some(110.0)));
+        assert_eq!(intensity(&progress_set(&source, &rule, 2)), (Some(121.0), Some(121.0)));
+
+        let capped = ProgressionRule::PercentageIncrease { percent: 10.0, max: Some(115.0) };
+        assert_eq!(intensity(&progress_set(&source, &capped, 2)), (Some(115.0), Some(115.0)));
+    }
+
+    #[test]
+    fn volume_ramp_adds_step_to_volume() {
+        let rule = ProgressionRule::VolumeRamp { step: 1.0, max: Some(10.0) };
+        let source = set((6.0, 8.0), (8.0, 8.0));
+        assert_eq!(volume(&progress_set(&source, &rule, 2)), (Some(8.0), Some(10.0)));
+        assert_eq!(volume(&progress_set(&source, &rule, 5)), (Some(10.0), Some(10.0)));
+        assert_eq!(intensity(&progress_set(&source, &rule, 5)), (Some(8.0), Some(8.0)));
+    }
+
+    #[test]
+    fn double_progression_climbs_rep_range_then_raises_intensity() {
+        let rule = ProgressionRule::DoubleProgression { rep_step: 1.0, intensity_step: 2.5 };
+        let source = set((8.0, 10.0), (60.0, 60.0));
+        let weeks: Vec<(Option<f64>, Option<f64>)> = (1..=4)
+            .map(|step| {
+                let set = progress_set(&source, &rule, step);
+                (set.volume_lower, set.intensity_lower)
+            })
+            .collect();
+        assert_eq!(
+            weeks,
+            [
+                (Some(9.0), Some(60.0)),
+                (Some(10.0), Some(60.0)),
+                (Some(8.0), Some(62.5)),
+                (Some(9.0), Some(62.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn double_progression_without_rep_range_raises_intensity_weekly() {
+        let rule = ProgressionRule::DoubleProgression { rep_step: 1.0, intensity_step: 2.5 };
+        let source = set((5.0, 5.0), (60.0, 60.0));
+        assert_eq!(intensity(&progress_set(&source, &rule, 2)), (Some(65.0), Some(65.0)));
+    }
+
+    #[test]
+    fn double_progression_handles_huge_rep_ranges() {
+        let rule = ProgressionRule::DoubleProgression { rep_step: 0.5, intensity_step: 1.0 };
+        let source = set((0.0, 1e300), (60.0, 60.0));
+        let progressed = progress_set(&source, &rule, 3);
+        assert_eq!(progressed.volume_lower, Some(1.5));
+        assert_eq!(progressed.intensity_lower, Some(60.0));
+    }
+
+    #[test]
+    fn deload_scales_previous_week_with_defaults() {
+        let deload = DeloadRule {
+            every: 4,
+            volume_factor: None,
+            intensity_factor: Some(0.8),
+        };
+        let deloaded = deload_set(&set((10.0, 10.0), (100.0, 100.0)), &deload);
+        assert_eq!(volume(&deloaded), (Some(6.0), Some(6.0)));
+        assert_eq!(intensity(&deloaded), (Some(80.0), Some(80.0)));
+    }
+
+    #[test]
+    fn rejects_unusable_rules() {
+        let invalid = [
+            ProgressionRule::LinearIntensity { step: -0.5, max: None },
+            ProgressionRule::VolumeRamp { step: f64::INFINITY, max: None },
+            ProgressionRule::PercentageIncrease { percent: -5.0, max: None },
+            ProgressionRule::PercentageIncrease { percent: 5.0, max: Some(f64::NAN) },
+            ProgressionRule::DoubleProgression { rep_step: 1e-300, intensity_step: 1.0 },
+            ProgressionRule::DoubleProgression { rep_step: f64::NAN, intensity_step: 1.0 },
+            ProgressionRule::DoubleProgression { rep_step: 1.0, intensity_step: -2.5 },
+        ];
+        for rule in invalid {
+            let result = validate_request(&request(rule, None));
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_deloads() {
+        let rule = || ProgressionRule::LinearIntensity { step: 0.5, max: None };
+        let too_frequent = DeloadRule {
+            every: 1,
+            volume_factor: None,
+            intensity_factor: None,
+        };
+        let growing = DeloadRule {
+            every: 4,
+            volume_factor: Some(1.5),
+            intensity_factor: None,
+        };
+        assert!(validate_request(&request(rule(), Some(too_frequent))).is_err());
+        assert!(validate_request(&request(rule(), Some(growing))).is_err());
+        assert!(validate_request(&request(rule(), None)).is_ok());
+    }
+}
@@ -0,0 +1,374 @@
+use std::time::Duration;
+
+use bson::doc;
+use futures::StreamExt;
+use lapin::{
+    options::{
+        BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicPublishOptions,
+        ConfirmSelectOptions, ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
+    },
+    types::{FieldTable, ShortString},
+    BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+use serde::Deserialize;
+
+use crate::config::REQUEUE_DELAY_SECS;
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::OutboxEvent;
+use crate::services::exercise_catalog;
+
+pub const USER_EVENTS_EXCHANGE: &str = "user_events";
+pub const USER_EVENTS_QUEUE: &str = "program_service_user_events";
+pub const EXERCISE_EVENTS_EXCHANGE: &str = "exercise_events";
+pub const CATALOG_RESYNC_QUEUE: &str = "program_service_catalog_resync_requests";
+/// Sent by consumers whose catalog read model is empty, e.g. on first start
+pub const CATALOG_RESYNC_ROUTING_KEY: &str = "catalog.resync_requested";
+
+/// RabbitMQ publisher for exercise catalog events
+pub struct RabbitMQPublisher {
+    // Kept alive for as long as the channel is in use
+    _connection: Connection,
+    channel: Channel,
+}
+
+impl RabbitMQPublisher {
+    pub async fn new(rabbitmq_url: &str) -> Result<Self, AppError> {
+        let connection = Connection::connect(rabbitmq_url, ConnectionProperties::default())
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to connect to RabbitMQ: {}", e))
+            })?;
+
+        let channel = connection.create_channel().await.map_err(|e| {
+            AppError::InternalServerError(format!("Failed to create RabbitMQ channel: {}", e))
+        })?;
+
+        // Wait for broker confirms so an event is only marked published once RabbitMQ has it
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to enable publisher confirms: {}", e))
+            })?;
+
+        channel
+            .exchange_declare(
+                EXERCISE_EVENTS_EXCHANGE,
+                ExchangeKind::Topic,
+                ExchangeDeclareOptions {
+                    durable: true,
+                    ..ExchangeDeclareOptions::default()
+                },
+                FieldTable::default(),
+            )
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to declare exchange: {}", e))
+            })?;
+
+        println!(
+            "RabbitMQ connected, exchange '{}' declared",
+            EXERCISE_EVENTS_EXCHANGE
+        );
+
+        Ok(Self {
+            _connection: connection,
+            channel,
+        })
+    }
+
+    pub async fn publish_event(&self, event: &OutboxEvent) -> Result<(), AppError> {
+        let confirmation = self
+            .channel
+            .basic_publish(
+                EXERCISE_EVENTS_EXCHANGE,
+                &event.routing_key,
+                BasicPublishOptions::default(),
+                event.payload.as_bytes(),
+                BasicProperties::default()
+                    .with_content_type("application/json".into())
+                    .with_delivery_mode(2) // Persistent
+                    .with_message_id(ShortString::from(event.id.to_hex()))
+                    .with_type(ShortString::from(event.event_type.clone()))
+                    .with_timestamp(event.created_at.timestamp() as u64),
+            )
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to publish message: {}", e))
+            })?
+            .await
+            .map_err(|e| {
+                AppError::InternalServerError(format!("Failed to confirm message: {}", e))
+            })?;
+
+        if !confirmation.is_ack() {
+            return Err(AppError::InternalServerError(
+                "RabbitMQ did not acknowledge the message".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// User lifecycle events published by auth-service that program-service reacts to
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum UserEvent {
+    UserDisabled { username: String },
+    UserDeleted { username: String },
+}
+
+/// Hide the programs of a disabled or deleted user from search, new starts and purchases.
+/// Athletes who already started them keep following their workouts.
+async fn handle_user_event(collections: &Collections, event: UserEvent) -> Result<(), AppError> {
+    let username = match event {
+        UserEvent::UserDisabled { username } | UserEvent::UserDeleted { username } => username,
+    };
+
+    let result = collections
+        .programs
+        .update_many(
+            doc! { "user_id": &username, "owner_disabled": { "$ne": true } },
+            doc! { "$set": { "owner_disabled": true } },
+        )
+        .await?;
+
+    println!(
+        "Hid {} program(s) of user {}",
+        result.modified_count, username
+    );
+
+    Ok(())
+}
+
+/// Start the RabbitMQ consumer for user lifecycle events
+pub async fn start_user_events_consumer(
+    rabbitmq_url: &str,
+    collections: Collections,
+) -> Result<(), AppError> {
+    let connection = Connection::connect(rabbitmq_url, ConnectionProperties::default())
+        .await
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to connect to RabbitMQ: {}", e))
+        })?;
+
+    let channel = connection.create_channel().await.map_err(|e| {
+        AppError::InternalServerError(format!("Failed to create RabbitMQ channel: {}", e))
+    })?;
+
+    channel
+        .exchange_declare(
+            USER_EVENTS_EXCHANGE,
+            ExchangeKind::Topic,
+            ExchangeDeclareOptions {
+                durable: true,
+                ..ExchangeDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to declare exchange: {}", e)))?;
+
+    channel
+        .queue_declare(
+            USER_EVENTS_QUEUE,
+            QueueDeclareOptions {
+                durable: true,
+                ..QueueDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to declare queue: {}", e)))?;
+
+    for routing_key in ["user.disabled", "user.deleted"] {
+        channel
+            .queue_bind(
+                USER_EVENTS_QUEUE,
+                USER_EVENTS_EXCHANGE,
+                routing_key,
+                QueueBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Failed to bind queue: {}", e)))?;
+    }
+
+    println!(
+        "RabbitMQ consumer connected, listening on queue '{}'",
+        USER_EVENTS_QUEUE
+    );
+
+    let mut consumer = channel
+        .basic_consume(
+            USER_EVENTS_QUEUE,
+            "program_service_user_events_consumer",
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create consumer: {}", e)))?;
+
+    tokio::spawn(async move {
+        // Keep the connection alive for as long as the consumer runs
+        let _connection = connection;
+
+        while let Some(delivery_result) = consumer.next().await {
+            match delivery_result {
+                Ok(delivery) => {
+                    let handled = match serde_json::from_slice::<UserEvent>(&delivery.data) {
+                        Ok(event) => handle_user_event(&collections, event).await,
+                        Err(e) => {
+                            // Unreadable messages would fail again; drop them
+                            eprintln!("Failed to parse user event: {}", e);
+                            Ok(())
+                        }
+                    };
+
+                    if let Err(e) = handled {
+                        eprintln!("Failed to handle user event, requeueing: {:?}", e);
+                        // Back off so a database outage doesn't turn into a redelivery loop
+                        tokio::time::sleep(Duration::from_secs(REQUEUE_DELAY_SECS)).await;
+                        if let Err(e) = delivery
+                            .nack(BasicNackOptions {
+                                requeue: true,
+                                ..BasicNackOptions::default()
+                            })
+                            .await
+                        {
+                            eprintln!("Failed to nack message: {}", e);
+                        }
+                    } else if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                        eprintln!("Failed to ack message: {}", e);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error receiving message: {}", e);
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Request from another service to publish the whole exercise catalog
+#[derive(Debug, Deserialize)]
+struct CatalogResyncRequest {
+    requested_by: String,
+}
+
+/// Start the RabbitMQ consumer for catalog resync requests, answered with a CatalogSynced event
+pub async fn start_catalog_resync_consumer(
+    rabbitmq_url: &str,
+    collections: Collections,
+) -> Result<(), AppError> {
+    let connection = Connection::connect(rabbitmq_url, ConnectionProperties::default())
+        .await
+        .map_err(|e| {
+            AppError::InternalServerError(format!("Failed to connect to RabbitMQ: {}", e))
+        })?;
+
+    let channel = connection.create_channel().await.map_err(|e| {
+        AppError::InternalServerError(format!("Failed to create RabbitMQ channel: {}", e))
+    })?;
+
+    channel
+        .exchange_declare(
+            EXERCISE_EVENTS_EXCHANGE,
+            ExchangeKind::Topic,
+            ExchangeDeclareOptions {
+                durable: true,
+                ..ExchangeDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to declare exchange: {}", e)))?;
+
+    channel
+        .queue_declare(
+            CATALOG_RESYNC_QUEUE,
+            QueueDeclareOptions {
+                durable: true,
+                ..QueueDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to declare queue: {}", e)))?;
+
+    channel
+        .queue_bind(
+            CATALOG_RESYNC_QUEUE,
+            EXERCISE_EVENTS_EXCHANGE,
+            CATALOG_RESYNC_ROUTING_KEY,
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to bind queue: {}", e)))?;
+
+    println!(
+        "RabbitMQ consumer connected, listening on queue '{}'",
+        CATALOG_RESYNC_QUEUE
+    );
+
+    let mut consumer = channel
+        .basic_consume(
+            CATALOG_RESYNC_QUEUE,
+            "program_service_catalog_resync_consumer",
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create consumer: {}", e)))?;
+
+    tokio::spawn(async move {
+        // Keep the connection alive for as long as the consumer runs
+        let _connection = connection;
+
+        while let Some(delivery_result) = consumer.next().await {
+            match delivery_result {
+                Ok(delivery) => {
+                    let requested_by =
+                        serde_json::from_slice::<CatalogResyncRequest>(&delivery.data)
+                            .map(|r| r.requested_by)
+                            .unwrap_or_else(|_| "unknown".to_string());
+
+                    match exercise_catalog::resync_catalog(&collections).await {
+                        Ok(result) => {
+                            println!(
+                                "Published exercise catalog for {} ({} exercises)",
+                                requested_by, result.exercises
+                            );
+                            if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
+                                eprintln!("Failed to ack message: {}", e);
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to resync exercise catalog, requeueing: {:?}", e);
+                            tokio::time::sleep(Duration::from_secs(REQUEUE_DELAY_SECS)).await;
+                            if let Err(e) = delivery
+                                .nack(BasicNackOptions {
+                                    requeue: true,
+                                    ..BasicNackOptions::default()
+                                })
+                                .await
+                            {
+                                eprintln!("Failed to nack message: {}", e);
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error receiving message: {}", e);
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use bson::{doc, oid::ObjectId, Bson};
+use chrono::Utc;
+use futures::TryStreamExt;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{
+    ModerateReviewRequest, PaginatedReviewResponse, Review, ReviewQueryParams, ReviewReplyRequest,
+    ReviewResponse, UpsertReviewRequest,
+};
+use crate::services::started_program_client::StartedProgramClient;
+
+const MAX_REVIEW_LENGTH: usize = 2000;
+
+/// Trim review or reply text and enforce the length limit
+fn clean_text(text: &str) -> Result<String, AppError> {
+    let text = text.trim().to_string();
+    if text.chars().count() > MAX_REVIEW_LENGTH {
+        return Err(AppError::BadRequest(format!(
+            "Text must be at most {} characters",
+            MAX_REVIEW_LENGTH
+        )));
+    }
+    Ok(text)
+}
+
+/// Recompute the average and count of visible reviews on the program
+async fn refresh_program_rating(
+    collections: &Collections,
+    program_id: &str,
+) -> Result<(), AppError> {
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let pipeline = vec![
+        doc! { "$match": { "program_id": program_id, "hidden": { "$ne": true } } },
+        doc! {
+            "$group": {
+                "_id": null,
+                "average": { "$avg": "$rating" },
+                "count": { "$sum": 1 }
+            }
+        },
+    ];
+
+    let mut cursor = collections.reviews.aggregate(pipeline).await?;
+    let (average, count) = match cursor.try_next().await? {
+        Some(result) => {
+            let average = result.get_f64("average").ok();
+            let count = match result.get("count") {
+                Some(Bson::Int32(n)) => *n as i64,
+                Some(Bson::Int64(n)) => *n,
+                _ => 0,
+            };
+            (average, count)
+        }
+        None => (None, 0),
+    };
+
+    collections
+        .programs
+        .update_one(
+            doc! { "_id": oid },
+            doc! { "$set": { "rating_average": average, "rating_count": count } },
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Create or edit the caller's review; only users who started the program can review it
+pub async fn upsert_review(
+    collections: &Collections,
+    started_programs: &StartedProgramClient<'_>,
+    user_id: &str,
+    program_id: &str,
+    req: UpsertReviewRequest,
+) -> Result<ReviewResponse, AppError> {
+    if !(1..=5).contains(&req.rating) {
+        return Err(AppError::BadRequest(
+            "Rating must be between 1 and 5".to_string(),
+        ));
+    }
+    let text = match req.text.as_deref() {
+        Some(text) => Some(clean_text(text)?).filter(|t| !t.is_empty()),
+        None => None,
+    };
+
+    let oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let program = collections
+        .programs
+        .find_one(doc! { "_id": oid, "deleted_at": null })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    if program.user_id == user_id {
+        return Err(AppError::Forbidden(
+            "Coaches cannot review their own program".to_string(),
+        ));
+    }
+
+    let filter = doc! { "program_id": program_id, "user_id": user_id };
+    let existing = collections.reviews.find_one(filter.clone()).await?;
+
+    // Editing an existing review does not need the started-program check again
+    if existing.is_none() && !started_programs.has_started(user_id, program_id).await? {
+        return Err(AppError::Forbidden(
+            "Only users who started this program can review it".to_string(),
+        ));
+    }
+
+    let now = Utc::now();
+    collections
+        .reviews
+        .update_one(
+            filter.clone(),
+            doc! {
+                "$set": {
+                    "rating": req.rating,
+                    "text": text,
+                    "updated_at": now.to_rfc3339()
+                },
+                "$setOnInsert": {
+                    "_id": ObjectId::new(),
+                    "reply": null,
+                    "hidden": false,
+                    "created_at": now.to_rfc3339()
+                }
+            },
+        )
+        .upsert(true)
+        .await?;
+
+    refresh_program_rating(collections, program_id).await?;
+
+    let review = collections
+        .reviews
+        .find_one(filter)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Review not found".to_string()))?;
+
+    Ok(review.into())
+}
+
+/// Visible reviews of a program, most recently updated first
+/// Uses MongoDB $facet aggregation for efficient pagination
+pub async fn get_reviews(
+    collections: &Collections,
+    program_id: &str,
+    params: ReviewQueryParams,
+) -> Result<PaginatedReviewResponse, AppError> {
+    let page = params.page.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(20).min(100);
+    let skip = ((page - 1) * limit) as i64;
+
+    let pipeline = vec![
+        doc! { "$match": { "program_id": program_id, "hidden": { "$ne": true } } },
+        doc! { "$sort": { "updated_at": -1 } },
+        doc! {
+            "$facet": {
+                "metadata": [{ "$count": "totalCount" }],
+                "data": [{ "$skip": skip }, { "$limit": limit as i64 }]
+            }
+        },
+    ];
+
+    let mut cursor = collections.reviews.aggregate(pipeline).await?;
+    let result = cursor.try_next().await?;
+
+    let total = result
+        .as_ref()
+        .and_then(|r| r.get_array("metadata").ok())
+        .and_then(|m| m.first())
+        .and_then(|d| d.as_document())
+        .and_then(|d| d.get_i32("totalCount").ok())
+        .unwrap_or(0) as u64;
+
+    let reviews: Vec<ReviewResponse> = result
+        .as_ref()
+        .and_then(|r| r.get_array("data").ok())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|b| b.as_document())
+                .filter_map(|d| bson::from_document::<Review>(d.clone()).ok())
+                .map(|r| r.into())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let total_pages = if total == 0 {
+        0
+    } else {
+        (total as f64 / limit as f64).ceil() as u64
+    };
+
+    Ok(PaginatedReviewResponse {
+        reviews,
+        total,
+        page,
+        limit,
+        total_pages,
+    })
+}
+
+/// Remove the caller's own review
+pub async fn delete_own_review(
+    collections: &Collections,
+    user_id: &str,
+    program_id: &str,
+) -> Result<(), AppError> {
+    let result = collections
+        .reviews
+        .delete_one(doc! { "program_id": program_id, "user_id": user_id })
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppError::NotFound("Review not found".to_string()));
+    }
+
+    refresh_program_rating(collections, program_id).await
+}
+
+/// Set or replace the coach's reply on a review of one of their programs
+pub async fn reply_to_review(
+    collections: &Collections,
+    user_id: &str,
+    program_id: &str,
+    review_id: &str,
+    req: ReviewReplyRequest,
+) -> Result<ReviewResponse, AppError> {
+    let program_oid = ObjectId::parse_str(program_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+    let review_oid = ObjectId::parse_str(review_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let text = clean_text(&req.text)?;
+    if text.is_empty() {
+        return Err(AppError::BadRequest("Reply text is required".to_string()));
+    }
+
+    collections
+        .programs
+        .find_one(doc! { "_id": program_oid, "user_id": user_id, "deleted_at": null })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    let filter = doc! { "_id": review_oid, "program_id": program_id };
+    let result = collections
+        .reviews
+        .update_one(
+            filter.clone(),
+            doc! {
+                "$set": {
+                    "reply": {
+                        "user_id": user_id,
+                        "text": text,
+                        "updated_at": Utc::now().to_rfc3339()
+                    }
+                }
+            },
+        )
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppError::NotFound("Review not found".to_string()));
+    }
+
+    let review = collections
+        .reviews
+        .find_one(filter)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Review not found".to_string()))?;
+
+    Ok(review.into())
+}
+
+/// Hide or unhide a review (admin moderation)
+pub async fn moderate_review(
+    collections: &Collections,
+    review_id: &str,
+    req: ModerateReviewRequest,
+) -> Result<ReviewResponse, AppError> {
+    let oid = ObjectId::parse_str(review_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let result = collections
+        .reviews
+        .update_one(doc! { "_id": oid }, doc! { "$set": { "hidden": req.hidden } })
+        .await?;
+
+    if result.matched_count == 0 {
+        return Err(AppError::NotFound("Review not found".to_string()));
+    }
+
+    let review = collections
+        .reviews
+        .find_one(doc! { "_id": oid })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Review not found".to_string()))?;
+
+    refresh_program_rating(collections, &review.program_id).await?;
+
+    Ok(review.into())
+}
+
+/// Remove any review (admin moderation)
+pub async fn delete_review(collections: &Collections, review_id: &str) -> Result<(), AppError> {
+    let oid = ObjectId::parse_str(review_id)
+        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
+
+    let review = collections
+        .reviews
+        .find_one_and_delete(doc! { "_id": oid })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Review not found".to_string()))?;
+
+    refresh_program_rating(collections, &review.program_id).await
+}
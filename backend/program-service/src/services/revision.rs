@@ -0,0 +1,125 @@
+use bson::{doc, oid::ObjectId, Bson};
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{options::ReturnDocument, ClientSession};
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{ChangeDetail, ProgramChange, ProgramChangeResponse};
+
+/// Increment a program's revision inside the caller's transaction and log what changed
+/// Fails with a revision conflict when `expected` no longer matches the stored revision
+pub async fn bump_revision(
+    collections: &Collections,
+    session: &mut ClientSession,
+    program_oid: ObjectId,
+    expected: Option<i64>,
+    detail: ChangeDetail,
+) -> Result<i64, AppError> {
+    let mut filter = doc! { "_id": program_oid };
+    match expected {
+        // Programs saved before revisions existed have no revision field
+        Some(0) => {
+            filter.insert("revision", doc! { "$in": [0_i64, Bson::Null] });
+        }
+        Some(revision) => {
+            filter.insert("revision", revision);
+        }
+        None => {}
+    }
+
+    let updated = collections
+        .programs
+        .find_one_and_update(filter, doc! { "$inc": { "revision": 1_i64 } })
+        .return_document(ReturnDocument::After)
+        .session(&mut *session)
+        .await?;
+
+    let Some(program) = updated else {
+        let current_revision = collections
+            .programs
+            .find_one(doc! { "_id": program_oid })
+            .session(&mut *session)
+            .await?
+            .map(|p| p.revision)
+            .unwrap_or(0);
+        return Err(
+            revision_conflict(collections, session, program_oid, current_revision, expected).await?,
+        );
+    };
+
+    let change = ProgramChange {
+        id: ObjectId::new(),
+        program_id: program_oid.to_hex(),
+        revision: program.revision,
+        changed_at: Utc::now(),
+        detail,
+    };
+    collections
+        .program_changes
+        .insert_one(&change)
+        .session(&mut *session)
+        .await?;
+
+    Ok(program.revision)
+}
+
+/// Fail with a revision conflict when `expected` doesn't match the revision read in the
+/// caller's transaction, for requests that end up changing nothing
+pub async fn check_revision(
+    collections: &Collections,
+    session: &mut ClientSession,
+    program_oid: ObjectId,
+    current_revision: i64,
+    expected: Option<i64>,
+) -> Result<(), AppError> {
+    match expected {
+        Some(revision) if revision != current_revision => Err(revision_conflict(
+            collections,
+            session,
+            program_oid,
+            current_revision,
+            expected,
+        )
+        .await?),
+        _ => Ok(()),
+    }
+}
+
+async fn revision_conflict(
+    collections: &Collections,
+    session: &mut ClientSession,
+    program_oid: ObjectId,
+    current_revision: i64,
+    expected: Option<i64>,
+) -> Result<AppError, AppError> {
+    let changes = changes_since(
+        collections,
+        session,
+        &program_oid.to_hex(),
+        expected.unwrap_or(0),
+    )
+    .await?;
+    Ok(AppError::RevisionConflict {
+        current_revision,
+        changes,
+    })
+}
+
+/// Changes recorded after a given revision, oldest first
+async fn changes_since(
+    collections: &Collections,
+    session: &mut ClientSession,
+    program_id: &str,
+    revision: i64,
+) -> Result<Vec<ProgramChangeResponse>, AppError> {
+    let mut cursor = collections
+        .program_changes
+        .find(doc! { "program_id": program_id, "revision": { "$gt": revision } })
+        .sort(doc! { "revision": 1 })
+        .session(&mut *session)
+        .await?;
+
+    let changes: Vec<ProgramChange> = cursor.stream(session).try_collect().await?;
+    Ok(changes.into_iter().map(|c| c.into()).collect())
+}
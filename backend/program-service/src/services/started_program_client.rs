@@ -0,0 +1,117 @@
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::error::AppError;
+
+/// Started program from started-program-service (only the fields used here)
+#[derive(Debug, Deserialize)]
+struct StartedProgramSummary {
+    program_id: String,
+    #[serde(default)]
+    program_version: Option<i32>,
+}
+
+/// How many athlete workout logs reference an exercise
+#[derive(Debug, Deserialize)]
+struct ExerciseUsage {
+    started_workout_exercises: u64,
+}
+
+/// How many running athletes follow a program's live workouts
+#[derive(Debug, Deserialize)]
+struct ProgramUsage {
+    unpinned_started_programs: u64,
+}
+
+/// Client for calling started-program-service
+pub struct StartedProgramClient<'a> {
+    client: &'a Client,
+    base_url: &'a str,
+}
+
+impl<'a> StartedProgramClient<'a> {
+    pub fn new(client: &'a Client, base_url: &'a str) -> Self {
+        Self { client, base_url }
+    }
+
+    /// Whether the user has a started program (running or finished) for this program
+    pub async fn has_started(&self, user_id: &str, program_id: &str) -> Result<bool, AppError> {
+        let started = self.started_programs(user_id).await?;
+        Ok(started.iter().any(|s| s.program_id == program_id))
+    }
+
+    /// Version the user's started program (running or finished) for this program is pinned to
+    pub async fn pinned_version(
+        &self,
+        user_id: &str,
+        program_id: &str,
+    ) -> Result<Option<i32>, AppError> {
+        let started = self.started_programs(user_id).await?;
+        Ok(started
+            .iter()
+            .find(|s| s.program_id == program_id)
+            .and_then(|s| s.program_version))
+    }
+
+    async fn started_programs(&self, user_id: &str) -> Result<Vec<StartedProgramSummary>, AppError> {
+        let url = format!("{}/started-programs", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("X-User-Id", user_id)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::InternalServerError(format!(
+                "Started program service error: {}",
+                response.status()
+            )));
+        }
+
+        response.json().await.map_err(|e| {
+            AppError::InternalServerError(format!("Failed to parse started programs: {}", e))
+        })
+    }
+
+    /// Number of logged athlete exercises that reference a catalog exercise
+    pub async fn exercise_usage(&self, exercise_id: &str) -> Result<u64, AppError> {
+        let url = format!("{}/exercises/{}/usage", self.base_url, exercise_id);
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::InternalServerError(format!(
+                "Started program service error: {}",
+                response.status()
+            )));
+        }
+
+        let usage: ExerciseUsage = response.json().await.map_err(|e| {
+            AppError::InternalServerError(format!("Failed to parse exercise usage: {}", e))
+        })?;
+
+        Ok(usage.started_workout_exercises)
+    }
+
+    /// Number of running started programs that follow the live workouts of a program
+    pub async fn program_usage(&self, program_id: &str) -> Result<u64, AppError> {
+        let url = format!("{}/programs/{}/usage", self.base_url, program_id);
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(AppError::InternalServerError(format!(
+                "Started program service error: {}",
+                response.status()
+            )));
+        }
+
+        let usage: ProgramUsage = response.json().await.map_err(|e| {
+            AppError::InternalServerError(format!("Failed to parse program usage: {}", e))
+        })?;
+
+        Ok(usage.unpinned_started_programs)
+    }
+}
@@ -0,0 +1,229 @@
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+use futures::future::BoxFuture;
+
+use crate::error::AppError;
+
+/// Object read back from storage
+pub struct StoredObject {
+    pub data: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Object storage for uploaded files, addressed by key (e.g. "programs/<id>/<uuid>.jpg")
+pub trait ObjectStorage: Send + Sync {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        data: Vec<u8>,
+        content_type: &'a str,
+    ) -> BoxFuture<'a, Result<(), AppError>>;
+
+    /// None if the key does not exist
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<StoredObject>, AppError>>;
+
+    /// Deleting a missing key is not an error
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), AppError>>;
+}
+
+/// Base URL that object keys are served from, set once at startup
+static PUBLIC_BASE_URL: OnceLock<String> = OnceLock::new();
+
+pub fn init_public_base_url(url: String) {
+    PUBLIC_BASE_URL
+        .set(url.trim_end_matches('/').to_string())
+        .ok();
+}
+
+/// Public URL of a stored object
+pub fn public_url(key: &str) -> String {
+    let base = PUBLIC_BASE_URL.get().map(String::as_str).unwrap_or("/files");
+    format!("{}/{}", base, key)
+}
+
+/// Object key from an image URL stored before programs kept keys
+/// (https://<bucket>.s3.<region>.amazonaws.com/<key>)
+pub fn key_from_legacy_url(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("https://")?;
+    let (host, key) = rest.split_once('/')?;
+    if host.ends_with(".amazonaws.com") && !key.is_empty() {
+        Some(key.to_string())
+    } else {
+        None
+    }
+}
+
+/// Content type from the file extension, for backends that don't store one
+fn content_type_for(key: &str) -> &'static str {
+    match key.rsplit('.').next() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+/// AWS S3 or an S3-compatible service such as MinIO
+pub struct S3Storage {
+    client: Client,
+    bucket: String,
+}
+
+impl S3Storage {
+    pub fn new(client: Client, bucket: String) -> Self {
+        Self { client, bucket }
+    }
+}
+
+impl ObjectStorage for S3Storage {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        data: Vec<u8>,
+        content_type: &'a str,
+    ) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .body(ByteStream::from(data))
+                .content_type(content_type)
+                .send()
+                .await
+                .map_err(|e| AppError::InternalServerError(format!("S3 upload failed: {}", e)))?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<StoredObject>, AppError>> {
+        Box::pin(async move {
+            let output = match self
+                .client
+                .get_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .send()
+                .await
+            {
+                Ok(output) => output,
+                Err(e) => {
+                    let e = e.into_service_error();
+                    if e.is_no_such_key() {
+                        return Ok(None);
+                    }
+                    return Err(AppError::InternalServerError(format!(
+                        "S3 download failed: {}",
+                        e
+                    )));
+                }
+            };
+
+            let content_type = output
+                .content_type()
+                .unwrap_or_else(|| content_type_for(key))
+                .to_string();
+            let data = output
+                .body
+                .collect()
+                .await
+                .map_err(|e| AppError::InternalServerError(format!("S3 download failed: {}", e)))?
+                .into_bytes()
+                .to_vec();
+
+            Ok(Some(StoredObject { data, content_type }))
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            self.client
+                .delete_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .send()
+                .await
+                .map_err(|e| AppError::InternalServerError(format!("S3 delete failed: {}", e)))?;
+            Ok(())
+        })
+    }
+}
+
+/// Files under a local directory, for development without S3
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Resolve a key inside the root, rejecting absolute paths and ".."
+    fn path_for(&self, key: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(key);
+        let safe = relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if key.is_empty() || !safe {
+            return Err(AppError::BadRequest("Invalid object key".to_string()));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+impl ObjectStorage for LocalStorage {
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        data: Vec<u8>,
+        _content_type: &'a str,
+    ) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                    AppError::InternalServerError(format!("Storage write failed: {}", e))
+                })?;
+            }
+            tokio::fs::write(&path, data)
+                .await
+                .map_err(|e| AppError::InternalServerError(format!("Storage write failed: {}", e)))
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<StoredObject>, AppError>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            match tokio::fs::read(&path).await {
+                Ok(data) => Ok(Some(StoredObject {
+                    data,
+                    content_type: content_type_for(key).to_string(),
+                })),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(AppError::InternalServerError(format!(
+                    "Storage read failed: {}",
+                    e
+                ))),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            let path = self.path_for(key)?;
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(AppError::InternalServerError(format!(
+                    "Storage delete failed: {}",
+                    e
+                ))),
+            }
+        })
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use bson::{doc, oid::ObjectId};
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::ClientSession;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{
+    AlternativeResponse, AlternativesQueryParams, CreateSubstitutionRequest, Equipment, Exercise,
+    MuscleIntensity, Substitution, SubstitutionResponse,
+};
+use crate::services::exercise::parse_filter;
+
+const MUSCLE_WEIGHT: f64 = 0.7;
+const PATTERN_WEIGHT: f64 = 0.2;
+const EQUIPMENT_WEIGHT: f64 = 0.1;
+/// Computed candidates below this score are not suggested
+const MIN_SCORE: f64 = 0.3;
+
+/// Weighted Jaccard overlap of two muscle lists: sum of min intensities over sum of max
+fn muscle_overlap(a: &[MuscleIntensity], b: &[MuscleIntensity]) -> f64 {
+    let mut intensities: HashMap<&str, (f64, f64)> = HashMap::new();
+    for m in a {
+        intensities.entry(m.muscle.as_str()).or_default().0 += m.intensity;
+    }
+    for m in b {
+        intensities.entry(m.muscle.as_str()).or_default().1 += m.intensity;
+    }
+
+    let (shared, total) = intensities
+        .values()
+        .fold((0.0, 0.0), |(shared, total), (x, y)| {
+            (shared + x.min(*y), total + x.max(*y))
+        });
+    if total > 0.0 {
+        shared / total
+    } else {
+        0.0
+    }
+}
+
+/// How well `candidate` can stand in for `exercise` (0.0-1.0)
+fn similarity(exercise: &Exercise, candidate: &Exercise) -> f64 {
+    let mut score = MUSCLE_WEIGHT * muscle_overlap(&exercise.muscles, &candidate.muscles);
+    let pattern = exercise.movement_pattern;
+    if pattern.is_some() && pattern == candidate.movement_pattern {
+        score += PATTERN_WEIGHT;
+    }
+    if exercise.equipment.is_some() && exercise.equipment == candidate.equipment {
+        score += EQUIPMENT_WEIGHT;
+    }
+    score
+}
+
+/// Filter matching substitutions that involve the exercise on either side
+fn involving(exercise_id: &str) -> bson::Document {
+    doc! { "$or": [{ "exercise_id": exercise_id }, { "substitute_id": exercise_id }] }
+}
+
+async fn find_exercise(collections: &Collections, exercise_id: &str) -> Result<Exercise, AppError> {
+    collections
+        .exercises
+        .find_one(doc! { "_id": exercise_id })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Exercise not found".to_string()))
+}
+
+/// Curate a substitution between two exercises (admin)
+pub async fn create_substitution(
+    collections: &Collections,
+    exercise_id: &str,
+    req: CreateSubstitutionRequest,
+) -> Result<SubstitutionResponse, AppError> {
+    if req.substitute_id == exercise_id {
+        return Err(AppError::BadRequest(
+            "An exercise cannot substitute itself".to_string(),
+        ));
+    }
+    find_exercise(collections, exercise_id).await?;
+    find_exercise(collections, &req.substitute_id).await?;
+
+    let existing = collections
+        .substitutions
+        .find_one(doc! {
+            "$or": [
+                { "exercise_id": exercise_id, "substitute_id": &req.substitute_id },
+                { "exercise_id": &req.substitute_id, "substitute_id": exercise_id }
+            ]
+        })
+        .await?;
+    if existing.is_some() {
+        return Err(AppError::Conflict(
+            "These exercises are already substitutions".to_string(),
+        ));
+    }
+
+    let substitution = Substitution {
+        id: ObjectId::new(),
+        exercise_id: exercise_id.to_string(),
+        substitute_id: req.substitute_id,
+        note: req
+            .note
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty()),
+        created_at: Utc::now(),
+    };
+    collections.substitutions.insert_one(&substitution).await?;
+
+    Ok(substitution.into())
+}
+
+/// Curated substitutions involving an exercise
+pub async fn get_substitutions(
+    collections: &Collections,
+    exercise_id: &str,
+) -> Result<Vec<SubstitutionResponse>, AppError> {
+    let cursor = collections
+        .substitutions
+        .find(involving(exercise_id))
+        .sort(doc! { "created_at": 1 })
+        .await?;
+
+    let substitutions: Vec<Substitution> = cursor.try_collect().await?;
+    Ok(substitutions.into_iter().map(|s| s.into()).collect())
+}
+
+/// Remove a curated substitution, whichever direction it was created in (admin)
+pub async fn delete_substitution(
+    collections: &Collections,
+    exercise_id: &str,
+    substitute_id: &str,
+) -> Result<(), AppError> {
+    let result = collections
+        .substitutions
+        .delete_one(doc! {
+            "$or": [
+                { "exercise_id": exercise_id, "substitute_id": substitute_id },
+                { "exercise_id": substitute_id, "substitute_id": exercise_id }
+            ]
+        })
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppError::NotFound("Substitution not found".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Drop every substitution that involves a removed exercise
+pub async fn delete_exercise_substitutions(
+    collections: &Collections,
+    session: &mut ClientSession,
+    exercise_id: &str,
+) -> Result<(), AppError> {
+    collections
+        .substitutions
+        .delete_many(involving(exercise_id))
+        .session(session)
+        .await?;
+    Ok(())
+}
+
+/// Ranked replacements for an exercise: curated substitutions first, then exercises
+/// with similar muscles, movement pattern and equipment
+pub async fn get_alternatives(
+    collections: &Collections,
+    exercise_id: &str,
+    params: AlternativesQueryParams,
+) -> Result<Vec<AlternativeResponse>, AppError> {
+    let limit = params.limit.unwrap_or(10).clamp(1, 50) as usize;
+    let excluded = parse_filter(
+        params.exclude_equipment.as_deref(),
+        &Equipment::ALL,
+        Equipment::as_str,
+        "equipment",
+    )?;
+
+    let exercise = find_exercise(collections, exercise_id).await?;
+
+    // Curated partner ID -> note
+    let curated: HashMap<String, Option<String>> = collections
+        .substitutions
+        .find(involving(exercise_id))
+        .await?
+        .try_collect::<Vec<Substitution>>()
+        .await?
+        .into_iter()
+        .map(|s| {
+            let other = if s.exercise_id == exercise_id {
+                s.substitute_id
+            } else {
+                s.exercise_id
+            };
+            (other, s.note)
+        })
+        .collect();
+
+    let muscles: Vec<&str> = exercise.muscles.iter().map(|m| m.muscle.as_str()).collect();
+    let curated_ids: Vec<&str> = curated.keys().map(String::as_str).collect();
+    let filter = doc! {
+        "_id": { "$ne": exercise_id },
+        "deprecated_at": null,
+        "equipment": { "$nin": excluded },
+        "$or": [
+            { "_id": { "$in": curated_ids } },
+            { "muscles.muscle": { "$in": muscles } }
+        ]
+    };
+    let candidates: Vec<Exercise> = collections
+        .exercises
+        .find(filter)
+        .await?
+        .try_collect()
+        .await?;
+
+    let mut alternatives: Vec<AlternativeResponse> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let score = similarity(&exercise, &candidate);
+            let (is_curated, note) = match curated.get(&candidate.id) {
+                Some(note) => (true, note.clone()),
+                None if score >= MIN_SCORE => (false, None),
+                None => return None,
+            };
+            Some(AlternativeResponse {
+                exercise: candidate.into(),
+                score: (score * 100.0).round() / 100.0,
+                curated: is_curated,
+                note,
+            })
+        })
+        .collect();
+
+    alternatives.sort_by(|a, b| {
+        b.curated
+            .cmp(&a.curated)
+            .then(b.score.total_cmp(&a.score))
+            .then_with(|| a.exercise.name.cmp(&b.exercise.name))
+    });
+    alternatives.truncate(limit);
+
+    Ok(alternatives)
+}
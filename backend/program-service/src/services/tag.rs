@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use bson::{doc, Document};
+use futures::TryStreamExt;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{CreateTagRequest, Tag, TagResponse, UpdateTagRequest};
+
+/// Lowercase and join words with "-", so "Power  Lifting" and "power_lifting" both become "power-lifting"
+pub fn normalize_tag(raw: &str) -> String {
+    raw.split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Normalize program tags and resolve aliases to their canonical slug
+/// Tags outside the vocabulary are kept in normalized form
+pub async fn canonicalize_tags(
+    collections: &Collections,
+    tags: &[String],
+) -> Result<Vec<String>, AppError> {
+    let normalized: Vec<String> = tags
+        .iter()
+        .map(|t| normalize_tag(t))
+        .filter(|t| !t.is_empty())
+        .collect();
+    if normalized.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cursor = collections
+        .tags
+        .find(doc! { "aliases": { "$in": &normalized } })
+        .await?;
+    let known: Vec<Tag> = cursor.try_collect().await?;
+    let aliases: HashMap<String, String> = known
+        .into_iter()
+        .flat_map(|t| {
+            let id = t.id;
+            t.aliases.into_iter().map(move |a| (a, id.clone()))
+        })
+        .collect();
+
+    let mut canonical: Vec<String> = Vec::new();
+    for tag in normalized {
+        let tag = aliases.get(&tag).cloned().unwrap_or(tag);
+        if !canonical.contains(&tag) {
+            canonical.push(tag);
+        }
+    }
+    Ok(canonical)
+}
+
+/// Normalized, de-duplicated aliases without the slug itself
+fn normalize_aliases(id: &str, aliases: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for alias in aliases.iter().map(|a| normalize_tag(a)) {
+        if !alias.is_empty() && alias != id && !normalized.contains(&alias) {
+            normalized.push(alias);
+        }
+    }
+    normalized
+}
+
+/// Reject slugs that another tag already uses as its ID or as an alias
+async fn check_unused(
+    collections: &Collections,
+    slugs: &[String],
+    exclude: Option<&str>,
+) -> Result<(), AppError> {
+    if slugs.is_empty() {
+        return Ok(());
+    }
+
+    let mut filter = doc! {
+        "$or": [
+            { "_id": { "$in": slugs } },
+            { "aliases": { "$in": slugs } }
+        ]
+    };
+    if let Some(id) = exclude {
+        filter.insert("_id", doc! { "$ne": id });
+    }
+
+    if let Some(existing) = collections.tags.find_one(filter).await? {
+        return Err(AppError::Conflict(format!(
+            "Tag or alias is already used by tag '{}'",
+            existing.id
+        )));
+    }
+    Ok(())
+}
+
+/// Escape regex metacharacters so user text matches literally
+pub fn escape_regex(word: &str) -> String {
+    let mut escaped = String::with_capacity(word.len());
+    for c in word.chars() {
+        if "\\.^$|?*+()[]{}/".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Rewrite tags on existing programs that spell this tag or one of its aliases
+/// in any case or separator style to the canonical slug
+async fn remap_program_tags(collections: &Collections, tag: &Tag) -> Result<(), AppError> {
+    let spellings: Vec<String> = std::iter::once(&tag.id)
+        .chain(&tag.aliases)
+        .map(|slug| {
+            slug.split('-')
+                .map(escape_regex)
+                .collect::<Vec<_>>()
+                .join(r"[\s_-]+")
+        })
+        .collect();
+    let pattern = format!("^(?:{})$", spellings.join("|"));
+
+    let filter = doc! {
+        "tags": { "$elemMatch": { "$regex": &pattern, "$options": "i", "$ne": &tag.id } }
+    };
+    let update: Vec<Document> = vec![doc! {
+        "$set": {
+            "tags": {
+                "$concatArrays": [
+                    {
+                        "$filter": {
+                            "input": "$tags",
+                            "cond": {
+                                "$not": [{
+                                    "$regexMatch": {
+                                        "input": "$$this",
+                                        "regex": &pattern,
+                                        "options": "i"
+                                    }
+                                }]
+                            }
+                        }
+                    },
+                    [&tag.id]
+                ]
+            }
+        }
+    }];
+
+    collections.programs.update_many(filter, update).await?;
+    Ok(())
+}
+
+pub async fn create_tag(
+    collections: &Collections,
+    req: CreateTagRequest,
+) -> Result<TagResponse, AppError> {
+    let name = req.name.trim().to_string();
+    let id = normalize_tag(req.id.as_deref().unwrap_or(&name));
+    if name.is_empty() || id.is_empty() {
+        return Err(AppError::BadRequest("Tag name is required".to_string()));
+    }
+    let aliases = normalize_aliases(&id, req.aliases.unwrap_or_default());
+
+    let mut slugs = aliases.clone();
+    slugs.push(id.clone());
+    check_unused(collections, &slugs, None).await?;
+
+    let tag = Tag { id, name, aliases };
+    collections.tags.insert_one(&tag).await?;
+    remap_program_tags(collections, &tag).await?;
+
+    Ok(tag.into())
+}
+
+pub async fn get_tags(collections: &Collections) -> Result<Vec<TagResponse>, AppError> {
+    let cursor = collections
+        .tags
+        .find(doc! {})
+        .sort(doc! { "name": 1 })
+        .await?;
+    let tags: Vec<Tag> = cursor.try_collect().await?;
+    Ok(tags.into_iter().map(|t| t.into()).collect())
+}
+
+pub async fn get_tag(collections: &Collections, tag_id: &str) -> Result<TagResponse, AppError> {
+    let tag = collections
+        .tags
+        .find_one(doc! { "_id": tag_id })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))?;
+
+    Ok(tag.into())
+}
+
+/// Rename a tag or replace its aliases; the slug stays stable
+pub async fn update_tag(
+    collections: &Collections,
+    tag_id: &str,
+    req: UpdateTagRequest,
+) -> Result<TagResponse, AppError> {
+    let tag = collections
+        .tags
+        .find_one(doc! { "_id": tag_id })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))?;
+
+    let mut update_doc = doc! {};
+
+    if let Some(name) = req.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("Tag name is required".to_string()));
+        }
+        update_doc.insert("name", name);
+    }
+    if let Some(aliases) = req.aliases {
+        let aliases = normalize_aliases(&tag.id, aliases);
+        check_unused(collections, &aliases, Some(&tag.id)).await?;
+        update_doc.insert("aliases", aliases);
+    }
+
+    if !update_doc.is_empty() {
+        collections
+            .tags
+            .update_one(doc! { "_id": tag_id }, doc! { "$set": update_doc })
+            .await?;
+    }
+
+    let tag = collections
+        .tags
+        .find_one(doc! { "_id": tag_id })
+        .await?
+        .ok_or_else(|| AppError::NotFound("Tag not found".to_string()))?;
+    remap_program_tags(collections, &tag).await?;
+
+    Ok(tag.into())
+}
+
+/// Remove a tag from the vocabulary; programs keep the slug as a free tag
+pub async fn delete_tag(collections: &Collections, tag_id: &str) -> Result<(), AppError> {
+    let result = collections
+        .tags
+        .delete_one(doc! { "_id": tag_id })
+        .await?;
+
+    if result.deleted_count == 0 {
+        return Err(AppError::NotFound("Tag not found".to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_case_and_separators() {
+        assert_eq!(normalize_tag("Power  Lifting"), "power-lifting");
+        assert_eq!(normalize_tag("power_lifting"), "power-lifting");
+        assert_eq!(normalize_tag(" -Power--Lifting_ "), "power-lifting");
+        assert_eq!(normalize_tag("HIIT"), "hiit");
+    }
+
+    #[test]
+    fn keeps_non_ascii_letters_and_digits() {
+        assert_eq!(normalize_tag("Über 5x5"), "über-5x5");
+    }
+
+    #[test]
+    fn separators_only_normalize_to_empty() {
+        assert_eq!(normalize_tag(" _-\t"), "");
+    }
+
+    #[test]
+    fn aliases_drop_slug_duplicates_and_blanks() {
+        let aliases = vec![
+            "Power Lifting".to_string(),
+            "powerlifting".to_string(),
+            "power_lifting".to_string(),
+            "  ".to_string(),
+            "PL".to_string(),
+        ];
+        assert_eq!(normalize_aliases("powerlifting", aliases), ["power-lifting", "pl"]);
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet};
+
+use bson::doc;
+use futures::TryStreamExt;
+use mongodb::ClientSession;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{Set, ValidationError, WorkoutExercise};
+
+/// Catalog exercise IDs that exist among the ones referenced by `exercises`
+pub async fn known_exercise_ids(
+    collections: &Collections,
+    session: &mut ClientSession,
+    exercises: &[WorkoutExercise],
+) -> Result<HashSet<String>, AppError> {
+    let referenced: HashSet<&str> = exercises.iter().map(|e| e.exercise_id.as_str()).collect();
+    if referenced.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    // Only the IDs are needed; a projected find can't deserialize into Exercise
+    let known = collections
+        .exercises
+        .distinct(
+            "_id",
+            doc! { "_id": { "$in": referenced.into_iter().collect::<Vec<_>>() } },
+        )
+        .session(session)
+        .await?;
+
+    Ok(known
+        .into_iter()
+        .filter_map(|id| id.as_str().map(str::to_string))
+        .collect())
+}
+
+/// Load and validate a program's full content before it is published
+pub async fn validate_for_publish(
+    collections: &Collections,
+    session: &mut ClientSession,
+    program_id: &str,
+    total_weeks: i32,
+) -> Result<(), AppError> {
+    let mut cursor = collections
+        .workout_exercises
+        .find(doc! { "program_id": program_id })
+        .session(&mut *session)
+        .await?;
+    let exercises: Vec<WorkoutExercise> = cursor.stream(&mut *session).try_collect().await?;
+    let known = known_exercise_ids(collections, session, &exercises).await?;
+
+    let errors = validate_exercises(total_weeks, &exercises, &known);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(errors))
+    }
+}
+
+/// Structural checks on a program's workout exercises
+pub fn validate_exercises(
+    total_weeks: i32,
+    exercises: &[WorkoutExercise],
+    known_exercise_ids: &HashSet<String>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut workout_weeks: HashMap<i32, i32> = HashMap::new();
+    let mut orders: HashSet<(i32, i32)> = HashSet::new();
+
+    for exercise in exercises {
+        let id = Some(exercise.id.to_hex());
+
+        if exercise.week < 1 || exercise.week > total_weeks {
+            errors.push(ValidationError::new(
+                id.clone(),
+                "week",
+                "week_out_of_range",
+                format!("Week must be between 1 and {}", total_weeks),
+            ));
+        }
+
+        if exercise.workout_number < 1 {
+            errors.push(ValidationError::new(
+                id.clone(),
+                "workout_number",
+                "invalid_workout_number",
+                "Workout number must be at least 1",
+            ));
+        } else if let Some(week) = workout_weeks.get(&exercise.workout_number) {
+            if *week != exercise.week {
+                errors.push(ValidationError::new(
+                    id.clone(),
+                    "week",
+                    "workout_week_mismatch",
+                    format!(
+                        "Workout {} is already in week {}",
+                        exercise.workout_number, week
+                    ),
+                ));
+            }
+        } else {
+            workout_weeks.insert(exercise.workout_number, exercise.week);
+        }
+
+        if exercise.order < 0 {
+            errors.push(ValidationError::new(
+                id.clone(),
+                "order",
+                "invalid_order",
+                "Order must not be negative",
+            ));
+        } else if !orders.insert((exercise.workout_number, exercise.order)) {
+            errors.push(ValidationError::new(
+                id.clone(),
+                "order",
+                "duplicate_order",
+                format!(
+                    "Order {} is used twice in workout {}",
+                    exercise.order, exercise.workout_number
+                ),
+            ));
+        }
+
+        if !known_exercise_ids.contains(&exercise.exercise_id) {
+            errors.push(ValidationError::new(
+                id.clone(),
+                "exercise_id",
+                "unknown_exercise",
+                format!("Exercise {} is not in the catalog", exercise.exercise_id),
+            ));
+        }
+
+        for (index, set) in exercise.sets.iter().enumerate() {
+            validate_set(&id, index, set, &mut errors);
+        }
+    }
+
+    errors
+}
+
+fn validate_set(id: &Option<String>, index: usize, set: &Set, errors: &mut Vec<ValidationError>) {
+    let field = |name: &str| format!("sets[{}].{}", index, name);
+
+    if set.number != index as i32 + 1 {
+        errors.push(ValidationError::new(
+            id.clone(),
+            field("number"),
+            "set_number_sequence",
+            format!("Set number should be {}", index + 1),
+        ));
+    }
+
+    let values = [
+        ("volume_lower", set.volume_lower),
+        ("volume_upper", set.volume_upper),
+        ("intensity_lower", set.intensity_lower),
+        ("intensity_upper", set.intensity_upper),
+    ];
+    for (name, value) in values {
+        if let Some(v) = value {
+            if !v.is_finite() || v < 0.0 {
+                errors.push(ValidationError::new(
+                    id.clone(),
+                    field(name),
+                    "invalid_value",
+                    "Value must be a non-negative number",
+                ));
+            }
+        }
+    }
+
+    if let (Some(lower), Some(upper)) = (set.volume_lower, set.volume_upper) {
+        if lower > upper {
+            errors.push(ValidationError::new(
+                id.clone(),
+                field("volume_lower"),
+                "inverted_range",
+                "volume_lower must not exceed volume_upper",
+            ));
+        }
+    }
+    if let (Some(lower), Some(upper)) = (set.intensity_lower, set.intensity_upper) {
+        if lower > upper {
+            errors.push(ValidationError::new(
+                id.clone(),
+                field("intensity_lower"),
+                "inverted_range",
+                "intensity_lower must not exceed intensity_upper",
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::oid::ObjectId;
+
+    use super::*;
+
+    fn set(number: i32, volume: (f64, f64)) -> Set {
+        Set {
+            number,
+            volume_lower: Some(volume.0),
+            volume_upper: Some(volume.1),
+            intensity_lower: None,
+            intensity_upper: None,
+        }
+    }
+
+    fn exercise(week: i32, workout_number: i32, order: i32) -> WorkoutExercise {
+        WorkoutExercise {
+            id: ObjectId::new(),
+            program_id: "program".to_string(),
+            week,
+            workout_number,
+            order,
+            exercise_id: "squat".to_string(),
+            volume_metric: None,
+            intensity_metric: None,
+            notes: None,
+            sets: vec![set(1, (5.0, 8.0)), set(2, (5.0, 8.0))],
+        }
+    }
+
+    fn known() -> HashSet<String> {
+        HashSet::from(["squat".to_string()])
+    }
+
+    fn codes(errors: &[ValidationError]) -> Vec<(&str, &str)> {
+        errors
+            .iter()
+            .map(|e| (e.field.as_str(), e.code.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_program() {
+        let exercises = [exercise(1, 1, 0), exercise(1, 1, 1), exercise(2, 2, 0)];
+        assert!(validate_exercises(2, &exercises, &known()).is_empty());
+    }
+
+    #[test]
+    fn rejects_weeks_outside_program() {
+        let exercises = [exercise(0, 1, 0), exercise(3, 2, 0)];
+        let errors = validate_exercises(2, &exercises, &known());
+        assert_eq!(
+            codes(&errors),
+            [("week", "week_out_of_range"), ("week", "week_out_of_range")]
+        );
+    }
+
+    #[test]
+    fn rejects_workout_spanning_weeks() {
+        let exercises = [exercise(1, 1, 0), exercise(2, 1, 1)];
+        let errors = validate_exercises(2, &exercises, &known());
+        assert_eq!(codes(&errors), [("week", "workout_week_mismatch")]);
+        assert_eq!(errors[0].id, Some(exercises[1].id.to_hex()));
+    }
+
+    #[test]
+    fn rejects_invalid_workout_numbers_and_orders() {
+        let exercises = [
+            exercise(1, 0, 0),
+            exercise(1, 1, -1),
+            exercise(1, 2, 0),
+            exercise(1, 2, 0),
+        ];
+        let errors = validate_exercises(1, &exercises, &known());
+        assert_eq!(
+            codes(&errors),
+            [
+                ("workout_number", "invalid_workout_number"),
+                ("order", "invalid_order"),
+                ("order", "duplicate_order"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_exercises() {
+        let errors = validate_exercises(1, &[exercise(1, 1, 0)], &HashSet::new());
+        assert_eq!(codes(&errors), [("exercise_id", "unknown_exercise")]);
+    }
+
+    #[test]
+    fn reports_set_problems_with_their_index() {
+        let mut bad = exercise(1, 1, 0);
+        bad.sets = vec![set(1, (8.0, 5.0)), set(3, (-1.0, f64::NAN))];
+        let errors = validate_exercises(1, &[bad], &known());
+        assert_eq!(
+            codes(&errors),
+            [
+                ("sets[0].volume_lower", "inverted_range"),
+                ("sets[1].number", "set_number_sequence"),
+                ("sets[1].volume_lower", "invalid_value"),
+                ("sets[1].volume_upper", "invalid_value"),
+            ]
+        );
+    }
+}
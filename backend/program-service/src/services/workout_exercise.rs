@@ -1,41 +1,93 @@
+use std::collections::HashSet;
+
 use bson::{doc, oid::ObjectId};
 use futures::TryStreamExt;
 
+use crate::config::MAX_TRANSACTION_ATTEMPTS;
 use crate::db::Collections;
 use crate::error::AppError;
 use crate::models::{
-    DeleteExercisesRequest, DeleteWorkoutsRequest, IdMapping, NextWorkoutResponse,
-    UpsertExercisesRequest, UpsertExercisesResponse, WeekResponse, WorkoutExercise,
-    WorkoutExerciseResponse, WorkoutGroup,
+    ChangeDetail, DeleteExercisesRequest, DeleteWorkoutsRequest, IdMapping, NextWorkoutResponse,
+    Program, ProgramVersion, UpsertExercisesRequest, UpsertExercisesResponse, ValidationError,
+    WeekResponse, WorkoutExercise,
+};
+use crate::services::entitlement::ensure_access;
+use crate::services::program_layout::week_response;
+use crate::services::program_version::{
+    get_program_version, next_workout_in_version, week_in_version,
 };
+use crate::services::revision::{bump_revision, check_revision};
+use crate::services::started_program_client::StartedProgramClient;
+use crate::services::validation::{known_exercise_ids, validate_exercises};
 
 /// Check if an ID is a temporary ID (starts with "temp-")
 fn is_temp_id(id: &str) -> bool {
     id.starts_with("temp-")
 }
 
-pub async fn get_week(
+/// Where a caller reads a program's workouts from
+enum WorkoutSource {
+    Live(Program),
+    Version(ProgramVersion),
+}
+
+/// Owners and readers of a published public program get the live workouts or the requested
+/// version. Anyone else, including everyone once the program is deleted, only gets the
+/// version their started program is pinned to; without one the program is not found
+/// (reported to the tracker as gone)
+async fn workout_source(
     collections: &Collections,
+    started_programs: &StartedProgramClient<'_>,
     user_id: &str,
     program_id: &str,
-    week: i32,
-) -> Result<WeekResponse, AppError> {
+    version: Option<i32>,
+) -> Result<WorkoutSource, AppError> {
     let program_oid = ObjectId::parse_str(program_id)
         .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
 
-    // Verify program access - user owns it OR it's a published public program
     let program = collections
         .programs
-        .find_one(doc! {
-            "_id": program_oid,
-            "$or": [
-                { "user_id": user_id },
-                { "public": true, "created_at": { "$ne": null } }
-            ]
-        })
+        .find_one(doc! { "_id": program_oid })
         .await?
         .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
 
+    let readable = program.deleted_at.is_none()
+        && (program.user_id == user_id || (program.public && program.created_at.is_some()));
+
+    let version = if readable {
+        version
+    } else {
+        match started_programs.pinned_version(user_id, program_id).await? {
+            Some(pinned) if version.is_none_or(|v| v == pinned) => Some(pinned),
+            _ => return Err(AppError::NotFound("Program not found".to_string())),
+        }
+    };
+
+    // Paid programs show their workouts only to the owner and buyers
+    ensure_access(collections, user_id, &program).await?;
+
+    match version {
+        Some(version) => Ok(WorkoutSource::Version(
+            get_program_version(collections, program_id, version).await?,
+        )),
+        None => Ok(WorkoutSource::Live(program)),
+    }
+}
+
+pub async fn get_week(
+    collections: &Collections,
+    started_programs: &StartedProgramClient<'_>,
+    user_id: &str,
+    program_id: &str,
+    week: i32,
+    version: Option<i32>,
+) -> Result<WeekResponse, AppError> {
+    let source = workout_source(collections, started_programs, user_id, program_id, version).await?;
+    let program = match source {
+        WorkoutSource::Live(program) => program,
+        WorkoutSource::Version(snapshot) => return Ok(week_in_version(&snapshot, week)),
+    };
+
     // Get all exercises for this week (program_id stored as string in exercises)
     let cursor = collections
         .workout_exercises
@@ -45,80 +97,41 @@ pub async fn get_week(
 
     let exercises: Vec<WorkoutExercise> = cursor.try_collect().await?;
 
-    // Group by workout_number and convert to response types
-    let mut workouts: Vec<WorkoutGroup> = Vec::new();
-    let mut current_workout_number: Option<i32> = None;
-
-    for exercise in exercises {
-        let exercise_response: WorkoutExerciseResponse = exercise.clone().into();
-        if current_workout_number != Some(exercise.workout_number) {
-            workouts.push(WorkoutGroup {
-                workout_number: exercise.workout_number,
-                exercises: vec![exercise_response],
-            });
-            current_workout_number = Some(exercise.workout_number);
-        } else {
-            workouts.last_mut().unwrap().exercises.push(exercise_response);
-        }
-    }
-
-    Ok(WeekResponse {
-        program_id: program_id.to_string(),
-        week,
-        total_weeks: program.total_weeks,
-        workouts,
-    })
+    Ok(week_response(program_id, program.total_weeks, week, exercises))
 }
 
+/// Upsert exercises in one transaction: every row is validated before anything is written,
+/// and existing rows are replaced in place so overlapping autosaves can't duplicate them
 pub async fn upsert_exercises(
     collections: &Collections,
     user_id: &str,
     program_id: &str,
     req: UpsertExercisesRequest,
+    expected_revision: Option<i64>,
 ) -> Result<UpsertExercisesResponse, AppError> {
     let program_oid = ObjectId::parse_str(program_id)
         .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
 
-    // Verify program ownership
-    let program = collections
-        .programs
-        .find_one(doc! { "_id": program_oid, "user_id": user_id })
-        .await?
-        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
-
-    // Find max workout_number in request
-    let max_workout_number = req
-        .exercises
-        .iter()
-        .map(|e| e.workout_number)
-        .max()
-        .unwrap_or(0);
-
     // Track ID mappings for temp IDs
     let mut id_mappings: Vec<IdMapping> = Vec::new();
-
-    // Separate exercises into new (temp IDs) and existing (real IDs)
-    let mut new_exercises: Vec<WorkoutExercise> = Vec::new();
-    let mut existing_exercises: Vec<WorkoutExercise> = Vec::new();
-    let mut existing_ids: Vec<ObjectId> = Vec::new();
+    let mut exercises: Vec<WorkoutExercise> = Vec::new();
 
     for exercise_input in req.exercises {
         // Check if this is a temp ID and generate a real ObjectId if so
-        let (real_id, is_new) = if is_temp_id(&exercise_input.id) {
+        let real_id = if is_temp_id(&exercise_input.id) {
             let new_id = ObjectId::new();
             id_mappings.push(IdMapping {
                 temp_id: exercise_input.id.clone(),
                 real_id: new_id.to_hex(),
             });
-            (new_id, true)
+            new_id
         } else {
             // Parse existing hex string to ObjectId
-            let oid = ObjectId::parse_str(&exercise_input.id)
-                .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
-            (oid, false)
+            ObjectId::parse_str(&exercise_input.id)
+                .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?
         };
 
-        let exercise = WorkoutExercise {
+        exercises.push(WorkoutExercise {
             id: real_id,
             program_id: program_id.to_string(),
             week: exercise_input.week,
@@ -129,48 +142,142 @@ pub async fn upsert_exercises(
             sets: exercise_input.sets,
             volume_metric: exercise_input.volume_metric,
             intensity_metric: exercise_input.intensity_metric,
-        };
+        });
+    }
 
-        if is_new {
-            new_exercises.push(exercise);
-        } else {
-            existing_ids.push(real_id);
-            existing_exercises.push(exercise);
+    // Overlapping saves abort each other's transactions; retry before reporting a conflict
+    let mut attempt = 1;
+    loop {
+        let result = save_exercises(
+            collections,
+            user_id,
+            program_oid,
+            program_id,
+            &exercises,
+            &id_mappings,
+            expected_revision,
+        )
+        .await;
+        match result {
+            Err(AppError::TransactionConflict(_)) if attempt < MAX_TRANSACTION_ATTEMPTS => {
+                attempt += 1;
+            }
+            result => {
+                return result.map(|revision| UpsertExercisesResponse {
+                    id_mappings,
+                    revision,
+                })
+            }
         }
     }
+}
 
-    // Insert all new exercises in one batch
-    if !new_exercises.is_empty() {
-        collections
-            .workout_exercises
-            .insert_many(&new_exercises)
-            .await?;
+/// Validate and write upserted exercises in one transaction, returning the new revision
+async fn save_exercises(
+    collections: &Collections,
+    user_id: &str,
+    program_oid: ObjectId,
+    program_id: &str,
+    exercises: &[WorkoutExercise],
+    id_mappings: &[IdMapping],
+    expected_revision: Option<i64>,
+) -> Result<i64, AppError> {
+    // Find max workout_number in request
+    let max_workout_number = exercises
+        .iter()
+        .map(|e| e.workout_number)
+        .max()
+        .unwrap_or(0);
+
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    // Verify program ownership
+    let program = collections
+        .programs
+        .find_one(doc! { "_id": program_oid, "user_id": user_id, "deleted_at": null })
+        .session(&mut session)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
+    // Existing IDs must belong to this program; upserting them would otherwise collide
+    let ids: Vec<ObjectId> = exercises.iter().map(|e| e.id).collect();
+    let foreign = collections
+        .workout_exercises
+        .count_documents(doc! {
+            "_id": { "$in": &ids },
+            "program_id": { "$ne": program_id }
+        })
+        .session(&mut session)
+        .await?;
+    if foreign > 0 {
+        return Err(AppError::BadRequest(
+            "Exercise IDs belong to another program".to_string(),
+        ));
     }
 
-    // For existing exercises: delete then insert (more efficient than individual replaces)
-    if !existing_exercises.is_empty() {
-        collections
-            .workout_exercises
-            .delete_many(doc! { "_id": { "$in": &existing_ids } })
-            .await?;
-        collections
-            .workout_exercises
-            .insert_many(&existing_exercises)
-            .await?;
+    // Validate the program as it will look after this save, reporting only rows in the request
+    let mut cursor = collections
+        .workout_exercises
+        .find(doc! { "program_id": program_id, "_id": { "$nin": &ids } })
+        .session(&mut session)
+        .await?;
+    let mut merged: Vec<WorkoutExercise> = cursor.stream(&mut session).try_collect().await?;
+    merged.extend(exercises.iter().cloned());
+    let known = known_exercise_ids(collections, &mut session, &merged).await?;
+
+    let request_ids: HashSet<String> = ids.iter().map(|id| id.to_hex()).collect();
+    let errors: Vec<ValidationError> = validate_exercises(program.total_weeks, &merged, &known)
+        .into_iter()
+        .filter(|e| e.id.as_ref().is_some_and(|id| request_ids.contains(id)))
+        .map(|e| ValidationError {
+            // Report new rows under the temp ID the client knows them by
+            id: e.id.map(|id| {
+                id_mappings
+                    .iter()
+                    .find(|m| m.real_id == id)
+                    .map_or(id, |m| m.temp_id.clone())
+            }),
+            ..e
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(AppError::Validation(errors));
     }
 
-    // Update last_workout_number if needed
-    if max_workout_number > program.last_workout_number {
+    let revision = bump_revision(
+        collections,
+        &mut session,
+        program_oid,
+        expected_revision,
+        ChangeDetail::ExercisesUpserted {
+            exercise_ids: ids.iter().map(|id| id.to_hex()).collect(),
+        },
+    )
+    .await?;
+
+    for exercise in exercises {
         collections
-            .programs
-            .update_one(
-                doc! { "_id": program_oid },
-                doc! { "$set": { "last_workout_number": max_workout_number } },
-            )
+            .workout_exercises
+            .replace_one(doc! { "_id": exercise.id }, exercise)
+            .upsert(true)
+            .session(&mut session)
             .await?;
     }
 
-    Ok(UpsertExercisesResponse { id_mappings })
+    // $max keeps last_workout_number monotonic under concurrent saves
+    collections
+        .programs
+        .update_one(
+            doc! { "_id": program_oid },
+            doc! { "$max": { "last_workout_number": max_workout_number } },
+        )
+        .session(&mut session)
+        .await?;
+
+    session.commit_transaction().await?;
+
+    Ok(revision)
 }
 
 pub async fn delete_workouts(
@@ -178,17 +285,33 @@ pub async fn delete_workouts(
     user_id: &str,
     program_id: &str,
     req: DeleteWorkoutsRequest,
-) -> Result<(), AppError> {
+    expected_revision: Option<i64>,
+) -> Result<i64, AppError> {
     let program_oid = ObjectId::parse_str(program_id)
         .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
 
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
     // Verify program ownership
     collections
         .programs
-        .find_one(doc! { "_id": program_oid, "user_id": user_id })
+        .find_one(doc! { "_id": program_oid, "user_id": user_id, "deleted_at": null })
+        .session(&mut session)
         .await?
         .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
 
+    let revision = bump_revision(
+        collections,
+        &mut session,
+        program_oid,
+        expected_revision,
+        ChangeDetail::WorkoutsDeleted {
+            workout_numbers: req.workout_numbers.clone(),
+        },
+    )
+    .await?;
+
     // Delete all exercises with matching workout_numbers
     collections
         .workout_exercises
@@ -196,9 +319,12 @@ pub async fn delete_workouts(
             "program_id": program_id,
             "workout_number": { "$in": &req.workout_numbers }
         })
+        .session(&mut session)
         .await?;
 
-    Ok(())
+    session.commit_transaction().await?;
+
+    Ok(revision)
 }
 
 pub async fn delete_exercises(
@@ -206,17 +332,11 @@ pub async fn delete_exercises(
     user_id: &str,
     program_id: &str,
     req: DeleteExercisesRequest,
-) -> Result<(), AppError> {
+    expected_revision: Option<i64>,
+) -> Result<i64, AppError> {
     let program_oid = ObjectId::parse_str(program_id)
         .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
 
-    // Verify program ownership
-    collections
-        .programs
-        .find_one(doc! { "_id": program_oid, "user_id": user_id })
-        .await?
-        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
-
     // Parse string IDs to ObjectIds
     let object_ids: Vec<ObjectId> = req
         .ids
@@ -224,10 +344,34 @@ pub async fn delete_exercises(
         .filter_map(|id| ObjectId::parse_str(id).ok())
         .collect();
 
+    let mut session = collections.client.start_session().await?;
+    session.start_transaction().await?;
+
+    // Verify program ownership
+    let program = collections
+        .programs
+        .find_one(doc! { "_id": program_oid, "user_id": user_id, "deleted_at": null })
+        .session(&mut session)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+
     if object_ids.is_empty() {
-        return Ok(());
+        check_revision(collections, &mut session, program_oid, program.revision, expected_revision)
+            .await?;
+        return Ok(program.revision);
     }
 
+    let revision = bump_revision(
+        collections,
+        &mut session,
+        program_oid,
+        expected_revision,
+        ChangeDetail::ExercisesDeleted {
+            exercise_ids: object_ids.iter().map(|id| id.to_hex()).collect(),
+        },
+    )
+    .await?;
+
     // Delete exercises by IDs
     collections
         .workout_exercises
@@ -235,34 +379,30 @@ pub async fn delete_exercises(
             "_id": { "$in": object_ids },
             "program_id": program_id
         })
+        .session(&mut session)
         .await?;
 
-    Ok(())
+    session.commit_transaction().await?;
+
+    Ok(revision)
 }
 
-/// Get the next workout after a given workout_number
+/// Get the next workout after a given workout_number, from the live program or a published version
 /// Returns None if there are no more workouts
 pub async fn get_next_workout(
     collections: &Collections,
+    started_programs: &StartedProgramClient<'_>,
     user_id: &str,
     program_id: &str,
     last_workout_number: i32,
+    version: Option<i32>,
 ) -> Result<Option<NextWorkoutResponse>, AppError> {
-    let program_oid = ObjectId::parse_str(program_id)
-        .map_err(|e| AppError::BadRequest(format!("Invalid ObjectId: {}", e)))?;
-
-    // Verify program exists and user has access (owner or public program)
-    collections
-        .programs
-        .find_one(doc! {
-            "_id": program_oid,
-            "$or": [
-                { "user_id": user_id },
-                { "public": true, "created_at": { "$ne": null } }
-            ]
-        })
-        .await?
-        .ok_or_else(|| AppError::NotFound("Program not found".to_string()))?;
+    // Started programs pinned to a version read from that snapshot, not the live program
+    if let WorkoutSource::Version(snapshot) =
+        workout_source(collections, started_programs, user_id, program_id, version).await?
+    {
+        return Ok(next_workout_in_version(&snapshot, last_workout_number));
+    }
 
     // Find the next workout (first workout with workout_number > last_workout_number)
     let next_exercise = collections
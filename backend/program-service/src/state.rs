@@ -1,10 +1,15 @@
-use aws_sdk_s3::Client as S3Client;
+use reqwest::Client as HttpClient;
+use std::sync::Arc;
 
 use crate::db::Collections;
+use crate::services::payment::PaymentProvider;
+use crate::services::storage::ObjectStorage;
 
 #[derive(Clone)]
 pub struct AppState {
     pub collections: Collections,
-    pub s3_client: S3Client,
-    pub s3_bucket: String,
+    pub storage: Arc<dyn ObjectStorage>,
+    pub http_client: HttpClient,
+    pub started_program_service_url: String,
+    pub payment_provider: Arc<dyn PaymentProvider>,
 }
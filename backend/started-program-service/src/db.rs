@@ -1,11 +1,13 @@
 use mongodb::{Collection, Database};
 
-use crate::models::{StartedProgram, StartedWorkoutExercise};
+use crate::models::{CatalogExercise, StartedProgram, StartedWorkoutExercise};
 
 #[derive(Clone)]
 pub struct Collections {
     pub started_programs: Collection<StartedProgram>,
     pub started_workout_exercises: Collection<StartedWorkoutExercise>,
+    /// Read model of program-service's exercise catalog
+    pub exercise_catalog: Collection<CatalogExercise>,
 }
 
 impl Collections {
@@ -13,6 +15,7 @@ impl Collections {
         Self {
             started_programs: db.collection("started_programs"),
             started_workout_exercises: db.collection("started_workout_exercises"),
+            exercise_catalog: db.collection("exercise_catalog"),
         }
     }
 }
@@ -16,6 +16,8 @@ pub enum AppError {
     InternalServerError(String),
     BadRequest(String),
     NotFound(String),
+    Gone(String),
+    PaymentRequired(String),
 }
 
 impl IntoResponse for AppError {
@@ -25,6 +27,8 @@ impl IntoResponse for AppError {
             AppError::InternalServerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            AppError::Gone(msg) => (StatusCode::GONE, msg),
+            AppError::PaymentRequired(msg) => (StatusCode::PAYMENT_REQUIRED, msg),
         };
 
         let body = Json(ErrorResponse {
@@ -7,11 +7,12 @@ use chrono::Utc;
 
 use crate::error::AppError;
 use crate::models::{
-    CurrentWorkoutResponse, ExerciseHistoryResponse, StartProgramRequest, StartedProgramResponse,
-    StartedWorkoutExerciseResponse, UpdateExerciseProgressRequest, WorkoutHistoryDetailResponse,
-    WorkoutHistoryResponse,
+    CurrentWorkoutResponse, ExerciseHistoryResponse, ExerciseUsageResponse, ProgramUsageResponse,
+    StartProgramRequest, StartedProgramResponse, StartedWorkoutExerciseResponse,
+    UpdateExerciseProgressRequest, WorkoutHistoryDetailResponse, WorkoutHistoryResponse,
 };
 use crate::services;
+use crate::services::exercise_catalog;
 use crate::services::rabbitmq::AnalyticsMessage;
 use crate::services::workout_client::WorkoutClient;
 use crate::state::AppState;
@@ -155,35 +156,48 @@ pub async fn update_exercise_progress(
     let exercise_catalog_id = response.exercise_id.clone();
     let sets = payload.sets;
     let rabbitmq = state.rabbitmq.clone();
+    let collections = state.collections.clone();
     let http_client = state.http_client.clone();
     let workout_service_url = state.workout_service_url.clone();
     let started_program_id_clone = started_program_id.clone();
     let user_id_clone = user_id.clone();
 
     tokio::spawn(async move {
-        // Get exercise info to retrieve muscles
-        let workout_client = WorkoutClient::new(&http_client, &workout_service_url);
-        if let Ok(exercise_info) = workout_client
-            .get_exercise(&exercise_catalog_id, &user_id_clone)
+        // Muscles come from the local catalog read model, or from workout-service if this
+        // exercise hasn't been synced here yet. Without either, analytics holds the message
+        // until its own read model has the exercise
+        let cached = exercise_catalog::get_muscles(&collections, &exercise_catalog_id)
             .await
-        {
-            let timestamp = Utc::now().to_rfc3339();
-
-            // Publish message for each completed set
-            for set in sets {
-                if set.done_volume.is_some() && set.done_intensity.is_some() {
-                    let message = AnalyticsMessage {
-                        timestamp: timestamp.clone(),
-                        user_id: user_id_clone.clone(),
-                        started_program_id: started_program_id_clone.clone(),
-                        exercise_id: exercise_catalog_id.clone(),
-                        set_number: set.number,
-                        muscles: exercise_info.muscles.clone(),
-                    };
-
-                    if let Err(e) = rabbitmq.publish_analytics(&message).await {
-                        eprintln!("Failed to publish analytics message: {:?}", e);
-                    }
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to read exercise catalog: {:?}", e);
+                None
+            });
+        let muscles = match cached {
+            Some(muscles) => muscles,
+            None => WorkoutClient::new(&http_client, &workout_service_url)
+                .get_exercise_muscles(&exercise_catalog_id, &user_id_clone)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to fetch exercise muscles: {:?}", e);
+                    Vec::new()
+                }),
+        };
+        let timestamp = Utc::now().to_rfc3339();
+
+        // Publish message for each completed set
+        for set in sets {
+            if set.done_volume.is_some() && set.done_intensity.is_some() {
+                let message = AnalyticsMessage {
+                    timestamp: timestamp.clone(),
+                    user_id: user_id_clone.clone(),
+                    started_program_id: started_program_id_clone.clone(),
+                    exercise_id: exercise_catalog_id.clone(),
+                    set_number: set.number,
+                    muscles: muscles.clone(),
+                };
+
+                if let Err(e) = rabbitmq.publish_analytics(&message).await {
+                    eprintln!("Failed to publish analytics message: {:?}", e);
                 }
             }
         }
@@ -238,3 +252,23 @@ pub async fn get_exercise_history(
 
     Ok(Json(response))
 }
+
+/// GET /exercises/:exercise_id/usage - Count references to a catalog exercise
+/// (internal, called by program-service before an exercise is deleted)
+pub async fn get_exercise_usage(
+    State(state): State<AppState>,
+    Path(exercise_id): Path<String>,
+) -> Result<Json<ExerciseUsageResponse>, AppError> {
+    let response = services::get_exercise_usage(&state.collections, &exercise_id).await?;
+    Ok(Json(response))
+}
+
+/// GET /programs/:program_id/usage - Count running athletes following a program's live workouts
+/// (internal, called by program-service before workouts are renumbered)
+pub async fn get_program_usage(
+    State(state): State<AppState>,
+    Path(program_id): Path<String>,
+) -> Result<Json<ProgramUsageResponse>, AppError> {
+    let response = services::get_program_usage(&state.collections, &program_id).await?;
+    Ok(Json(response))
+}
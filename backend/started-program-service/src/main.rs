@@ -19,8 +19,9 @@ use config::AppConfig;
 use db::Collections;
 use handlers::{
     delete_started_program, finish_workout, get_current_workout, get_exercise_history,
-    get_started_program, get_started_programs, get_workout_history, get_workout_history_detail,
-    start_program, start_workout, update_exercise_progress,
+    get_exercise_usage, get_program_usage, get_started_program, get_started_programs,
+    get_workout_history, get_workout_history_detail, start_program, start_workout,
+    update_exercise_progress,
 };
 use services::RabbitMQPublisher;
 use state::AppState;
@@ -55,6 +56,14 @@ async fn main() {
         .await
         .expect("Failed to connect to RabbitMQ");
 
+    // Start RabbitMQ consumer for exercise catalog events
+    services::rabbitmq::start_exercise_events_consumer(
+        &config.rabbitmq_url,
+        collections.clone(),
+    )
+    .await
+    .expect("Failed to start RabbitMQ consumer");
+
     let state = AppState {
         collections,
         http_client,
@@ -110,6 +119,10 @@ async fn main() {
             "/exercises/{exercise_id}/history",
             get(get_exercise_history),
         )
+        // Exercise usage route (for program-service)
+        .route("/exercises/{exercise_id}/usage", get(get_exercise_usage))
+        // Program usage route (for program-service)
+        .route("/programs/{program_id}/usage", get(get_program_usage))
         .layer(cors)
         .with_state(state);
 
@@ -166,5 +179,16 @@ async fn create_indexes(collections: &Collections) {
         .await
         .ok();
 
+    // Index for started_workout_exercises: exercise_id (usage checks and merges)
+    let exercise_usage_index = IndexModel::builder()
+        .keys(doc! { "exercise_id": 1 })
+        .build();
+
+    collections
+        .started_workout_exercises
+        .create_index(exercise_usage_index)
+        .await
+        .ok();
+
     println!("Database indexes created");
 }
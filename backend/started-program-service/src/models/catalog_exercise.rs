@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Muscle intensity from exercise definition
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MuscleIntensity {
+    pub muscle: String,
+    pub intensity: f64,
+}
+
+/// Local read model of a program-service catalog exercise, kept current by exercise events
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CatalogExercise {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    pub muscles: Vec<MuscleIntensity>,
+    /// When the last event for this exercise was applied
+    pub synced_at: DateTime<Utc>,
+}
+
+/// Catalog entry as carried by program-service exercise events (only the fields used here)
+#[derive(Debug, Deserialize)]
+pub struct ExerciseSnapshot {
+    pub id: String,
+    pub name: String,
+    pub muscles: Vec<MuscleIntensity>,
+}
@@ -1,5 +1,7 @@
+mod catalog_exercise;
 mod started_program;
 mod started_workout_exercise;
 
+pub use catalog_exercise::*;
 pub use started_program::*;
 pub use started_workout_exercise::*;
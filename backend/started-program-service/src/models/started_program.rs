@@ -11,6 +11,9 @@ pub struct StartedProgram {
     pub program_id: String,
     pub program_name: String,
     pub program_image_url: Option<String>,
+    /// Published program version this run follows (None = started from a draft, follows live edits)
+    #[serde(default)]
+    pub program_version: Option<i32>,
     /// None means program is finished
     pub current_workout_number: Option<i32>,
     pub workout_started: bool,
@@ -25,6 +28,7 @@ pub struct StartedProgramResponse {
     pub program_id: String,
     pub program_name: String,
     pub program_image_url: Option<String>,
+    pub program_version: Option<i32>,
     /// null means program is finished
     pub current_workout_number: Option<i32>,
     pub workout_started: bool,
@@ -39,6 +43,7 @@ impl From<StartedProgram> for StartedProgramResponse {
             program_id: p.program_id,
             program_name: p.program_name,
             program_image_url: p.program_image_url,
+            program_version: p.program_version,
             current_workout_number: p.current_workout_number,
             workout_started: p.workout_started,
             updated_at: p.updated_at.to_rfc3339(),
@@ -51,3 +56,10 @@ impl From<StartedProgram> for StartedProgramResponse {
 pub struct StartProgramRequest {
     pub program_id: String,
 }
+
+/// Running athletes that follow a program's live workouts instead of a published version
+#[derive(Debug, Serialize)]
+pub struct ProgramUsageResponse {
+    pub program_id: String,
+    pub unpinned_started_programs: u64,
+}
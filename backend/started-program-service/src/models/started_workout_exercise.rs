@@ -125,3 +125,10 @@ pub struct ExerciseHistoryResponse {
     pub exercise_id: String,
     pub history: Vec<StartedWorkoutExerciseResponse>,
 }
+
+/// How often a catalog exercise appears in athletes' workouts (for program-service)
+#[derive(Debug, Serialize)]
+pub struct ExerciseUsageResponse {
+    pub exercise_id: String,
+    pub started_workout_exercises: u64,
+}
@@ -0,0 +1,68 @@
+use bson::doc;
+use chrono::Utc;
+
+use crate::db::Collections;
+use crate::error::AppError;
+use crate::models::{CatalogExercise, ExerciseSnapshot, MuscleIntensity};
+
+/// Store the latest state of a catalog exercise
+pub async fn upsert_exercise(
+    collections: &Collections,
+    snapshot: ExerciseSnapshot,
+) -> Result<(), AppError> {
+    let exercise = CatalogExercise {
+        id: snapshot.id,
+        name: snapshot.name,
+        muscles: snapshot.muscles,
+        synced_at: Utc::now(),
+    };
+
+    collections
+        .exercise_catalog
+        .replace_one(doc! { "_id": &exercise.id }, &exercise)
+        .upsert(true)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_exercise(collections: &Collections, exercise_id: &str) -> Result<(), AppError> {
+    collections
+        .exercise_catalog
+        .delete_one(doc! { "_id": exercise_id })
+        .await?;
+    Ok(())
+}
+
+/// Replace the read model with a full catalog snapshot
+pub async fn replace_catalog(
+    collections: &Collections,
+    exercises: Vec<ExerciseSnapshot>,
+) -> Result<(), AppError> {
+    let ids: Vec<String> = exercises.iter().map(|e| e.id.clone()).collect();
+
+    for snapshot in exercises {
+        upsert_exercise(collections, snapshot).await?;
+    }
+
+    // Exercises missing from the snapshot were deleted while we weren't listening
+    collections
+        .exercise_catalog
+        .delete_many(doc! { "_id": { "$nin": ids } })
+        .await?;
+
+    Ok(())
+}
+
+/// Muscles of a catalog exercise, or None if it hasn't been synced yet
+pub async fn get_muscles(
+    collections: &Collections,
+    exercise_id: &str,
+) -> Result<Option<Vec<MuscleIntensity>>, AppError> {
+    let exercise = collections
+        .exercise_catalog
+        .find_one(doc! { "_id": exercise_id })
+        .await?;
+
+    Ok(exercise.map(|e| e.muscles))
+}
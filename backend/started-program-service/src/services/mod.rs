@@ -1,4 +1,5 @@
 mod started_program;
+pub mod exercise_catalog;
 pub mod rabbitmq;
 pub mod workout_client;
 
@@ -5,9 +5,9 @@ use futures::TryStreamExt;
 use crate::db::Collections;
 use crate::error::AppError;
 use crate::models::{
-    CurrentWorkoutResponse, ExerciseHistoryResponse, StartedProgram, StartedProgramResponse,
-    StartedSet, StartedWorkoutExercise, StartedWorkoutExerciseResponse, WeekHistoryGroup,
-    WorkoutHistoryDetailResponse, WorkoutHistoryItem, WorkoutHistoryResponse,
+    CurrentWorkoutResponse, ExerciseHistoryResponse, ExerciseUsageResponse, ProgramUsageResponse,
+    StartedProgram, StartedProgramResponse, StartedSet, StartedWorkoutExercise, StartedWorkoutExerciseResponse,
+    WeekHistoryGroup, WorkoutHistoryDetailResponse, WorkoutHistoryItem, WorkoutHistoryResponse,
 };
 use crate::services::workout_client::{NextWorkoutResponse, WorkoutClient};
 
@@ -21,6 +21,18 @@ pub async fn start_program(
     // 1. Verify program exists by calling workout-service
     let program = workout_client.get_program(program_id, user_id).await?;
 
+    if program.archived {
+        return Err(AppError::BadRequest(
+            "Program is archived and cannot be started".to_string(),
+        ));
+    }
+
+    if !workout_client.is_entitled(program_id, user_id).await? {
+        return Err(AppError::PaymentRequired(
+            "Purchase this program before starting it".to_string(),
+        ));
+    }
+
     // 2. Check if user already has this program started
     let existing = collections
         .started_programs
@@ -33,9 +45,9 @@ pub async fn start_program(
         ));
     }
 
-    // 3. Get next workout (starting from 0)
+    // 3. Get next workout (starting from 0) from the version the run is pinned to
     let next_workout = workout_client
-        .get_next_workout(program_id, 0, user_id)
+        .get_next_workout(program_id, program.current_version, 0, user_id)
         .await?;
 
     let now = Utc::now();
@@ -46,6 +58,7 @@ pub async fn start_program(
         program_id: program_id.to_string(),
         program_name: program.name,
         program_image_url: program.image_url,
+        program_version: program.current_version,
         current_workout_number: next_workout.as_ref().map(|w| w.workout_number),
         workout_started: false,
         updated_at: now,
@@ -56,6 +69,11 @@ pub async fn start_program(
         .insert_one(&started_program)
         .await?;
 
+    // Popularity counter is best effort and must not fail the start
+    if let Err(e) = workout_client.record_program_start(program_id).await {
+        eprintln!("Failed to record program start: {:?}", e);
+    }
+
     // 5. If next workout exists, create started workout exercises
     if let Some(workout) = next_workout {
         initialize_workout_exercises(collections, user_id, &started_program.id.to_hex(), &workout).await?;
@@ -184,6 +202,16 @@ pub async fn finish_workout(
         return Err(AppError::BadRequest("No workout in progress".to_string()));
     }
 
+    // Find next workout first so a removed program leaves the run untouched
+    let next_workout = workout_client
+        .get_next_workout(
+            &started_program.program_id,
+            started_program.program_version,
+            current_workout_number,
+            user_id,
+        )
+        .await?;
+
     let now = Utc::now();
 
     // Mark current workout exercises as completed (instead of deleting them)
@@ -198,11 +226,6 @@ pub async fn finish_workout(
         )
         .await?;
 
-    // Try to find next workout
-    let next_workout = workout_client
-        .get_next_workout(&started_program.program_id, current_workout_number, user_id)
-        .await?;
-
     let new_workout_number = if let Some(workout) = &next_workout {
         // Initialize new workout exercises
         initialize_workout_exercises(collections, user_id, started_program_id, workout).await?;
@@ -541,3 +564,39 @@ pub async fn get_exercise_history(
         history: exercises.into_iter().map(|e| e.into()).collect(),
     })
 }
+
+/// Count logged exercises of all users that reference a catalog exercise
+pub async fn get_exercise_usage(
+    collections: &Collections,
+    exercise_id: &str,
+) -> Result<ExerciseUsageResponse, AppError> {
+    let count = collections
+        .started_workout_exercises
+        .count_documents(doc! { "exercise_id": exercise_id })
+        .await?;
+
+    Ok(ExerciseUsageResponse {
+        exercise_id: exercise_id.to_string(),
+        started_workout_exercises: count,
+    })
+}
+
+/// Count running started programs of all users that follow a program's live workouts
+pub async fn get_program_usage(
+    collections: &Collections,
+    program_id: &str,
+) -> Result<ProgramUsageResponse, AppError> {
+    let count = collections
+        .started_programs
+        .count_documents(doc! {
+            "program_id": program_id,
+            "program_version": null,
+            "current_workout_number": { "$ne": null }
+        })
+        .await?;
+
+    Ok(ProgramUsageResponse {
+        program_id: program_id.to_string(),
+        unpinned_started_programs: count,
+    })
+}
@@ -2,6 +2,7 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
+use crate::models::MuscleIntensity;
 
 /// Response from workout-service for program details
 #[derive(Debug, Deserialize)]
@@ -14,6 +15,17 @@ pub struct ProgramResponse {
     pub total_weeks: i32,
     pub last_workout_number: i32,
     pub public: bool,
+    /// Latest published version (None = draft)
+    #[serde(default)]
+    pub current_version: Option<i32>,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Response from workout-service entitlement check
+#[derive(Debug, Deserialize)]
+pub struct EntitlementStatusResponse {
+    pub entitled: bool,
 }
 
 /// Set from workout-service
@@ -49,20 +61,10 @@ pub struct NextWorkoutResponse {
     pub exercises: Vec<WorkoutExerciseResponse>,
 }
 
-/// Muscle intensity from exercise definition
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct MuscleIntensity {
-    pub muscle: String,
-    pub intensity: f64,
-}
-
-/// Response from get_exercise endpoint
+/// Catalog exercise from workout-service (only the fields used here)
 #[derive(Debug, Deserialize)]
-pub struct ExerciseResponse {
-    pub id: String,
-    pub name: String,
-    pub demonstration_link: String,
-    pub muscles: Vec<MuscleIntensity>,
+struct ExerciseMuscles {
+    muscles: Vec<MuscleIntensity>,
 }
 
 /// Client for calling workout-service
@@ -105,18 +107,22 @@ impl<'a> WorkoutClient<'a> {
         }
     }
 
-    /// Get next workout from a program
-    /// Returns None if there are no more workouts
+    /// Get next workout from a program, reading from the pinned version when there is one
+    /// Returns None if there are no more workouts, and a Gone error if the program no longer exists
     pub async fn get_next_workout(
         &self,
         program_id: &str,
+        program_version: Option<i32>,
         last_workout_number: i32,
         user_id: &str,
     ) -> Result<Option<NextWorkoutResponse>, AppError> {
-        let url = format!(
+        let mut url = format!(
             "{}/programs/{}/next-workout?last_workout_number={}",
             self.base_url, program_id, last_workout_number
         );
+        if let Some(version) = program_version {
+            url.push_str(&format!("&version={}", version));
+        }
 
         let response = self
             .client
@@ -126,13 +132,42 @@ impl<'a> WorkoutClient<'a> {
             .await?;
 
         if response.status().is_success() {
-            let workout: NextWorkoutResponse = response.json().await.map_err(|e| {
+            // null body means there are no more workouts
+            response.json().await.map_err(|e| {
                 AppError::InternalServerError(format!("Failed to parse workout response: {}", e))
+            })
+        } else if response.status().as_u16() == 404 {
+            Err(AppError::Gone("Program is no longer available".to_string()))
+        } else if response.status().as_u16() == 402 {
+            Err(AppError::PaymentRequired(
+                "Purchase this program to continue".to_string(),
+            ))
+        } else {
+            Err(AppError::InternalServerError(format!(
+                "Workout service error: {}",
+                response.status()
+            )))
+        }
+    }
+
+    /// Whether the user may start the program (free, owned or purchased)
+    pub async fn is_entitled(&self, program_id: &str, user_id: &str) -> Result<bool, AppError> {
+        let url = format!("{}/programs/{}/entitlement", self.base_url, program_id);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("X-User-Id", user_id)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let status: EntitlementStatusResponse = response.json().await.map_err(|e| {
+                AppError::InternalServerError(format!("Failed to parse entitlement response: {}", e))
             })?;
-            Ok(Some(workout))
+            Ok(status.entitled)
         } else if response.status().as_u16() == 404 {
-            // No more workouts
-            Ok(None)
+            Err(AppError::NotFound("Program not found".to_string()))
         } else {
             Err(AppError::InternalServerError(format!(
                 "Workout service error: {}",
@@ -141,12 +176,12 @@ impl<'a> WorkoutClient<'a> {
         }
     }
 
-    /// Get exercise by ID to retrieve muscle information
-    pub async fn get_exercise(
+    /// Muscles of a catalog exercise, read from workout-service directly
+    pub async fn get_exercise_muscles(
         &self,
         exercise_id: &str,
         user_id: &str,
-    ) -> Result<ExerciseResponse, AppError> {
+    ) -> Result<Vec<MuscleIntensity>, AppError> {
         let url = format!("{}/exercises/{}", self.base_url, exercise_id);
 
         let response = self
@@ -157,9 +192,10 @@ impl<'a> WorkoutClient<'a> {
             .await?;
 
         if response.status().is_success() {
-            response.json().await.map_err(|e| {
+            let exercise: ExerciseMuscles = response.json().await.map_err(|e| {
                 AppError::InternalServerError(format!("Failed to parse exercise response: {}", e))
-            })
+            })?;
+            Ok(exercise.muscles)
         } else if response.status().as_u16() == 404 {
             Err(AppError::NotFound("Exercise not found".to_string()))
         } else {
@@ -169,4 +205,20 @@ impl<'a> WorkoutClient<'a> {
             )))
         }
     }
+
+    /// Count a program start so program search can rank by popularity
+    pub async fn record_program_start(&self, program_id: &str) -> Result<(), AppError> {
+        let url = format!("{}/programs/{}/starts", self.base_url, program_id);
+
+        let response = self.client.post(&url).send().await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(AppError::InternalServerError(format!(
+                "Workout service error: {}",
+                response.status()
+            )))
+        }
+    }
 }